@@ -1,4 +1,7 @@
-use clap::{Parser, Subcommand};
+use crate::models::checksum::Checksum;
+use crate::models::node_config::{ByteSize, DownloadConfig, parse_chunk_size};
+use clap::{Args, Parser, Subcommand};
+use std::path::PathBuf;
 
 #[derive(Parser)]
 #[command(name = "tronctl")]
@@ -17,13 +20,24 @@ pub enum Commands {
         #[arg(short, long)]
         snapshot: Option<String>,
 
+        /// 安装指定日期的快照，格式 YYYYMMDD (默认最新)
+        #[arg(long, value_name = "YYYYMMDD")]
+        snapshot_date: Option<String>,
+
         /// FullNode 版本 (默认最新)
         #[arg(short, long)]
         version: Option<String>,
 
+        /// FullNode.jar 的校验值，如 sha256:<hex>，省略算法时按长度推断 (默认使用 Release 发布的 SHA-256)
+        #[arg(long)]
+        checksum: Option<Checksum>,
+
         /// 跳过环境检查
         #[arg(long)]
         skip_checks: bool,
+
+        #[command(flatten)]
+        download: DownloadArgs,
     },
 
     /// 启动 Tron FullNode
@@ -47,6 +61,28 @@ pub enum Commands {
         daemon: bool,
     },
 
+    /// 升级 FullNode.jar，新版本未能恢复健康时自动回滚
+    Upgrade {
+        /// 目标版本 (默认最新)
+        #[arg(short, long)]
+        version: Option<String>,
+
+        /// FullNode.jar 的校验值，如 sha256:<hex>，省略算法时按长度推断 (默认使用 Release 发布的 SHA-256)
+        #[arg(long)]
+        checksum: Option<Checksum>,
+
+        /// 等待新版本恢复健康的超时时间（秒）
+        #[arg(long, default_value_t = crate::constants::UPGRADE_HEALTH_TIMEOUT_SECS)]
+        timeout: u64,
+
+        /// 未指定版本时允许升级到预发布版本
+        #[arg(long)]
+        prerelease: bool,
+
+        #[command(flatten)]
+        download: DownloadArgs,
+    },
+
     /// 查看 Tron FullNode 状态
     Status {
         /// 详细输出
@@ -54,6 +90,24 @@ pub enum Commands {
         verbose: bool,
     },
 
+    /// 审计主机环境、端口与防火墙
+    Doctor {
+        /// 以 JSON 格式输出
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// 查看对等节点与 P2P 网络状态
+    Peers {
+        /// 列出所有已发现节点
+        #[arg(short, long)]
+        verbose: bool,
+
+        /// 仅记录连接数并检查无连接时长，不输出详情（供 systemd 定时器周期调用）
+        #[arg(long)]
+        record: bool,
+    },
+
     /// 查看 Tron FullNode 日志
     Logs {
         /// 跟随日志输出
@@ -78,4 +132,118 @@ pub enum Commands {
         #[arg(short, long)]
         force: bool,
     },
+
+    /// 管理 FullNode 使用的 Java 运行时
+    Java {
+        #[command(subcommand)]
+        action: JavaAction,
+    },
+
+    /// 显示 tronctl 与 FullNode 版本
+    Version {
+        /// 对比 GitHub 最新版本并显示其间的发布说明
+        #[arg(long)]
+        check: bool,
+    },
+
+    /// 管理版本目录中并存的 FullNode.jar
+    Versions {
+        #[command(subcommand)]
+        action: VersionsAction,
+    },
+
+    /// 查看快照服务器上可用的快照
+    Snapshot {
+        #[command(subcommand)]
+        action: SnapshotAction,
+    },
+
+    /// 与可信参考节点比对区块哈希，检查本地链是否分叉
+    VerifyChain {
+        /// 参考节点 HTTP 地址，可多次指定 (默认读取配置文件)
+        #[arg(short, long)]
+        reference: Vec<String>,
+
+        /// 比对的采样高度数量
+        #[arg(short, long, default_value_t = crate::constants::CHAIN_VERIFY_SAMPLE_COUNT)]
+        samples: usize,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum JavaAction {
+    /// 列出本机找到的 Java 运行时
+    List,
+
+    /// 设置 FullNode 使用的 JDK (不提供路径则交互式选择)
+    Use {
+        /// java 可执行文件路径
+        path: Option<PathBuf>,
+    },
+
+    /// 安装 JDK 到 tronctl 管理的运行时目录 (默认从 Adoptium 下载本机所需版本)
+    Install {
+        /// 本地 .tar.gz 归档或下载地址
+        #[arg(long)]
+        from: Option<String>,
+
+        /// 归档的校验值，如 sha256:<hex>，省略算法时按长度推断 (默认读取同名 .sha256.txt 校验文件，两者都没有时拒绝安装)
+        #[arg(long, alias = "sha256")]
+        checksum: Option<Checksum>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum SnapshotAction {
+    /// 列出各服务器上的快照日期、大小、校验文件与延迟
+    List {
+        /// 快照类型: lite, full (默认全部)
+        #[arg(short = 't', long = "type", value_parser = ["lite", "full"])]
+        snapshot_type: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum VersionsAction {
+    /// 列出已下载的版本，* 标记当前版本
+    List,
+
+    /// 将 FullNode.jar 切换到指定版本 (需重启节点生效)
+    Use {
+        /// 版本标签，如 GreatVoyage-v4.8.0
+        tag: String,
+    },
+
+    /// 删除旧版本，当前版本始终保留
+    Prune {
+        /// 保留最近下载的版本数量
+        #[arg(long)]
+        keep: usize,
+    },
+}
+
+/// 下载参数，覆盖配置文件中的 `[download]`
+#[derive(Args)]
+pub struct DownloadArgs {
+    /// 分块下载的并发连接数 (默认 CPU 核数)
+    #[arg(long)]
+    pub connections: Option<usize>,
+
+    /// 分块大小，如 16M，不小于 1M (默认 16M)
+    #[arg(long, value_name = "SIZE", value_parser = parse_chunk_size)]
+    pub chunk_size: Option<ByteSize>,
+
+    /// 下载限速（每秒），如 10M
+    #[arg(long, value_name = "RATE")]
+    pub limit_rate: Option<ByteSize>,
+}
+
+impl From<DownloadArgs> for DownloadConfig {
+    fn from(args: DownloadArgs) -> Self {
+        Self {
+            connections: args.connections,
+            chunk_size: args.chunk_size,
+            limit_rate: args.limit_rate,
+        }
+    }
 }
@@ -1,8 +1,16 @@
 pub mod clean;
+pub mod doctor;
 pub mod init;
+pub mod java;
 pub mod logs;
+pub mod peers;
 pub mod restart;
+pub mod snapshot;
 pub mod start;
 pub mod status;
 pub mod stop;
 pub mod systemd;
+pub mod upgrade;
+pub mod verify_chain;
+pub mod version;
+pub mod versions;
@@ -1,4 +1,4 @@
-use crate::constants::{CONFIG_DIR, DATA_DIR, LOG_DIR, PID_FILE};
+use crate::constants::{CONFIG_DIR, DATA_DIR, LOG_DIR, PID_FILE, VERSIONS_DIR};
 use crate::core::ProcessManager;
 use crate::error::Result;
 use dialoguer::Confirm;
@@ -69,6 +69,7 @@ pub async fn execute(skip_confirm: bool) -> Result<()> {
         // 仅清理 FullNode.jar，保留区块链数据
         let jar_path = Path::new(DATA_DIR).join("FullNode.jar");
         clean_file(jar_path.to_str().unwrap_or(""), "FullNode.jar").await?;
+        clean_directory(VERSIONS_DIR, "FullNode 版本").await?;
 
         info!("已保留区块链数据目录: {}/data", DATA_DIR);
     }
@@ -0,0 +1,60 @@
+use crate::core::{HostAuditor, NodePorts, PortChecker, ProcessManager};
+use crate::error::{Result, TronCtlError};
+use crate::models::TronCtlConfig;
+use crate::models::check_result::{CheckLevel, CheckResult};
+
+pub async fn execute(json: bool) -> Result<()> {
+    let config = TronCtlConfig::load_or_default()?;
+    let ports = NodePorts::load(&config.node_config)?;
+
+    let node_running = ProcessManager::read_pid()?.is_some_and(ProcessManager::is_process_alive);
+
+    let mut results = HostAuditor::audit(&config).await;
+
+    // 节点运行中时确认端口已监听，否则确认端口空闲
+    if node_running {
+        results.extend(PortChecker::check_bound(&ports));
+    } else {
+        results.extend(PortChecker::check_free(&ports));
+    }
+    results.extend(PortChecker::check_firewall(ports.p2p));
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&results)?);
+    } else {
+        print_results(&results);
+    }
+
+    let failed = results
+        .iter()
+        .filter(|r| r.level == CheckLevel::Fail)
+        .count();
+    if failed > 0 {
+        return Err(TronCtlError::Other(anyhow::anyhow!(
+            "{} 项检查未通过",
+            failed
+        )));
+    }
+
+    Ok(())
+}
+
+/// 打印检查结果及修复建议
+pub fn print_results(results: &[CheckResult]) {
+    for result in results {
+        println!("[{}] {}: {}", result.symbol(), result.name, result.message);
+        if result.level != CheckLevel::Pass
+            && let Some(hint) = &result.hint
+        {
+            println!("      建议: {}", hint);
+        }
+    }
+
+    let count = |level| results.iter().filter(|r| r.level == level).count();
+    println!(
+        "\n通过 {}，警告 {}，失败 {}",
+        count(CheckLevel::Pass),
+        count(CheckLevel::Warn),
+        count(CheckLevel::Fail)
+    );
+}
@@ -1,22 +1,43 @@
-use crate::constants::{APP_CONFIG, CONFIG_DIR, DATA_DIR, LOG_DIR, NODE_CONFIG};
-use crate::core::{Downloader, EnvironmentChecker, SnapshotManager};
-use crate::error::Result;
+use crate::constants::{
+    APP_CONFIG, CONFIG_DIR, DATA_DIR, LOG_DIR, NODE_CONFIG, NODE_CONFIG_TEMPLATE_ENTRY,
+};
+use crate::core::disk_planner::DiskPlan;
+use crate::core::snapshot::{parse_snapshot_date, server_table};
+use crate::core::{
+    ConfigTemplate, DiskPlanner, Downloader, EnvironmentChecker, JavaRequirement, NodePorts,
+    PortChecker, ReleaseChecker, SnapshotManager, SnapshotMode, SnapshotStaging, Supervisor,
+    VersionStore,
+};
+use crate::error::{Result, TronCtlError};
 use crate::models::TronCtlConfig;
-use crate::utils::fs;
+use crate::models::check_result::CheckLevel;
+use crate::models::checksum::Checksum;
+use crate::models::node_config::DownloadConfig;
+use crate::utils::{archive, fs, network};
 use dialoguer::{Confirm, Input, Select};
 use std::path::{Path, PathBuf};
 use tracing::{info, warn};
 
-use super::systemd;
+use super::{doctor, java, systemd};
 
 pub async fn execute(
     snapshot_type: Option<String>,
+    snapshot_date: Option<String>,
     version: Option<String>,
+    checksum: Option<Checksum>,
     skip_checks: bool,
+    download: DownloadConfig,
 ) -> Result<()> {
     info!("开始初始化 Tron FullNode...");
 
+    if let Some(date) = &snapshot_date {
+        parse_snapshot_date(date)?;
+    }
+
     // 1. 环境检查
+    let existing = TronCtlConfig::load_or_default()?;
+    // 命令行下载参数优先于 [download]
+    let download = existing.download.clone().merge(download);
     if !skip_checks {
         EnvironmentChecker::check_all()?;
     } else {
@@ -27,17 +48,54 @@ pub async fn execute(
     create_directories().await?;
 
     // 3. 下载 FullNode.jar
-    let downloader = Downloader::new();
+    let downloader = Downloader::new().with_config(&download);
+    let releases = ReleaseChecker::from_config(&existing.github);
     let fullnode_jar = PathBuf::from(DATA_DIR).join("FullNode.jar");
 
-    if fullnode_jar.exists() {
+    let store = VersionStore::new();
+
+    let fullnode_version = if fullnode_jar.exists() {
         info!("FullNode.jar 已存在，跳过下载: {:?}", fullnode_jar);
+        store.active(&fullnode_jar)
     } else {
-        downloader.download_fullnode(version, &fullnode_jar).await?;
-    }
+        // 下载到版本目录，FullNode.jar 作为指向当前版本的符号链接
+        let tag = match version {
+            Some(tag) => tag,
+            None => releases.latest().await?.tag_name,
+        };
+        store
+            .install(&downloader, &releases, &tag, checksum.as_ref())
+            .await?;
+        store.activate(&tag, &fullnode_jar)?;
+        Some(tag)
+    };
+
+    // 按 FullNode.jar 的字节码版本选择 JDK
+    let configured_java = existing.java_path.clone();
+    let java_path = if !skip_checks {
+        let requirement = JavaRequirement::for_jar(&fullnode_jar);
+        let java = java::select_or_install(&configured_java, &requirement).await?;
+        info!("使用 Java: {}", java.summary());
+        java.path
+    } else {
+        configured_java
+    };
 
-    // 4. 生成默认配置文件
-    generate_default_config().await?;
+    // 4. 生成默认配置文件（与 FullNode.jar 版本一致）
+    let template = ConfigTemplate::from_config(&existing.github);
+    let node_config_tag = generate_default_config(
+        &store,
+        &template,
+        &fullnode_jar,
+        fullnode_version.as_deref(),
+        existing.node_config_tag.clone(),
+    )
+    .await?;
+
+    // 端口与防火墙预检（端口从节点配置读取），在长时间下载快照之前发现问题
+    if !skip_checks {
+        check_ports()?;
+    }
 
     // 5. 交互式选择快照
     let snapshot_choice = if let Some(s) = snapshot_type {
@@ -55,8 +113,8 @@ pub async fn execute(
             "none".to_string()
         } else {
             let items = vec![
-                "Lite 快照 (53 GB) - 推荐：快速同步，适合大多数场景",
-                "Full 快照 (2937 GB) - 完整数据，适合归档节点",
+                "Lite 快照 - 推荐：快速同步，适合大多数场景",
+                "Full 快照 - 完整数据，适合归档节点",
             ];
 
             let selection = Select::new()
@@ -74,68 +132,175 @@ pub async fn execute(
     };
 
     // 6. 下载快照（如果需要）
-    if snapshot_choice != "none" {
-        // 检查快照数据目录是否已存在
-        let snapshot_db_dir = PathBuf::from(DATA_DIR).join("data/output-directory/database");
-        if snapshot_db_dir.exists() && snapshot_db_dir.read_dir()?.next().is_some() {
+    if snapshot_choice == "none" {
+        if snapshot_date.is_some() {
+            warn!("未选择快照，忽略 --snapshot-date");
+        }
+        check_disk_plan(
+            DiskPlanner::plan(Path::new(DATA_DIR), 0, SnapshotMode::None)?,
+            skip_checks,
+        )?;
+    } else {
+        // 快照先解压到暂存目录，校验通过后才移动到位；遗留的暂存目录说明上次未完成
+        let data_dir = PathBuf::from(DATA_DIR).join("data");
+        let snapshot_db_dir = data_dir.join("output-directory/database");
+        let leftovers = SnapshotStaging::leftovers(&data_dir);
+
+        // 只有与本次选择的类型（及日期）一致的已完成暂存目录才直接使用，其他暂存目录在下载前清理
+        let ready = leftovers.iter().find(|staging| {
+            staging.is_complete() && staging.is_for(&snapshot_choice, snapshot_date.as_deref())
+        });
+        for staging in leftovers.iter().filter(|staging| staging.is_complete()) {
+            if ready.is_none_or(|ready| ready.dir() != staging.dir()) {
+                warn!(
+                    "已完成的暂存快照与本次选择不符，不使用: {:?}",
+                    staging.dir()
+                );
+            }
+        }
+        // 未指定日期时已完成的快照可能不是最新的，由用户决定是否使用
+        let use_ready = match ready {
+            Some(staging) if snapshot_date.is_none() => Confirm::new()
+                .with_prompt(format!(
+                    "检测到已完成校验但未移动的快照 {:?}，是否直接使用？（否则重新下载最新快照）",
+                    staging.dir()
+                ))
+                .default(true)
+                .interact()?,
+            Some(_) => true,
+            None => false,
+        };
+
+        if let Some(staging) = ready.filter(|_| use_ready) {
+            info!(
+                "检测到已完成校验但未移动的快照，移动到数据目录: {:?}",
+                staging.dir()
+            );
+            staging.commit().await?;
+        } else if leftovers.is_empty()
+            && snapshot_db_dir.exists()
+            && snapshot_db_dir.read_dir()?.next().is_some()
+        {
             info!("检测到已存在的快照数据，跳过下载: {:?}", snapshot_db_dir);
         } else {
-            let snapshot_mgr = SnapshotManager::new();
+            if !leftovers.is_empty() {
+                warn!("检测到未完成的快照解压，将继续或重新下载");
+            }
 
-            info!("选择快照服务器...");
-            let server = snapshot_mgr.select_fastest_server().await?;
+            let snapshot_mgr = SnapshotManager::from_config(&existing.snapshot);
 
-            info!("获取最新快照元数据...");
-            let metadata = snapshot_mgr
-                .get_latest_snapshot(&server, &snapshot_choice)
+            // 并发探测各服务器的延迟与实测吞吐，排除没有目标快照的服务器
+            let servers = snapshot_mgr
+                .rank_servers(&snapshot_choice, snapshot_date.as_deref())
                 .await?;
+            println!("{}", server_table(&servers));
+            let selection = SnapshotManager::select_server(
+                servers,
+                &snapshot_choice,
+                snapshot_date.as_deref(),
+            )?;
+            let metadata = selection.metadata.clone();
+            // 快照服务器可能需要认证头
+            let downloader =
+                Downloader::with_headers(network::header_map(&selection.primary().source.headers)?)
+                    .with_config(&download);
+
+            // 服务器未返回大小时按标称大小规划磁盘
+            let size_bytes = match metadata.size_bytes {
+                0 => SnapshotManager::nominal_bytes(&snapshot_choice)?,
+                size => size,
+            };
+            info!(
+                "下载快照: {} ({} GB)",
+                metadata.date,
+                size_bytes.div_ceil(1024 * 1024 * 1024)
+            );
+
+            // 下载前按实际快照大小规划磁盘，避免长时间下载后才发现空间不足
+            let data_root = Path::new(DATA_DIR);
+            let stream_plan = DiskPlanner::plan(data_root, size_bytes, SnapshotMode::Stream)?;
+            check_disk_plan(stream_plan, skip_checks)?;
+
+            let verify_plan =
+                DiskPlanner::plan(data_root, size_bytes, SnapshotMode::VerifyChecksum)?;
+
+            // 流式解压同样会校验压缩数据流；完整下载模式可在解压前发现损坏，避免解压无效数据
+            let verify = match &metadata.checksum {
+                None => {
+                    warn!("快照服务器未提供校验文件，无法校验快照完整性");
+                    false
+                }
+                Some(_) if !verify_plan.fits() => {
+                    warn!(
+                        "磁盘空间不足以完整下载后再解压（需要 {}GB），使用流式解压模式",
+                        verify_plan.required_bytes.div_ceil(1024 * 1024 * 1024)
+                    );
+                    false
+                }
+                Some(checksum) => {
+                    if !verify_plan.comfortable() {
+                        warn!("完整下载后再解压将使磁盘余量低于推荐值");
+                    }
+                    Confirm::new()
+                        .with_prompt(format!(
+                            "是否先完整下载再解压？\n  \
+                        启用: 下载完整文件并通过 {} 校验后再解压，需要更多磁盘空间\n  \
+                        禁用: 流式下载解压并同时校验，节省磁盘空间，校验失败时删除已解压数据；进程中断后需重新下载整个快照\n  \
+                        选择",
+                            checksum.algorithm.name()
+                        ))
+                        .default(false)
+                        .interact()?
+                }
+            };
 
-            info!("下载快照: {} ({} GB)", metadata.date, metadata.size_gb);
-
-            // 询问是否需要 MD5 校验
-            let verify_md5 = Confirm::new()
-                .with_prompt(
-                    "是否启用 MD5 校验？\n  \
-                    启用: 下载完整文件后校验，更安全但需要更多磁盘空间\n  \
-                    禁用: 流式下载解压，节省磁盘空间但无法验证完整性\n  \
-                    选择",
-                )
-                .default(false)
-                .interact()?;
-
-            let data_dir = PathBuf::from(DATA_DIR).join("data");
             fs::ensure_dir_exists(&data_dir).await?;
+            let staging = SnapshotStaging::new(&data_dir, &metadata.date, &snapshot_choice);
+            staging.prepare().await?;
 
-            if verify_md5 {
-                info!("使用 MD5 校验模式（完整下载后解压）");
+            if verify {
+                info!("使用完整校验模式（完整下载后解压）");
                 info!("正在下载快照到本地文件...");
 
                 let temp_file =
                     PathBuf::from(DATA_DIR).join(format!("tron-snapshot-{}.tgz", metadata.date));
 
-                // 完整下载并校验
+                // 从提供相同快照的各镜像并行下载并校验
                 downloader
-                    .download_with_progress(&metadata.download_url, &temp_file, Some(&metadata.md5))
+                    .download_from_mirrors(
+                        &selection.mirrors()?,
+                        &temp_file,
+                        metadata.checksum.as_ref(),
+                    )
                     .await?;
 
-                info!("MD5 校验通过，开始解压...");
+                info!("校验通过，开始解压...");
 
                 // 解压
-                extract_snapshot_file(&temp_file, &data_dir).await?;
+                archive::extract_tgz(&temp_file, staging.dir()).await?;
 
                 // 删除压缩文件
                 tokio::fs::remove_file(&temp_file).await?;
                 info!("压缩文件已清理");
             } else {
-                info!("使用流式解压模式（无 MD5 校验）");
+                info!("使用流式解压模式（边下载边校验）");
+                warn!("流式解压无法跨进程续传，中断后需重新下载整个快照");
                 info!("正在流式下载并解压，请耐心等待...");
 
                 // 流式下载并解压
+                // 某个服务器用尽重试预算后从同一偏移切换到其他提供相同快照的服务器
                 downloader
-                    .download_and_extract_tgz(&metadata.download_url, &data_dir, None)
+                    .download_and_extract_tgz(
+                        &selection.mirrors()?,
+                        staging.dir(),
+                        metadata.checksum.as_ref(),
+                    )
                     .await?;
             }
 
+            // 解压与校验均已完成，写入标记后再移动到位，中断后可据此继续
+            staging.mark_complete(&metadata).await?;
+            staging.commit().await?;
             info!("快照下载并解压完成");
         }
     }
@@ -178,7 +343,14 @@ pub async fn execute(
         .interact()?;
 
     // 8. 保存配置
-    save_config(&snapshot_choice, &jvm_min_heap, &jvm_max_heap)?;
+    save_config(
+        &java_path,
+        fullnode_version,
+        node_config_tag,
+        &snapshot_choice,
+        &jvm_min_heap,
+        &jvm_max_heap,
+    )?;
 
     // 9. 生成 systemd 服务文件
     info!("生成 systemd 服务文件...");
@@ -209,138 +381,106 @@ async fn create_directories() -> Result<()> {
     Ok(())
 }
 
-async fn generate_default_config() -> Result<()> {
-    info!("下载默认配置文件...");
-
+/// 生成节点配置，返回其所基于的默认配置模板的版本标签
+async fn generate_default_config(
+    store: &VersionStore,
+    template: &ConfigTemplate,
+    fullnode_jar: &Path,
+    fullnode_version: Option<&str>,
+    existing_tag: Option<String>,
+) -> Result<Option<String>> {
     let config_path = PathBuf::from(CONFIG_DIR).join(NODE_CONFIG);
 
     if config_path.exists() {
         warn!("配置文件已存在，跳过生成: {:?}", config_path);
-        return Ok(());
+        return Ok(existing_tag);
     }
 
-    // 从 GitHub 下载官方配置文件
-    let config_url = "https://raw.githubusercontent.com/tronprotocol/java-tron/master/framework/src/main/resources/config.conf";
-
-    let client = reqwest::Client::new();
-    let response = client.get(config_url).send().await?;
-
-    if !response.status().is_success() {
-        return Err(crate::error::TronCtlError::DownloadFailed(format!(
-            "下载配置文件失败: HTTP {}",
-            response.status()
-        )));
-    }
-
-    let config_content = response.text().await?;
-    tokio::fs::write(&config_path, config_content).await?;
+    let (content, tag) = match fullnode_version {
+        Some(tag) => (store.template(tag, template).await?, Some(tag.to_string())),
+        // 版本未知的 jar：读取其内置配置，没有时退回 master 分支
+        None => match archive::read_jar_entry(fullnode_jar, NODE_CONFIG_TEMPLATE_ENTRY)? {
+            Some(content) => (content, None),
+            None => (template.fetch("master").await?, None),
+        },
+    };
 
-    info!("配置文件已下载: {:?}", config_path);
+    tokio::fs::write(&config_path, content).await?;
+    info!(
+        "配置文件已生成: {:?} (模板版本: {})",
+        config_path,
+        tag.as_deref().unwrap_or("未知")
+    );
 
-    Ok(())
+    Ok(tag)
 }
 
-fn save_config(snapshot_type: &str, jvm_min_heap: &str, jvm_max_heap: &str) -> Result<()> {
-    let config = TronCtlConfig {
-        snapshot_type: snapshot_type.to_string(),
-        jvm_min_heap: jvm_min_heap.to_string(),
-        jvm_max_heap: jvm_max_heap.to_string(),
-        ..Default::default()
-    };
-
-    let toml = toml::to_string(&config)?;
-    let config_path = PathBuf::from(CONFIG_DIR).join(APP_CONFIG);
+fn check_ports() -> Result<()> {
+    info!("检查端口与防火墙...");
 
-    std::fs::write(&config_path, toml)?;
-    info!("配置已保存: {:?}", config_path);
+    let ports = NodePorts::load(&PathBuf::from(CONFIG_DIR).join(NODE_CONFIG))?;
 
-    Ok(())
-}
-
-async fn extract_snapshot_file(archive_path: &Path, dest_dir: &Path) -> Result<()> {
-    use flate2::read::GzDecoder;
-    use std::fs::File;
-    use std::path::Component;
-    use tar::Archive;
-
-    info!("开始解压快照文件...");
-
-    // 规范化目标路径
-    let dest_dir_canonical = dest_dir
-        .canonicalize()
-        .map_err(|e| crate::error::TronCtlError::Other(anyhow::anyhow!("无效的目标路径: {}", e)))?;
-
-    // 在独立线程中进行解压（阻塞操作）
-    let archive_path = archive_path.to_path_buf();
-    let extract_task = tokio::task::spawn_blocking(move || {
-        let file = File::open(&archive_path)?;
-        let decoder = GzDecoder::new(file);
-        let mut archive = Archive::new(decoder);
-
-        // 安全解压：验证每个文件的路径
-        for entry in archive.entries()? {
-            let mut entry = entry?;
-            let path = entry.path()?;
-
-            // 1. 检查路径是否包含 .. 组件
-            for component in path.components() {
-                if matches!(component, Component::ParentDir) {
-                    return Err(std::io::Error::new(
-                        std::io::ErrorKind::PermissionDenied,
-                        format!("检测到路径遍历攻击（包含 ..）: {:?}", path),
-                    ));
-                }
-            }
+    // 节点运行中时端口由节点自身占用，与 doctor 一样只确认端口已监听
+    if Supervisor::detect().running_pid()?.is_some() {
+        let mut results = PortChecker::check_bound(&ports);
+        results.extend(PortChecker::check_firewall(ports.p2p));
+        doctor::print_results(&results);
+        return Ok(());
+    }
 
-            // 2. 检查是否为绝对路径
-            if path.is_absolute() {
-                return Err(std::io::Error::new(
-                    std::io::ErrorKind::PermissionDenied,
-                    format!("拒绝解压绝对路径: {:?}", path),
-                ));
-            }
+    let mut results = PortChecker::check_free(&ports);
+    results.extend(PortChecker::check_firewall(ports.p2p));
 
-            // 3. 构造完整路径并验证
-            let full_path = dest_dir_canonical.join(&path);
+    doctor::print_results(&results);
 
-            // 4. 验证解压路径确实在目标目录内
-            let path_to_check = if full_path.exists() {
-                full_path.canonicalize()?
-            } else {
-                // 确保父目录存在
-                if let Some(parent) = full_path.parent() {
-                    std::fs::create_dir_all(parent)?;
-                }
-                // 对于不存在的文件，验证其父目录在目标目录内
-                if let Some(parent) = full_path.parent() {
-                    parent.canonicalize()?
-                } else {
-                    return Err(std::io::Error::new(
-                        std::io::ErrorKind::InvalidInput,
-                        "无效的文件路径",
-                    ));
-                }
-            };
+    let occupied: Vec<String> = results
+        .into_iter()
+        .filter(|r| r.level == CheckLevel::Fail)
+        .map(|r| r.name)
+        .collect();
 
-            // 确保路径在目标目录内
-            if !path_to_check.starts_with(&dest_dir_canonical) {
-                return Err(std::io::Error::new(
-                    std::io::ErrorKind::PermissionDenied,
-                    format!("路径在目标目录外: {:?} -> {:?}", path, path_to_check),
-                ));
-            }
+    if occupied.is_empty() {
+        Ok(())
+    } else {
+        Err(TronCtlError::PortInUse(occupied.join(", ")))
+    }
+}
 
-            // 安全解压
-            entry.unpack(&full_path)?;
+/// 空间不足时阻止下载；跳过检查时仅输出警告
+fn check_disk_plan(plan: DiskPlan, skip_checks: bool) -> Result<()> {
+    match plan.enforce() {
+        Err(e) if skip_checks => {
+            warn!("{}", e);
+            Ok(())
         }
+        result => result,
+    }
+}
 
-        Ok::<_, std::io::Error>(())
-    });
+fn save_config(
+    java_path: &Path,
+    fullnode_version: Option<String>,
+    node_config_tag: Option<String>,
+    snapshot_type: &str,
+    jvm_min_heap: &str,
+    jvm_max_heap: &str,
+) -> Result<()> {
+    let config = TronCtlConfig {
+        java_path: java_path.to_path_buf(),
+        fullnode_version,
+        node_config_tag,
+        snapshot_type: snapshot_type.to_string(),
+        jvm_min_heap: jvm_min_heap.to_string(),
+        jvm_max_heap: jvm_max_heap.to_string(),
+        // 保留用户预先配置的 [github] 镜像、webhook 等设置
+        ..TronCtlConfig::load_or_default()?
+    };
 
-    extract_task
-        .await
-        .map_err(|e| crate::error::TronCtlError::Other(anyhow::anyhow!("解压任务失败: {}", e)))??;
+    config.save()?;
+    info!(
+        "配置已保存: {:?}",
+        PathBuf::from(CONFIG_DIR).join(APP_CONFIG)
+    );
 
-    info!("快照解压完成");
     Ok(())
 }
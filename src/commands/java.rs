@@ -0,0 +1,134 @@
+use crate::core::{JavaDiscovery, JavaRequirement, JdkInstaller};
+use crate::error::{Result, TronCtlError};
+use crate::models::TronCtlConfig;
+use crate::models::checksum::Checksum;
+use crate::models::java_info::JavaInstallation;
+use dialoguer::{Confirm, Select};
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+use super::systemd;
+
+/// 列出本机找到的 Java 运行时及其兼容性
+pub async fn list() -> Result<()> {
+    let config = TronCtlConfig::load_or_default()?;
+    let found = JavaDiscovery::discover(&config.java_path);
+    let requirement = JavaRequirement::for_jar(&config.fullnode_jar);
+
+    println!("FullNode 要求: Java {}", requirement);
+
+    if found.is_empty() {
+        println!("未找到 Java 运行时");
+        return Ok(());
+    }
+
+    for java in &found {
+        let symbol = if requirement.check(java).is_ok() {
+            "✓"
+        } else {
+            "✗"
+        };
+        let active = if java.path == config.java_path {
+            " (当前)"
+        } else {
+            ""
+        };
+        println!("  {} {}{}", symbol, java.summary(), active);
+    }
+
+    Ok(())
+}
+
+/// 将指定（或交互选择的）JDK 写入 java_path，并更新已安装的 systemd 服务
+pub async fn use_java(path: Option<PathBuf>) -> Result<()> {
+    let mut config = TronCtlConfig::load_or_default()?;
+    let requirement = JavaRequirement::for_jar(&config.fullnode_jar);
+
+    let java = match path {
+        Some(path) => JavaDiscovery::check_path(&path, &requirement)?,
+        None => select_compatible(&config.java_path, &requirement)?,
+    };
+
+    record_java_path(&mut config, &java)
+}
+
+/// 安装 JDK 到 tronctl 管理的运行时目录并写入 java_path
+pub async fn install(from: Option<String>, checksum: Option<Checksum>) -> Result<()> {
+    let mut config = TronCtlConfig::load_or_default()?;
+    let java = JdkInstaller::new()
+        .with_requirement(JavaRequirement::for_jar(&config.fullnode_jar))
+        .install(from.as_deref(), checksum.as_ref())
+        .await?;
+
+    record_java_path(&mut config, &java)
+}
+
+/// 选择满足 FullNode.jar 要求的 JDK，找不到时提示自动安装
+pub async fn select_or_install(
+    configured: &Path,
+    requirement: &JavaRequirement,
+) -> Result<JavaInstallation> {
+    match select_compatible(configured, requirement) {
+        Err(TronCtlError::IncompatibleJavaVersion { current, .. })
+            if Confirm::new()
+                .with_prompt(format!(
+                    "未找到兼容的 JDK（当前: {}），是否自动安装 Java {}？",
+                    current, requirement
+                ))
+                .default(true)
+                .interact()? =>
+        {
+            JdkInstaller::new()
+                .with_requirement(requirement.clone())
+                .install(None, None)
+                .await
+        }
+        result => result,
+    }
+}
+
+fn record_java_path(config: &mut TronCtlConfig, java: &JavaInstallation) -> Result<()> {
+    config.java_path = java.path.clone();
+    config.save()?;
+    info!("java_path 已设置为: {}", java.summary());
+    systemd::refresh(config)
+}
+
+/// 在兼容的 JDK 中选择一个：只有一个时直接使用，多个时交互选择（默认已配置的路径）
+pub fn select_compatible(
+    configured: &Path,
+    requirement: &JavaRequirement,
+) -> Result<JavaInstallation> {
+    let found = JavaDiscovery::discover(configured);
+    let mut compatible: Vec<JavaInstallation> = found
+        .iter()
+        .filter(|java| requirement.check(java).is_ok())
+        .cloned()
+        .collect();
+
+    match compatible.len() {
+        0 => Err(TronCtlError::IncompatibleJavaVersion {
+            required: requirement.to_string(),
+            current: found
+                .first()
+                .map(|java| java.summary())
+                .unwrap_or_else(|| "未找到 Java".to_string()),
+        }),
+        1 => Ok(compatible.remove(0)),
+        _ => {
+            let items: Vec<String> = compatible.iter().map(|java| java.summary()).collect();
+            let default = compatible
+                .iter()
+                .position(|java| java.path == configured)
+                .unwrap_or(0);
+
+            let selection = Select::new()
+                .with_prompt("选择 FullNode 使用的 JDK")
+                .items(&items)
+                .default(default)
+                .interact()?;
+
+            Ok(compatible.remove(selection))
+        }
+    }
+}
@@ -0,0 +1,139 @@
+use crate::constants::{LOCAL_HTTP_ENDPOINT, PEER_STATE_FILE};
+use crate::core::peers;
+use crate::core::{HealthChecker, PeerMonitor, ProcessManager, Supervisor};
+use crate::error::Result;
+use crate::models::TronCtlConfig;
+use crate::models::node_state::NodeStatus;
+use crate::models::peer_info::NodeInfo;
+use std::path::Path;
+use std::time::Duration;
+use tracing::{info, warn};
+
+pub async fn execute(verbose: bool, record: bool) -> Result<()> {
+    if record {
+        return record_only().await;
+    }
+
+    let monitor = PeerMonitor::new(LOCAL_HTTP_ENDPOINT);
+    let info = monitor.node_info().await?;
+
+    if let Ok(Some(pid)) = ProcessManager::read_pid()
+        && let Err(e) = peers::record_peer_count(
+            Path::new(PEER_STATE_FILE),
+            pid,
+            info.peer_count(),
+            chrono::Utc::now().timestamp(),
+        )
+    {
+        warn!("无法记录对等节点状态: {}", e);
+    }
+
+    print_summary(&info);
+
+    match monitor.list_nodes().await {
+        Ok(nodes) => {
+            println!("已发现节点: {}", nodes.nodes.len());
+            if verbose {
+                for node in &nodes.nodes {
+                    println!("  {}:{}", node.address.decoded_host(), node.address.port);
+                }
+            }
+        }
+        Err(e) => warn!("查询已发现节点失败: {}", e),
+    }
+
+    print_peers(&info);
+
+    Ok(())
+}
+
+/// 记录本次连接数，持续无连接超过阈值时写入警告日志（systemd 定时器周期调用，不依赖人工执行 status）
+async fn record_only() -> Result<()> {
+    let Some(pid) = Supervisor::detect().running_pid()? else {
+        info!("节点未运行，跳过记录");
+        return Ok(());
+    };
+
+    let info = PeerMonitor::new(LOCAL_HTTP_ENDPOINT).node_info().await?;
+    let zero_for = peers::record_peer_count(
+        Path::new(PEER_STATE_FILE),
+        pid,
+        info.peer_count(),
+        chrono::Utc::now().timestamp(),
+    )?;
+
+    let config = TronCtlConfig::load_or_default()?;
+    if let NodeStatus::Unhealthy { reason, .. } = HealthChecker::evaluate_peers(
+        pid,
+        zero_for,
+        Duration::from_secs(config.zero_peer_timeout_secs),
+    ) {
+        warn!("节点异常: {}", reason);
+    }
+
+    Ok(())
+}
+
+fn print_summary(info: &NodeInfo) {
+    let local_head = info.head_block();
+
+    println!(
+        "本地区块: {}",
+        local_head.map_or("未知".to_string(), |h| h.to_string())
+    );
+    println!(
+        "连接节点: {} (出站 {} / 入站 {})",
+        info.peer_count(),
+        info.outbound(),
+        info.inbound()
+    );
+
+    if let Some(config) = &info.config_node_info {
+        println!(
+            "节点版本: {} (p2p {}, db {})",
+            config.code_version, config.p2p_version, config.db_version
+        );
+        println!(
+            "节点配置: 监听端口 {}, 最大连接 {}, 同 IP 最大连接 {}, 节点发现 {}",
+            config.listen_port,
+            config.max_connect_count,
+            config.same_ip_max_connect_count,
+            if config.discover_enable {
+                "开启"
+            } else {
+                "关闭"
+            }
+        );
+    }
+}
+
+fn print_peers(info: &NodeInfo) {
+    if info.peer_list.is_empty() {
+        println!("\n当前没有对等节点连接");
+        return;
+    }
+
+    let local_head = info.head_block();
+
+    println!(
+        "\n{:<22} {:<6} {:>12} {:>8} {:>10}",
+        "对等节点", "方向", "区块", "差值", "延迟(ms)"
+    );
+
+    for peer in &info.peer_list {
+        let head = peer.head_estimate();
+        let diff = match (head, local_head) {
+            (Some(h), Some(l)) => format!("{:+}", h as i64 - l as i64),
+            _ => "-".to_string(),
+        };
+
+        println!(
+            "{:<22} {:<6} {:>12} {:>8} {:>10.1}",
+            format!("{}:{}", peer.host, peer.port),
+            if peer.active { "出站" } else { "入站" },
+            head.map_or("-".to_string(), |h| h.to_string()),
+            diff,
+            peer.avg_latency
+        );
+    }
+}
@@ -0,0 +1,54 @@
+use crate::core::SnapshotManager;
+use crate::error::Result;
+use crate::models::TronCtlConfig;
+
+/// 列出所有快照服务器上可用的快照日期、大小、校验文件与服务器延迟
+pub async fn list(snapshot_type: Option<String>) -> Result<()> {
+    let types = match snapshot_type {
+        Some(snapshot_type) => vec![snapshot_type],
+        None => vec!["lite".to_string(), "full".to_string()],
+    };
+
+    let config = TronCtlConfig::load_or_default()?;
+    let manager = SnapshotManager::from_config(&config.snapshot);
+    for server in manager.probe_servers().await {
+        if !server.available {
+            println!("\n{} (不可用)", server.url());
+            continue;
+        }
+        println!("\n{} (延迟 {}ms)", server.url(), server.latency.as_millis());
+
+        for snapshot_type in &types {
+            let snapshots = manager
+                .list_snapshots(&server.source, snapshot_type)
+                .await?;
+            if snapshots.is_empty() {
+                println!("  {:<5} 无可用快照", snapshot_type);
+                continue;
+            }
+
+            for snapshot in snapshots {
+                let size = if snapshot.size_bytes > 0 {
+                    format!(
+                        "{:.1} GB",
+                        snapshot.size_bytes as f64 / (1024.0 * 1024.0 * 1024.0)
+                    )
+                } else {
+                    "未知".to_string()
+                };
+                let checksum = snapshot
+                    .checksum
+                    .as_ref()
+                    .map(|checksum| checksum.algorithm.id())
+                    .unwrap_or("无");
+                println!(
+                    "  {:<5} {}  {:>10}  校验: {}",
+                    snapshot_type, snapshot.date, size, checksum
+                );
+            }
+        }
+    }
+
+    println!("\n使用 'tronctl init --snapshot <类型> --snapshot-date <日期>' 安装指定快照");
+    Ok(())
+}
@@ -1,5 +1,13 @@
-use crate::core::{HealthChecker, ProcessManager};
+use crate::constants::{LOCAL_HTTP_ENDPOINT, PEER_STATE_FILE};
+use crate::core::{HealthChecker, NodePorts, PeerMonitor, PortChecker, ProcessManager, peers};
 use crate::error::Result;
+use crate::models::TronCtlConfig;
+use crate::models::node_state::NodeStatus;
+use std::path::Path;
+use std::time::Duration;
+use tracing::warn;
+
+use super::version;
 
 pub async fn execute(verbose: bool) -> Result<()> {
     let pid = ProcessManager::read_pid()?;
@@ -8,13 +16,51 @@ pub async fn execute(verbose: bool) -> Result<()> {
         None => {
             println!("状态: 未运行");
             println!("提示: 运行 'tronctl init' 初始化节点");
+
+            let config = TronCtlConfig::load_or_default()?;
+            if let Some(current) = version::installed_version(&config) {
+                version::warn_mandatory(&config, &current).await;
+            }
             Ok(())
         }
         Some(pid) => {
+            let config = TronCtlConfig::load_or_default()?;
             let checker = HealthChecker::new();
             let health = checker.check(pid).await?;
 
-            println!("状态: 运行中");
+            // 对等节点规则：持续无连接超过配置时长判定为异常
+            let mut node_status = NodeStatus::Running { pid };
+            let mut peer_count = None;
+            let mut running_version = None;
+            if health.rpc_responding
+                && let Ok(info) = PeerMonitor::new(LOCAL_HTTP_ENDPOINT).node_info().await
+            {
+                let zero_for = peers::record_peer_count(
+                    Path::new(PEER_STATE_FILE),
+                    pid,
+                    info.peer_count(),
+                    chrono::Utc::now().timestamp(),
+                )
+                .unwrap_or_else(|e| {
+                    warn!("无法记录对等节点状态: {}", e);
+                    None
+                });
+                node_status = HealthChecker::evaluate_peers(
+                    pid,
+                    zero_for,
+                    Duration::from_secs(config.zero_peer_timeout_secs),
+                );
+                peer_count = Some(info.peer_count());
+                running_version = info
+                    .config_node_info
+                    .map(|c| c.code_version)
+                    .filter(|v| !v.is_empty());
+            }
+
+            match &node_status {
+                NodeStatus::Unhealthy { reason, .. } => println!("状态: 异常 ({})", reason),
+                _ => println!("状态: 运行中"),
+            }
             println!("PID: {}", pid);
             println!("进程存活: {}", if health.process_alive { "✓" } else { "✗" });
             println!(
@@ -25,6 +71,14 @@ pub async fn execute(verbose: bool) -> Result<()> {
             if health.rpc_responding {
                 println!("当前区块: {}", health.current_block);
 
+                if let Some(v) = &running_version {
+                    println!("节点版本: {}", v);
+                }
+
+                if let Some(count) = peer_count {
+                    println!("连接节点: {}", count);
+                }
+
                 if verbose {
                     println!("\n检查区块同步状态...");
                     let syncing = checker.check_block_syncing().await?;
@@ -32,6 +86,18 @@ pub async fn execute(verbose: bool) -> Result<()> {
                 }
             }
 
+            if verbose && health.process_alive {
+                println!("\n端口监听:");
+                let ports = NodePorts::load(&config.node_config)?;
+                for result in PortChecker::check_bound(&ports) {
+                    println!("  {} {}: {}", result.symbol(), result.name, result.message);
+                }
+            }
+
+            if let Some(current) = running_version.or_else(|| version::installed_version(&config)) {
+                version::warn_mandatory(&config, &current).await;
+            }
+
             Ok(())
         }
     }
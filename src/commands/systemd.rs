@@ -1,15 +1,19 @@
-use crate::constants::{CONFIG_DIR, DATA_DIR, LOG_DIR};
+use crate::constants::{
+    CONFIG_DIR, DATA_DIR, LOG_DIR, PEER_RECORD_INTERVAL_SECS, SYSTEMD_PEERS_SERVICE_FILE,
+    SYSTEMD_PEERS_TIMER, SYSTEMD_PEERS_TIMER_FILE, SYSTEMD_SERVICE, SYSTEMD_SERVICE_FILE,
+};
 use crate::error::Result;
 use crate::models::TronCtlConfig;
 use std::path::Path;
-use tracing::info;
+use std::process::Command;
+use tracing::{info, warn};
 
 /// 生成并安装 systemd 服务文件
 pub async fn execute(force: bool) -> Result<()> {
     info!("生成 systemd 服务文件...");
 
     // 检查是否已存在服务文件
-    let service_path = "/etc/systemd/system/java-tron.service";
+    let service_path = SYSTEMD_SERVICE_FILE;
     if Path::new(service_path).exists() && !force {
         info!("服务文件已存在: {}", service_path);
         info!("如需重新生成，请使用 --force 参数");
@@ -32,25 +36,61 @@ pub async fn execute(force: bool) -> Result<()> {
     std::fs::write(service_path, service_content)?;
 
     info!("systemd 服务文件已生成: {}", service_path);
+
+    // 对等节点定时器：周期记录连接数，无人执行 status 时无连接规则同样生效
+    let tronctl = std::env::current_exe()?;
+    let (peers_service, peers_timer) = generate_peer_units(&tronctl.to_string_lossy());
+    std::fs::write(SYSTEMD_PEERS_SERVICE_FILE, peers_service)?;
+    std::fs::write(SYSTEMD_PEERS_TIMER_FILE, peers_timer)?;
+    info!("对等节点定时器已生成: {}", SYSTEMD_PEERS_TIMER_FILE);
+
     info!("运行以下命令启用并启动服务:");
     info!("  sudo systemctl daemon-reload");
-    info!("  sudo systemctl enable java-tron");
-    info!("  sudo systemctl start java-tron");
+    info!("  sudo systemctl enable {}", SYSTEMD_SERVICE);
+    info!("  sudo systemctl start {}", SYSTEMD_SERVICE);
+    info!("  sudo systemctl enable --now {}", SYSTEMD_PEERS_TIMER);
+
+    Ok(())
+}
+
+/// 按当前配置更新已安装的服务文件，内容变化时执行 `systemctl daemon-reload`
+pub fn refresh(config: &TronCtlConfig) -> Result<()> {
+    let service_path = Path::new(SYSTEMD_SERVICE_FILE);
+    if !service_path.exists() {
+        return Ok(());
+    }
+
+    let service_content = generate_service_file(config);
+    if std::fs::read_to_string(service_path).is_ok_and(|current| current == service_content) {
+        return Ok(());
+    }
+    std::fs::write(service_path, service_content)?;
+    info!("systemd 服务文件已更新: {}", SYSTEMD_SERVICE_FILE);
 
+    match Command::new("systemctl").arg("daemon-reload").status() {
+        Ok(status) if status.success() => {}
+        Ok(status) => warn!("systemctl daemon-reload 失败: {}", status),
+        Err(e) => warn!("无法执行 systemctl daemon-reload: {}", e),
+    }
     Ok(())
 }
 
 /// 生成 systemd 服务文件内容
 fn generate_service_file(config: &TronCtlConfig) -> String {
+    let java_path = config.java_path.to_string_lossy();
     let fullnode_jar = config.fullnode_jar.to_string_lossy();
     let node_config = config.node_config.to_string_lossy();
     let data_dir = config.data_dir.to_string_lossy();
     let jvm_opts = format!("-Xms{} -Xmx{}", config.jvm_min_heap, config.jvm_max_heap);
+    let description = match &config.fullnode_version {
+        Some(tag) => format!("TRON FullNode Service ({})", tag),
+        None => "TRON FullNode Service".to_string(),
+    };
 
     indoc::formatdoc!(
         r#"
         [Unit]
-        Description=TRON FullNode Service
+        Description={description}
         Documentation=https://github.com/tronprotocol/java-tron
         After=network-online.target
         Wants=network-online.target
@@ -60,7 +100,7 @@ fn generate_service_file(config: &TronCtlConfig) -> String {
         User=root
         WorkingDirectory={DATA_DIR}
         Environment="JAVA_OPTS={jvm_opts}"
-        ExecStart=/usr/bin/java $JAVA_OPTS -jar {fullnode_jar} -c {node_config} -d {data_dir}
+        ExecStart={java_path} $JAVA_OPTS -jar {fullnode_jar} -c {node_config} -d {data_dir}
         ExecStop=/usr/bin/kill -SIGTERM $MAINPID
         Restart=on-failure
         RestartSec=10
@@ -74,8 +114,9 @@ fn generate_service_file(config: &TronCtlConfig) -> String {
         ProtectHome=true
         ReadWritePaths={DATA_DIR} {LOG_DIR}
 
-        # 文件句柄限制（TRON 节点需要大量连接）
+        # 文件句柄与进程数限制（TRON 节点需要大量连接与线程）
         LimitNOFILE=1048576
+        LimitNPROC=65536
 
         [Install]
         WantedBy=multi-user.target
@@ -83,6 +124,38 @@ fn generate_service_file(config: &TronCtlConfig) -> String {
     )
 }
 
+/// 生成周期执行 `tronctl peers --record` 的 service 与 timer 单元内容
+fn generate_peer_units(tronctl: &str) -> (String, String) {
+    let service = indoc::formatdoc!(
+        r#"
+        [Unit]
+        Description=Record TRON FullNode peer count
+        After={SYSTEMD_SERVICE}.service
+
+        [Service]
+        Type=oneshot
+        User=root
+        ExecStart={tronctl} peers --record
+        "#
+    );
+
+    let timer = indoc::formatdoc!(
+        r#"
+        [Unit]
+        Description=Record TRON FullNode peer count every {PEER_RECORD_INTERVAL_SECS}s
+
+        [Timer]
+        OnBootSec={PEER_RECORD_INTERVAL_SECS}
+        OnUnitActiveSec={PEER_RECORD_INTERVAL_SECS}
+
+        [Install]
+        WantedBy=timers.target
+        "#
+    );
+
+    (service, timer)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -112,9 +185,45 @@ mod tests {
         assert!(service.contains("PrivateTmp=true"));
         assert!(service.contains("ProtectSystem=full"));
         assert!(service.contains("LimitNOFILE=1048576"));
+        assert!(service.contains("LimitNPROC=65536"));
         assert!(service.contains("$JAVA_OPTS"));
     }
 
+    #[test]
+    fn test_service_uses_configured_java() {
+        let config = TronCtlConfig {
+            java_path: "/opt/java/jdk8u392-b08/bin/java".into(),
+            ..Default::default()
+        };
+        let service = generate_service_file(&config);
+
+        assert!(service.contains("ExecStart=/opt/java/jdk8u392-b08/bin/java $JAVA_OPTS"));
+        assert!(!service.contains("/usr/bin/java"));
+    }
+
+    #[test]
+    fn test_service_description_includes_version() {
+        let config = TronCtlConfig {
+            fullnode_version: Some("GreatVoyage-v4.8.0".to_string()),
+            ..Default::default()
+        };
+        let service = generate_service_file(&config);
+
+        assert!(service.contains("Description=TRON FullNode Service (GreatVoyage-v4.8.0)"));
+    }
+
+    #[test]
+    fn test_generate_peer_units() {
+        let (service, timer) = generate_peer_units("/usr/local/bin/tronctl");
+
+        assert!(service.contains("Type=oneshot"));
+        assert!(service.contains("ExecStart=/usr/local/bin/tronctl peers --record"));
+        assert!(service.contains("After=java-tron.service"));
+        assert!(timer.contains("[Timer]"));
+        assert!(timer.contains(&format!("OnUnitActiveSec={}", PEER_RECORD_INTERVAL_SECS)));
+        assert!(timer.contains("WantedBy=timers.target"));
+    }
+
     #[test]
     fn test_service_contains_paths() {
         let config = TronCtlConfig::default();
@@ -0,0 +1,179 @@
+use crate::constants::HEALTH_CHECK_INTERVAL_SECS;
+use crate::core::config_template::{ConfigDiff, unified_diff};
+use crate::core::{
+    ConfigTemplate, Downloader, HealthChecker, ReleaseChecker, Supervisor, VersionStore,
+};
+use crate::error::{Result, TronCtlError};
+use crate::models::TronCtlConfig;
+use crate::models::checksum::Checksum;
+use crate::models::node_config::DownloadConfig;
+use std::time::Duration;
+use tracing::{info, warn};
+
+use super::versions::{check_java_for, record_active_version};
+
+pub async fn execute(
+    version: Option<String>,
+    checksum: Option<Checksum>,
+    timeout_secs: u64,
+    prerelease: bool,
+    download: DownloadConfig,
+) -> Result<()> {
+    let mut config = TronCtlConfig::load_or_default()?;
+    let jar_link = config.fullnode_jar.clone();
+
+    let downloader = Downloader::new().with_config(&config.download.clone().merge(download));
+    let releases = ReleaseChecker::from_config(&config.github)
+        .with_prerelease(config.github.prerelease || prerelease);
+    let tag = match version {
+        Some(tag) => tag,
+        None => releases.latest().await?.tag_name,
+    };
+
+    let store = VersionStore::new();
+    let previous = store.adopt(&jar_link)?;
+
+    if previous.as_deref() == Some(tag.as_str()) {
+        info!("当前已是 {}，无需升级", tag);
+        return Ok(());
+    }
+
+    // 新版本下载到版本目录，与旧版本并存
+    store
+        .install(&downloader, &releases, &tag, checksum.as_ref())
+        .await?;
+    check_java_for(&config, &store.jar_path(&tag)?)?;
+
+    let template = ConfigTemplate::from_config(&config.github);
+    show_config_diff(&store, &template, &config, &tag).await;
+
+    let supervisor = Supervisor::detect();
+    let was_running = supervisor.running_pid()?.is_some();
+    if was_running {
+        info!("停止节点...");
+        supervisor.stop()?;
+    }
+
+    store.activate(&tag, &jar_link)?;
+    let previous_version = config.fullnode_version.clone();
+    record_active_version(&mut config, Some(tag.clone()))?;
+
+    if !was_running {
+        info!("节点未运行，已切换到 {}，下次启动生效", tag);
+        return Ok(());
+    }
+
+    let result = start_and_wait(supervisor, &config, timeout_secs).await;
+
+    match result {
+        Ok(block) => {
+            info!("升级完成: {} (当前区块 {})", tag, block);
+            Ok(())
+        }
+        Err(e) => {
+            warn!("新版本未能在 {} 秒内恢复健康: {}", timeout_secs, e);
+            rollback(
+                supervisor,
+                &store,
+                &mut config,
+                previous.as_deref(),
+                previous_version,
+            )
+            .await?;
+            Err(TronCtlError::UpgradeRolledBack {
+                version: tag,
+                reason: e.to_string(),
+            })
+        }
+    }
+}
+
+/// 对比旧默认配置、新默认配置与当前 tron.conf，提示需要手动合并的改动
+async fn show_config_diff(
+    store: &VersionStore,
+    template: &ConfigTemplate,
+    config: &TronCtlConfig,
+    tag: &str,
+) {
+    let Ok(user) = tokio::fs::read_to_string(&config.node_config).await else {
+        return;
+    };
+
+    let new_default = match store.template(tag, template).await {
+        Ok(content) => content,
+        Err(e) => {
+            warn!("无法获取 {} 的默认配置，跳过配置对比: {}", tag, e);
+            return;
+        }
+    };
+
+    let old_default = match config.node_config_tag.as_deref() {
+        Some(old_tag) => store
+            .template(old_tag, template)
+            .await
+            .ok()
+            .map(|content| (old_tag, content)),
+        None => None,
+    };
+
+    match old_default {
+        Some((old_tag, old_default)) => {
+            let diff = ConfigDiff::new(&old_default, &new_default, &user);
+            if diff.upstream_changed() {
+                println!("\n{}", diff.render(old_tag, tag));
+            } else {
+                info!("{} 与 {} 的默认配置相同", old_tag, tag);
+            }
+        }
+        None => {
+            println!(
+                "\n未记录 tron.conf 所基于的模板版本，仅显示与 {} 默认配置的差异:",
+                tag
+            );
+            println!("{}", unified_diff(&user, &new_default, "tron.conf", tag));
+        }
+    }
+}
+
+async fn start_and_wait(
+    supervisor: Supervisor,
+    config: &TronCtlConfig,
+    timeout_secs: u64,
+) -> Result<u64> {
+    info!("启动节点并等待 RPC 与区块同步...");
+    let pid = supervisor.start(config).await?;
+
+    HealthChecker::new()
+        .wait_healthy(
+            pid,
+            Duration::from_secs(timeout_secs),
+            Duration::from_secs(HEALTH_CHECK_INTERVAL_SECS),
+        )
+        .await
+}
+
+/// 停止新版本，切回之前的版本并重新启动
+async fn rollback(
+    supervisor: Supervisor,
+    store: &VersionStore,
+    config: &mut TronCtlConfig,
+    previous: Option<&str>,
+    previous_version: Option<String>,
+) -> Result<()> {
+    if supervisor.running_pid()?.is_some() {
+        supervisor.stop()?;
+    }
+
+    let Some(previous) = previous else {
+        warn!("没有可回滚的旧版本，节点保持停止");
+        return Ok(());
+    };
+
+    info!("回滚到 {}...", previous);
+    store.activate(previous, &config.fullnode_jar)?;
+    record_active_version(config, previous_version)?;
+
+    let pid = supervisor.start(config).await?;
+    info!("已回滚并重新启动节点 (PID: {})", pid);
+    Ok(())
+}
@@ -0,0 +1,81 @@
+use crate::constants::LOCAL_HTTP_ENDPOINT;
+use crate::core::ChainVerifier;
+use crate::error::{Result, TronCtlError};
+use crate::models::TronCtlConfig;
+use crate::models::chain_report::{ChainConsistency, ChainReport};
+
+pub async fn execute(references: Vec<String>, samples: usize) -> Result<()> {
+    let references = if references.is_empty() {
+        TronCtlConfig::load_or_default()?.reference_endpoints
+    } else {
+        references
+    };
+
+    if references.is_empty() {
+        return Err(TronCtlError::ConfigError(
+            "未配置参考节点: 请使用 --reference 或在 tronctl.toml 中设置 reference_endpoints"
+                .to_string(),
+        ));
+    }
+
+    let verifier = ChainVerifier::new(LOCAL_HTTP_ENDPOINT);
+    let report = verifier.verify(&references, samples).await?;
+
+    print_report(&report);
+
+    if let Some((endpoint, height)) = report.first_fork() {
+        return Err(TronCtlError::ChainForked {
+            endpoint: endpoint.to_string(),
+            height,
+        });
+    }
+
+    if report.all_unreachable() {
+        return Err(TronCtlError::RpcCallFailed(
+            "所有参考节点均不可达".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+fn print_report(report: &ChainReport) {
+    println!("\n本地区块高度: {}", report.local_head);
+    if report.local_tail > 1 {
+        println!(
+            "本地最早区块: {}（轻节点，只比对此后的区块）",
+            report.local_tail
+        );
+    }
+
+    for reference in &report.references {
+        println!("\n参考节点: {}", reference.endpoint);
+
+        if let Some(head) = reference.reference_head {
+            println!("  参考高度: {}", head);
+        }
+
+        for c in &reference.comparisons {
+            println!(
+                "  {} 高度 {}: {}",
+                if c.matches { "✓" } else { "✗" },
+                c.height,
+                if c.matches {
+                    c.local_hash.clone()
+                } else {
+                    format!("本地 {} / 参考 {}", c.local_hash, c.reference_hash)
+                }
+            );
+        }
+
+        let summary = match &reference.status {
+            ChainConsistency::Consistent => "一致".to_string(),
+            ChainConsistency::Behind { blocks } => format!("一致，本地落后 {} 个区块", blocks),
+            ChainConsistency::Forked { first_mismatch } => {
+                format!("已分叉，首个不一致高度: {}", first_mismatch)
+            }
+            ChainConsistency::Unreachable { reason } => format!("无法比对: {}", reason),
+        };
+        println!("  结果: {}", summary);
+    }
+}
@@ -0,0 +1,149 @@
+use crate::constants::{LOCAL_HTTP_ENDPOINT, RELEASE_NOTES_MAX_LINES, RELEASE_STATE_FILE};
+use crate::core::{Notifier, PeerMonitor, ReleaseChecker, VersionStore, releases};
+use crate::error::Result;
+use crate::models::TronCtlConfig;
+use std::path::Path;
+use tracing::{debug, warn};
+
+/// 显示 tronctl、已安装与运行中的 FullNode 版本，`check` 时对比 GitHub 最新版本
+pub async fn execute(check: bool) -> Result<()> {
+    let config = TronCtlConfig::load_or_default()?;
+    let installed = installed_version(&config);
+    let running = running_version().await;
+
+    println!("tronctl: {}", env!("CARGO_PKG_VERSION"));
+    println!("已安装版本: {}", installed.as_deref().unwrap_or("未知"));
+    println!(
+        "运行中版本: {}",
+        running.as_deref().unwrap_or("节点未运行或 RPC 未响应")
+    );
+
+    if !check {
+        return Ok(());
+    }
+
+    let checker = ReleaseChecker::from_config(&config.github);
+    let current = running.or(installed);
+    // 翻页直到当前版本，落后较多时也能列出全部发布说明与强制升级标记
+    let all = match &current {
+        Some(current) => checker.list_since(current).await?,
+        None => checker.list().await?,
+    };
+    if let Some(latest) = all.iter().find(|r| checker.prerelease() || !r.prerelease) {
+        println!(
+            "最新版本: {} ({})",
+            latest.tag_name,
+            latest.published_date()
+        );
+    }
+
+    let Some(current) = current else {
+        println!("\n无法确定当前版本，跳过发布说明");
+        return Ok(());
+    };
+
+    let newer = releases::newer_releases(&all, &current, checker.prerelease());
+    if newer.is_empty() {
+        println!("\n已是最新版本");
+        return Ok(());
+    }
+
+    println!("\n{} 之后发布了 {} 个版本:", current, newer.len());
+    for release in &newer {
+        let mandatory = if release.is_mandatory() {
+            " [强制升级]"
+        } else {
+            ""
+        };
+        println!(
+            "\n== {} ({}){}",
+            release.tag_name,
+            release.published_date(),
+            mandatory
+        );
+
+        let body = release.body.as_deref().unwrap_or_default().trim();
+        let lines: Vec<&str> = body.lines().collect();
+        for line in lines.iter().take(RELEASE_NOTES_MAX_LINES) {
+            println!("  {}", line);
+        }
+        if lines.len() > RELEASE_NOTES_MAX_LINES {
+            println!("  ...");
+        }
+        println!("  {}", release.html_url);
+    }
+
+    Ok(())
+}
+
+/// 检查是否有比 `current` 新的强制升级版本：有则醒目提示，并在配置了 webhook 时通知一次
+pub async fn warn_mandatory(config: &TronCtlConfig, current: &str) {
+    let state_file = Path::new(RELEASE_STATE_FILE);
+    let now = chrono::Utc::now().timestamp();
+
+    let mut state = match ReleaseChecker::from_config(&config.github)
+        .check_mandatory(state_file, current, now)
+        .await
+    {
+        Ok(state) => state,
+        Err(e) => {
+            debug!("检查新版本失败: {}", e);
+            return;
+        }
+    };
+
+    if state.mandatory.is_empty() {
+        return;
+    }
+
+    println!("\n⚠ 发现强制升级版本: {}", state.mandatory.join(", "));
+    println!("  当前版本 {} 可能在硬分叉后无法同步，请尽快升级", current);
+    println!("  运行 'tronctl version --check' 查看发布说明，'tronctl upgrade' 升级");
+
+    let Some(url) = &config.webhook_url else {
+        return;
+    };
+
+    let pending: Vec<String> = state
+        .mandatory
+        .iter()
+        .filter(|tag| !state.notified.contains(tag))
+        .cloned()
+        .collect();
+    if pending.is_empty() {
+        return;
+    }
+
+    let text = format!(
+        "java-tron 强制升级版本已发布: {}（当前 {}）",
+        pending.join(", "),
+        current
+    );
+    match Notifier::new(url).send("mandatory_release", &text).await {
+        Ok(()) => {
+            state.notified.extend(pending);
+            if let Err(e) = releases::save_state(state_file, &state) {
+                warn!("保存版本检查结果失败: {}", e);
+            }
+        }
+        Err(e) => warn!("{}", e),
+    }
+}
+
+/// FullNode.jar 指向的版本，未记录时读取配置中的 fullnode_version
+pub fn installed_version(config: &TronCtlConfig) -> Option<String> {
+    VersionStore::new()
+        .active(&config.fullnode_jar)
+        .or_else(|| config.fullnode_version.clone())
+}
+
+/// 运行中节点通过 getnodeinfo 报告的版本
+async fn running_version() -> Option<String> {
+    let info = PeerMonitor::new(LOCAL_HTTP_ENDPOINT)
+        .node_info()
+        .await
+        .ok()?;
+    info.config_node_info
+        .map(|c| c.code_version)
+        .filter(|v| !v.is_empty())
+}
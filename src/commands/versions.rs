@@ -0,0 +1,86 @@
+use crate::core::{JavaDiscovery, JavaRequirement, VersionStore};
+use crate::error::Result;
+use crate::models::TronCtlConfig;
+use std::path::Path;
+use tracing::info;
+
+use super::systemd;
+
+/// 列出版本目录中的 jar，标记当前生效的版本
+pub async fn list() -> Result<()> {
+    let config = TronCtlConfig::load_or_default()?;
+    let store = VersionStore::new();
+    let active = store.active(&config.fullnode_jar);
+    let versions = store.list()?;
+
+    if versions.is_empty() {
+        println!("版本目录为空，运行 'tronctl upgrade' 下载 FullNode.jar");
+        return Ok(());
+    }
+
+    println!("  {:<28} {:<17} {:>8}  SHA-256", "版本", "下载时间", "大小");
+    for version in versions {
+        let marker = if active.as_deref() == Some(version.tag.as_str()) {
+            "*"
+        } else {
+            " "
+        };
+        println!(
+            "{} {:<28} {:<17} {:>6}MB  {}",
+            marker,
+            version.tag,
+            version.downloaded_at_display(),
+            version.size / (1024 * 1024),
+            &version.sha256[..version.sha256.len().min(16)]
+        );
+    }
+
+    Ok(())
+}
+
+/// 切换到版本目录中的指定版本，需重启节点生效
+pub async fn use_version(tag: &str) -> Result<()> {
+    let mut config = TronCtlConfig::load_or_default()?;
+    let store = VersionStore::new();
+
+    store.adopt(&config.fullnode_jar)?;
+    check_java_for(&config, &store.jar_path(tag)?)?;
+    store.activate(tag, &config.fullnode_jar)?;
+    record_active_version(&mut config, Some(tag.to_string()))?;
+
+    info!(
+        "已切换到 {}，运行 'tronctl restart' 或 'systemctl restart java-tron' 生效",
+        tag
+    );
+    Ok(())
+}
+
+/// 删除旧版本，只保留最近的 `keep` 个（当前版本始终保留）
+pub async fn prune(keep: usize) -> Result<()> {
+    let config = TronCtlConfig::load_or_default()?;
+    let store = VersionStore::new();
+    let active = store.active(&config.fullnode_jar);
+
+    let removed = store.prune(keep, active.as_deref())?;
+    if removed.is_empty() {
+        info!("没有需要清理的版本");
+    }
+    for tag in removed {
+        info!("已删除版本: {}", tag);
+    }
+
+    Ok(())
+}
+
+/// 切换前确认 java_path 满足目标 jar 的要求，避免切换后节点无法启动
+pub fn check_java_for(config: &TronCtlConfig, jar: &Path) -> Result<()> {
+    JavaDiscovery::check_path(&config.java_path, &JavaRequirement::for_jar(jar))?;
+    Ok(())
+}
+
+/// 在配置中记录当前版本，并更新已安装的 systemd 服务
+pub fn record_active_version(config: &mut TronCtlConfig, tag: Option<String>) -> Result<()> {
+    config.fullnode_version = tag;
+    config.save()?;
+    systemd::refresh(config)
+}
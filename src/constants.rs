@@ -2,19 +2,81 @@ pub const DATA_DIR: &str = "/var/lib/tronctl";
 pub const CONFIG_DIR: &str = "/etc/tronctl";
 pub const LOG_DIR: &str = "/var/log/tronctl";
 pub const PID_FILE: &str = "/run/tronctl/tronctl.pid";
+pub const PEER_STATE_FILE: &str = "/run/tronctl/peers.state";
 
 pub const NODE_CONFIG: &str = "tron.conf";
 pub const APP_CONFIG: &str = "tronctl.toml";
 
-pub const REQUIRED_JAVA_VERSION: &str = "1.8";
+pub const REQUIRED_JAVA_MAJOR_X86_64: u32 = 8;
+pub const REQUIRED_JAVA_MAJOR_AARCH64: u32 = 17;
+/// 并列存放各版本 FullNode.jar 的目录
+pub const VERSIONS_DIR: &str = "/var/lib/tronctl/versions";
+/// tronctl 管理的 JDK 安装目录
+pub const JAVA_RUNTIME_DIR: &str = "/var/lib/tronctl/runtime";
+pub const ADOPTIUM_API: &str = "https://api.adoptium.net/v3";
+/// 常见 JDK 安装目录，其下每个子目录视为一个 JAVA_HOME
+pub const JAVA_SEARCH_DIRS: &[&str] =
+    &["/usr/lib/jvm", "/usr/java", "/opt/java", "/usr/local/java"];
 
 pub const RECOMMENDED_MEMORY_GB: u64 = 32;
 pub const RECOMMENDED_DISK_GB: u64 = 2560;
+pub const MIN_MEMORY_GB: u64 = 16;
+pub const MIN_CPU_CORES: usize = 4;
+pub const RECOMMENDED_CPU_CORES: usize = 8;
+pub const RECOMMENDED_NOFILE: u64 = 65536;
+pub const RECOMMENDED_NPROC: u64 = 65536;
+pub const RECOMMENDED_MAX_MAP_COUNT: u64 = 262144;
+
+/// 快照解压后相对压缩包大小的估算比例（百分比），数据库文件本身已压缩
+pub const SNAPSHOT_EXTRACT_RATIO_PERCENT: u64 = 115;
+/// FullNode.jar、日志等额外预留空间
+pub const DISK_BASE_RESERVE_GB: u64 = 10;
+/// 在所需空间之上为链增长预留的余量（百分比）
+pub const DISK_GROWTH_HEADROOM_PERCENT: u64 = 20;
 
 pub const GITHUB_REPO: &str = "tronprotocol/java-tron";
+pub const GITHUB_API_BASE: &str = "https://api.github.com";
+pub const GITHUB_DOWNLOAD_BASE: &str = "https://github.com";
+pub const GITHUB_RAW_BASE: &str = "https://raw.githubusercontent.com";
+/// java-tron 默认节点配置在仓库中的路径，也以 `config.conf` 打包在 FullNode.jar 根目录
+pub const NODE_CONFIG_TEMPLATE_PATH: &str = "framework/src/main/resources/config.conf";
+pub const NODE_CONFIG_TEMPLATE_ENTRY: &str = "config.conf";
+/// Release 中 FullNode 附件的文件名
+pub const FULLNODE_ASSET: &str = "FullNode.jar";
+/// 有效的 FullNode.jar 必须包含的主类
+pub const FULLNODE_MAIN_CLASS: &str = "org/tron/program/FullNode.class";
 pub const GITHUB_API_RELEASES: &str =
     "https://api.github.com/repos/tronprotocol/java-tron/releases";
 
+/// Releases API 每页返回的版本数（GitHub 上限为 100）
+pub const RELEASES_PER_PAGE: usize = 100;
+/// 翻页查找当前版本时最多读取的页数
+pub const RELEASES_MAX_PAGES: usize = 10;
+
+/// 两次检查新版本之间的最短间隔（GitHub 未认证请求每小时限 60 次）
+pub const RELEASE_CHECK_INTERVAL_SECS: i64 = 6 * 3600;
+pub const RELEASE_STATE_FILE: &str = "/var/lib/tronctl/release.state";
+/// `version --check` 每个版本最多显示的发布说明行数
+pub const RELEASE_NOTES_MAX_LINES: usize = 30;
+
+/// 服务器未提供目录索引时向前探测快照的天数
+pub const SNAPSHOT_LOOKBACK_DAYS: i64 = 7;
+
+/// 选择快照服务器时分段下载以测量吞吐的字节数
+pub const SNAPSHOT_PROBE_BYTES: u64 = 4 * 1024 * 1024;
+/// 吞吐测量的最长时间（秒）
+pub const SNAPSHOT_PROBE_TIMEOUT_SECS: u64 = 8;
+/// 列出快照时同时探测的日期数
+pub const SNAPSHOT_LIST_CONCURRENCY: usize = 8;
+
+/// 分块下载的默认分块大小
+pub const DEFAULT_DOWNLOAD_CHUNK_SIZE: u64 = 16 * 1024 * 1024;
+/// 分块大小的下限，过小的分块会使进度文件过大
+pub const MIN_DOWNLOAD_CHUNK_SIZE: u64 = 1024 * 1024;
+
+/// 快照相对服务器根地址的默认路径
+pub const SNAPSHOT_PATH_TEMPLATE: &str = "backup{date}/{prefix}.tgz";
+
 pub const SNAPSHOT_SERVERS: &[&str] = &[
     "http://34.143.247.77",
     "http://34.86.86.229",
@@ -24,9 +86,29 @@ pub const SNAPSHOT_SERVERS: &[&str] = &[
 pub const DEFAULT_JVM_MIN_HEAP: &str = "8g";
 pub const DEFAULT_JVM_MAX_HEAP: &str = "12g";
 
+pub const DEFAULT_P2P_PORT: u16 = 18888;
+pub const DEFAULT_HTTP_PORT: u16 = 8090;
+pub const DEFAULT_GRPC_PORT: u16 = 50051;
+pub const DEFAULT_JSONRPC_PORT: u16 = 8545;
+
 pub const RPC_ENDPOINT: &str = "http://127.0.0.1:8090/wallet/getnowblock";
+pub const LOCAL_HTTP_ENDPOINT: &str = "http://127.0.0.1:8090";
 pub const HEALTH_CHECK_INTERVAL_SECS: u64 = 5;
 pub const BLOCK_HEIGHT_CHECK_COUNT: usize = 3;
+pub const ZERO_PEER_TIMEOUT_SECS: u64 = 600;
+/// 升级后等待节点恢复健康的默认时长（java-tron 加载数据库较慢）
+pub const UPGRADE_HEALTH_TIMEOUT_SECS: u64 = 900;
+
+pub const SYSTEMD_SERVICE: &str = "java-tron";
+pub const SYSTEMD_SERVICE_FILE: &str = "/etc/systemd/system/java-tron.service";
+/// 周期记录对等节点连接数的 systemd 定时器
+pub const SYSTEMD_PEERS_TIMER: &str = "java-tron-peers.timer";
+pub const SYSTEMD_PEERS_SERVICE_FILE: &str = "/etc/systemd/system/java-tron-peers.service";
+pub const SYSTEMD_PEERS_TIMER_FILE: &str = "/etc/systemd/system/java-tron-peers.timer";
+pub const PEER_RECORD_INTERVAL_SECS: u64 = 60;
+
+pub const DEFAULT_REFERENCE_ENDPOINTS: &[&str] = &["https://api.trongrid.io"];
+pub const CHAIN_VERIFY_SAMPLE_COUNT: usize = 8;
 
 #[cfg(test)]
 mod tests {
@@ -38,6 +120,7 @@ mod tests {
         assert!(CONFIG_DIR.starts_with('/'));
         assert!(LOG_DIR.starts_with('/'));
         assert!(PID_FILE.starts_with('/'));
+        assert!(PEER_STATE_FILE.starts_with('/'));
     }
 
     #[test]
@@ -50,15 +133,22 @@ mod tests {
 
     #[test]
     fn test_java_version_format() {
-        assert!(!REQUIRED_JAVA_VERSION.is_empty());
-        assert_eq!(REQUIRED_JAVA_VERSION, "1.8");
+        assert_eq!(REQUIRED_JAVA_MAJOR_X86_64, 8);
+        assert_eq!(REQUIRED_JAVA_MAJOR_AARCH64, 17);
+        assert!(JAVA_SEARCH_DIRS.iter().all(|d| d.starts_with('/')));
+        assert!(JAVA_RUNTIME_DIR.starts_with(DATA_DIR));
+        assert!(VERSIONS_DIR.starts_with(DATA_DIR));
     }
 
     #[test]
     fn test_memory_disk_requirements() {
-        assert!(RECOMMENDED_MEMORY_GB > 0);
-        assert!(RECOMMENDED_DISK_GB > 0);
-        assert!(RECOMMENDED_DISK_GB > RECOMMENDED_MEMORY_GB);
+        const { assert!(RECOMMENDED_MEMORY_GB > 0) };
+        const { assert!(RECOMMENDED_DISK_GB > 0) };
+        const { assert!(RECOMMENDED_DISK_GB > RECOMMENDED_MEMORY_GB) };
+        const { assert!(MIN_MEMORY_GB < RECOMMENDED_MEMORY_GB) };
+        const { assert!(SNAPSHOT_EXTRACT_RATIO_PERCENT >= 100) };
+        const { assert!(DISK_BASE_RESERVE_GB < RECOMMENDED_DISK_GB) };
+        const { assert!(MIN_CPU_CORES < RECOMMENDED_CPU_CORES) };
     }
 
     #[test]
@@ -72,6 +162,9 @@ mod tests {
     fn test_github_api_url() {
         assert!(GITHUB_API_RELEASES.starts_with("https://"));
         assert!(GITHUB_API_RELEASES.contains("api.github.com"));
+        assert!(GITHUB_API_RELEASES.starts_with(GITHUB_API_BASE));
+        assert!(GITHUB_API_RELEASES.contains(GITHUB_REPO));
+        assert!(!GITHUB_DOWNLOAD_BASE.ends_with('/'));
     }
 
     #[test]
@@ -92,6 +185,20 @@ mod tests {
         assert!(!DEFAULT_JVM_MAX_HEAP.is_empty());
     }
 
+    #[test]
+    fn test_default_ports_distinct() {
+        let ports = [
+            DEFAULT_P2P_PORT,
+            DEFAULT_HTTP_PORT,
+            DEFAULT_GRPC_PORT,
+            DEFAULT_JSONRPC_PORT,
+        ];
+        for (i, a) in ports.iter().enumerate() {
+            assert!(ports[i + 1..].iter().all(|b| a != b));
+        }
+        assert!(RPC_ENDPOINT.contains(&DEFAULT_HTTP_PORT.to_string()));
+    }
+
     #[test]
     fn test_rpc_endpoint_format() {
         assert!(RPC_ENDPOINT.starts_with("http://"));
@@ -99,10 +206,22 @@ mod tests {
         assert!(RPC_ENDPOINT.contains(":8090"));
     }
 
+    #[test]
+    fn test_reference_endpoints_valid() {
+        assert!(!DEFAULT_REFERENCE_ENDPOINTS.is_empty());
+        for endpoint in DEFAULT_REFERENCE_ENDPOINTS {
+            assert!(endpoint.starts_with("https://"));
+            assert!(!endpoint.ends_with('/'));
+        }
+        assert!(RPC_ENDPOINT.starts_with(LOCAL_HTTP_ENDPOINT));
+    }
+
     #[test]
     fn test_health_check_params() {
-        assert!(HEALTH_CHECK_INTERVAL_SECS > 0);
-        assert!(BLOCK_HEIGHT_CHECK_COUNT > 1);
-        assert!(BLOCK_HEIGHT_CHECK_COUNT < 10);
+        const { assert!(HEALTH_CHECK_INTERVAL_SECS > 0) };
+        const { assert!(BLOCK_HEIGHT_CHECK_COUNT > 1) };
+        const { assert!(BLOCK_HEIGHT_CHECK_COUNT < 10) };
+        const { assert!(ZERO_PEER_TIMEOUT_SECS > HEALTH_CHECK_INTERVAL_SECS) };
+        const { assert!(UPGRADE_HEALTH_TIMEOUT_SECS > HEALTH_CHECK_INTERVAL_SECS) };
     }
 }
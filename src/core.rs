@@ -1,11 +1,37 @@
+pub mod chain_verifier;
+pub mod config_template;
+pub mod disk_planner;
 pub mod downloader;
 pub mod environment;
 pub mod health;
+pub mod host_audit;
+pub mod java;
+pub mod jdk_installer;
+pub mod notifier;
+pub mod peers;
+pub mod ports;
 pub mod process;
+pub mod releases;
 pub mod snapshot;
+pub mod staging;
+pub mod supervisor;
+pub mod versions;
 
+pub use chain_verifier::ChainVerifier;
+pub use config_template::ConfigTemplate;
+pub use disk_planner::{DiskPlanner, SnapshotMode};
 pub use downloader::Downloader;
 pub use environment::EnvironmentChecker;
 pub use health::HealthChecker;
+pub use host_audit::HostAuditor;
+pub use java::{JavaDiscovery, JavaRequirement};
+pub use jdk_installer::JdkInstaller;
+pub use notifier::Notifier;
+pub use peers::PeerMonitor;
+pub use ports::{NodePorts, PortChecker};
 pub use process::ProcessManager;
+pub use releases::ReleaseChecker;
 pub use snapshot::SnapshotManager;
+pub use staging::SnapshotStaging;
+pub use supervisor::Supervisor;
+pub use versions::VersionStore;
@@ -0,0 +1,480 @@
+use crate::error::{Result, TronCtlError};
+use crate::models::chain_report::{
+    BlockIdResponse, ChainConsistency, ChainReport, HeightComparison, ReferenceResult,
+};
+use crate::models::health_status::BlockInfo;
+use reqwest::Client;
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+pub struct ChainVerifier {
+    client: Client,
+    local_endpoint: String,
+}
+
+impl ChainVerifier {
+    pub fn new(local_endpoint: &str) -> Self {
+        Self {
+            client: Client::builder()
+                .timeout(Duration::from_secs(10))
+                .user_agent("tronctl/0.1.0")
+                .build()
+                .expect("Failed to build HTTP client"),
+            local_endpoint: local_endpoint.trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// 将本地节点与所有参考节点逐一比对，只在本地节点保存的区块范围内取样
+    pub async fn verify(&self, references: &[String], samples: usize) -> Result<ChainReport> {
+        let local_head = self.get_head(&self.local_endpoint).await?;
+        info!("本地区块高度: {}", local_head);
+
+        let local_tail = self.local_tail(local_head).await?;
+        if local_tail > 1 {
+            info!("本地节点只保存 {} 之后的区块（轻节点）", local_tail);
+        }
+
+        let mut results = Vec::new();
+        for reference in references {
+            let reference = reference.trim_end_matches('/');
+            info!("比对参考节点: {}", reference);
+            results.push(
+                self.verify_reference(reference, local_tail, local_head, samples)
+                    .await?,
+            );
+        }
+
+        Ok(ChainReport {
+            local_head,
+            local_tail,
+            references: results,
+        })
+    }
+
+    /// 本地节点保存的最早区块：全节点为 1，轻节点从快照高度附近开始
+    async fn local_tail(&self, head: u64) -> Result<u64> {
+        if head <= 1
+            || self
+                .get_block_hash(&self.local_endpoint, 1)
+                .await?
+                .is_some()
+        {
+            return Ok(head.min(1));
+        }
+
+        // lo 处缺少区块，hi 处存在区块（head 总是存在）
+        let (mut lo, mut hi) = (1, head);
+        while hi - lo > 1 {
+            let mid = lo + (hi - lo) / 2;
+            if self
+                .get_block_hash(&self.local_endpoint, mid)
+                .await?
+                .is_some()
+            {
+                hi = mid;
+            } else {
+                lo = mid;
+            }
+        }
+
+        Ok(hi)
+    }
+
+    /// 与单个参考节点比对；参考节点的错误记为不可达，本地节点的错误中止整体检查
+    async fn verify_reference(
+        &self,
+        reference: &str,
+        local_tail: u64,
+        local_head: u64,
+        samples: usize,
+    ) -> Result<ReferenceResult> {
+        let mut result = ReferenceResult {
+            endpoint: reference.to_string(),
+            reference_head: None,
+            comparisons: Vec::new(),
+            status: ChainConsistency::Consistent,
+        };
+
+        let reference_head = match self.get_head(reference).await {
+            Ok(head) => head,
+            Err(e) => {
+                warn!("参考节点不可达 {}: {}", reference, e);
+                result.status = ChainConsistency::Unreachable {
+                    reason: e.to_string(),
+                };
+                return Ok(result);
+            }
+        };
+        result.reference_head = Some(reference_head);
+
+        let common_head = local_head.min(reference_head);
+        if common_head < local_tail {
+            result.status = ChainConsistency::Unreachable {
+                reason: format!(
+                    "参考节点高度 {} 低于本地最早区块 {}",
+                    reference_head, local_tail
+                ),
+            };
+            return Ok(result);
+        }
+        let mut last_match = local_tail.saturating_sub(1);
+
+        for height in Self::sample_heights(local_tail, common_head, samples) {
+            let comparison = match self.compare_height(reference, height).await {
+                Ok(c) => c,
+                Err(CompareError::Local(e)) => return Err(e),
+                Err(CompareError::Reference(e)) => {
+                    result.status = ChainConsistency::Unreachable {
+                        reason: e.to_string(),
+                    };
+                    return Ok(result);
+                }
+            };
+
+            let matches = comparison.matches;
+            result.comparisons.push(comparison);
+
+            if matches {
+                last_match = height;
+                continue;
+            }
+
+            // 分叉后两条链不会再重合，在上一个一致高度与当前高度之间二分查找首个分歧点；
+            // 轻节点在最早区块之前的分歧无法确定，此时报告为最早区块
+            result.status = match self
+                .find_first_mismatch(reference, last_match, height)
+                .await
+            {
+                Ok(first_mismatch) => ChainConsistency::Forked { first_mismatch },
+                Err(CompareError::Local(e)) => return Err(e),
+                Err(CompareError::Reference(e)) => ChainConsistency::Unreachable {
+                    reason: e.to_string(),
+                },
+            };
+            return Ok(result);
+        }
+
+        if local_head < reference_head {
+            result.status = ChainConsistency::Behind {
+                blocks: reference_head - local_head,
+            };
+        }
+
+        Ok(result)
+    }
+
+    /// 在 (good, bad] 区间内查找首个哈希不一致的高度
+    async fn find_first_mismatch(
+        &self,
+        reference: &str,
+        good: u64,
+        bad: u64,
+    ) -> std::result::Result<u64, CompareError> {
+        let (mut lo, mut hi) = (good, bad);
+
+        while hi - lo > 1 {
+            let mid = lo + (hi - lo) / 2;
+            if self.compare_height(reference, mid).await?.matches {
+                lo = mid;
+            } else {
+                hi = mid;
+            }
+        }
+
+        Ok(hi)
+    }
+
+    async fn compare_height(
+        &self,
+        reference: &str,
+        height: u64,
+    ) -> std::result::Result<HeightComparison, CompareError> {
+        let local_hash = self
+            .get_block_hash(&self.local_endpoint, height)
+            .await
+            .and_then(|hash| {
+                hash.ok_or_else(|| {
+                    TronCtlError::RpcCallFailed(format!("本地节点缺少区块 {}", height))
+                })
+            })
+            .map_err(CompareError::Local)?;
+        let reference_hash = self
+            .get_block_hash(reference, height)
+            .await
+            .and_then(|hash| {
+                hash.ok_or_else(|| {
+                    TronCtlError::RpcCallFailed(format!("{} 缺少区块 {}", reference, height))
+                })
+            })
+            .map_err(CompareError::Reference)?;
+
+        debug!(
+            "高度 {}: 本地 {} / 参考 {}",
+            height, local_hash, reference_hash
+        );
+
+        Ok(HeightComparison {
+            height,
+            matches: local_hash == reference_hash,
+            local_hash,
+            reference_hash,
+        })
+    }
+
+    /// 在 [tail, head] 内均匀取样，并始终包含 head 本身
+    fn sample_heights(tail: u64, head: u64, samples: usize) -> Vec<u64> {
+        let tail = tail.max(1);
+        if head < tail || samples == 0 {
+            return Vec::new();
+        }
+
+        let span = head - tail + 1;
+        let samples = (samples as u64).min(span);
+        let mut heights: Vec<u64> = (1..=samples)
+            .map(|i| tail - 1 + span * i / samples)
+            .collect();
+        heights.dedup();
+        heights
+    }
+
+    async fn get_head(&self, endpoint: &str) -> Result<u64> {
+        let url = format!("{}/wallet/getnowblock", endpoint);
+        let resp = self.client.get(&url).send().await?;
+
+        if !resp.status().is_success() {
+            return Err(TronCtlError::RpcCallFailed(format!(
+                "{} HTTP {}",
+                url,
+                resp.status()
+            )));
+        }
+
+        let block: BlockInfo = resp.json().await?;
+        Ok(block.block_header.raw_data.number)
+    }
+
+    /// 区块不存在时返回 None
+    async fn get_block_hash(&self, endpoint: &str, height: u64) -> Result<Option<String>> {
+        let url = format!("{}/wallet/getblockbynum", endpoint);
+        let resp = self
+            .client
+            .post(&url)
+            .json(&serde_json::json!({ "num": height }))
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            return Err(TronCtlError::RpcCallFailed(format!(
+                "{} HTTP {}",
+                url,
+                resp.status()
+            )));
+        }
+
+        let block: BlockIdResponse = resp.json().await?;
+        Ok(block.block_id)
+    }
+}
+
+/// 比对失败的一方：本地节点的错误中止整体检查，参考节点的错误只影响该参考节点
+enum CompareError {
+    Local(TronCtlError),
+    Reference(TronCtlError),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::{Matcher, Server, ServerGuard};
+
+    /// 启动一个模拟 TRON 节点：高度 <= fork_at 之前的哈希与主链一致，之后带上 tag 后缀
+    async fn mock_node(head: u64, fork_at: Option<u64>, tag: &'static str) -> ServerGuard {
+        mock_node_from(1, head, fork_at, tag).await
+    }
+
+    /// 只保存 [tail, head] 区块的模拟节点（轻节点）
+    async fn mock_node_from(
+        tail: u64,
+        head: u64,
+        fork_at: Option<u64>,
+        tag: &'static str,
+    ) -> ServerGuard {
+        let mut server = Server::new_async().await;
+
+        server
+            .mock("GET", "/wallet/getnowblock")
+            .with_status(200)
+            .with_body(format!(
+                r#"{{"block_header":{{"raw_data":{{"number":{},"timestamp":0}}}}}}"#,
+                head
+            ))
+            .create_async()
+            .await;
+
+        server
+            .mock("POST", "/wallet/getblockbynum")
+            .match_body(Matcher::Any)
+            .with_status(200)
+            .with_body_from_request(move |req| {
+                let body: serde_json::Value = serde_json::from_slice(req.body().unwrap()).unwrap();
+                let num = body["num"].as_u64().unwrap();
+
+                if num > head || num < tail {
+                    return b"{}".to_vec();
+                }
+
+                let hash = match fork_at {
+                    Some(f) if num >= f => format!("{:016x}{}", num, tag),
+                    _ => format!("{:016x}main", num),
+                };
+                format!(r#"{{"blockID":"{}"}}"#, hash).into_bytes()
+            })
+            .create_async()
+            .await;
+
+        server
+    }
+
+    #[test]
+    fn test_sample_heights_includes_head() {
+        let heights = ChainVerifier::sample_heights(1, 1000, 4);
+        assert_eq!(heights, vec![250, 500, 750, 1000]);
+    }
+
+    #[test]
+    fn test_sample_heights_small_chain() {
+        assert_eq!(ChainVerifier::sample_heights(1, 3, 8), vec![1, 2, 3]);
+        assert!(ChainVerifier::sample_heights(1, 0, 8).is_empty());
+    }
+
+    #[test]
+    fn test_sample_heights_within_local_range() {
+        assert_eq!(
+            ChainVerifier::sample_heights(801, 1000, 4),
+            vec![850, 900, 950, 1000]
+        );
+        assert!(ChainVerifier::sample_heights(900, 800, 4).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_verify_lite_node() {
+        let local = mock_node_from(801, 1000, None, "").await;
+        let reference = mock_node(1000, None, "").await;
+
+        let verifier = ChainVerifier::new(&local.url());
+        let report = verifier.verify(&[reference.url()], 5).await.unwrap();
+
+        assert_eq!(report.local_tail, 801);
+        assert_eq!(report.references[0].status, ChainConsistency::Consistent);
+        assert!(
+            report.references[0]
+                .comparisons
+                .iter()
+                .all(|c| c.height >= 801)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_verify_local_block_error_is_not_unreachable() {
+        let mut local = Server::new_async().await;
+        local
+            .mock("GET", "/wallet/getnowblock")
+            .with_status(200)
+            .with_body(r#"{"block_header":{"raw_data":{"number":100,"timestamp":0}}}"#)
+            .create_async()
+            .await;
+        local
+            .mock("POST", "/wallet/getblockbynum")
+            .with_status(500)
+            .create_async()
+            .await;
+        let reference = mock_node(100, None, "").await;
+
+        let verifier = ChainVerifier::new(&local.url());
+        assert!(verifier.verify(&[reference.url()], 3).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_verify_consistent() {
+        let local = mock_node(1000, None, "").await;
+        let reference = mock_node(1000, None, "").await;
+
+        let verifier = ChainVerifier::new(&local.url());
+        let report = verifier.verify(&[reference.url()], 5).await.unwrap();
+
+        assert_eq!(report.local_head, 1000);
+        assert_eq!(report.references[0].status, ChainConsistency::Consistent);
+        assert_eq!(report.references[0].comparisons.len(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_verify_behind() {
+        let local = mock_node(900, None, "").await;
+        let reference = mock_node(1000, None, "").await;
+
+        let verifier = ChainVerifier::new(&local.url());
+        let report = verifier.verify(&[reference.url()], 5).await.unwrap();
+
+        assert_eq!(
+            report.references[0].status,
+            ChainConsistency::Behind { blocks: 100 }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_verify_forked_finds_first_mismatch() {
+        let local = mock_node(1000, Some(637), "fork").await;
+        let reference = mock_node(1000, None, "").await;
+
+        let verifier = ChainVerifier::new(&local.url());
+        let report = verifier.verify(&[reference.url()], 4).await.unwrap();
+
+        assert!(report.first_fork().is_some());
+        assert_eq!(
+            report.references[0].status,
+            ChainConsistency::Forked {
+                first_mismatch: 637
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_verify_multiple_references() {
+        let local = mock_node(500, None, "").await;
+        let good = mock_node(500, None, "").await;
+        let forked = mock_node(500, Some(1), "other").await;
+
+        let verifier = ChainVerifier::new(&local.url());
+        let report = verifier
+            .verify(&[good.url(), forked.url()], 3)
+            .await
+            .unwrap();
+
+        assert_eq!(report.references[0].status, ChainConsistency::Consistent);
+        assert_eq!(
+            report.references[1].status,
+            ChainConsistency::Forked { first_mismatch: 1 }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_verify_unreachable_reference() {
+        let local = mock_node(100, None, "").await;
+
+        let verifier = ChainVerifier::new(&local.url());
+        let report = verifier
+            .verify(&["http://invalid.test.nonexistent".to_string()], 3)
+            .await
+            .unwrap();
+
+        assert!(report.all_unreachable());
+        assert!(report.references[0].reference_head.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_verify_local_unreachable() {
+        let verifier = ChainVerifier::new("http://invalid.test.nonexistent");
+        let result = verifier.verify(&[], 3).await;
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,261 @@
+use crate::constants::{
+    GITHUB_RAW_BASE, GITHUB_REPO, NODE_CONFIG_TEMPLATE_ENTRY, NODE_CONFIG_TEMPLATE_PATH,
+};
+use crate::error::{Result, TronCtlError};
+use crate::models::node_config::GithubConfig;
+use crate::utils::archive;
+use reqwest::Client;
+use similar::{ChangeTag, DiffOp, TextDiff};
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::time::Duration;
+use tracing::info;
+
+/// 获取与 FullNode 版本一致的默认节点配置 (config.conf)
+pub struct ConfigTemplate {
+    client: Client,
+    raw_base: String,
+}
+
+impl ConfigTemplate {
+    pub fn new() -> Self {
+        Self::with_raw_base(GITHUB_RAW_BASE)
+    }
+
+    pub fn from_config(github: &GithubConfig) -> Self {
+        Self::with_raw_base(&github.raw_base)
+    }
+
+    pub fn with_raw_base(raw_base: &str) -> Self {
+        Self {
+            client: Client::builder()
+                .timeout(Duration::from_secs(30))
+                .user_agent("tronctl/0.1.0")
+                .build()
+                .expect("Failed to build HTTP client"),
+            raw_base: raw_base.trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// 指定标签下 config.conf 的下载地址
+    pub fn url(&self, tag: &str) -> String {
+        format!(
+            "{}/{}/{}/{}",
+            self.raw_base, GITHUB_REPO, tag, NODE_CONFIG_TEMPLATE_PATH
+        )
+    }
+
+    /// 优先读取 jar 内置的 config.conf，没有时从仓库的同一标签下载
+    pub async fn load(&self, jar: &Path, tag: &str) -> Result<String> {
+        if jar.exists()
+            && let Some(content) = archive::read_jar_entry(jar, NODE_CONFIG_TEMPLATE_ENTRY)?
+        {
+            info!("使用 {} 内置的默认配置", tag);
+            return Ok(content);
+        }
+
+        self.fetch(tag).await
+    }
+
+    /// 从仓库下载指定标签的 config.conf
+    pub async fn fetch(&self, tag: &str) -> Result<String> {
+        let url = self.url(tag);
+        info!("下载默认配置: {}", url);
+
+        let response = self.client.get(&url).send().await?;
+        if !response.status().is_success() {
+            return Err(TronCtlError::DownloadFailed(format!(
+                "下载配置文件失败: HTTP {}",
+                response.status()
+            )));
+        }
+
+        Ok(response.text().await?)
+    }
+}
+
+impl Default for ConfigTemplate {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 旧默认配置、新默认配置与用户配置的三方对比
+pub struct ConfigDiff<'a> {
+    old_default: &'a str,
+    new_default: &'a str,
+    user: &'a str,
+}
+
+impl<'a> ConfigDiff<'a> {
+    pub fn new(old_default: &'a str, new_default: &'a str, user: &'a str) -> Self {
+        Self {
+            old_default,
+            new_default,
+            user,
+        }
+    }
+
+    /// 官方默认配置是否有变化
+    pub fn upstream_changed(&self) -> bool {
+        self.old_default != self.new_default
+    }
+
+    /// 官方与用户都修改过的旧默认配置行（行号从 1 开始）
+    pub fn conflicts(&self) -> Vec<(usize, &'a str)> {
+        let upstream = touched_lines(&TextDiff::from_lines(self.old_default, self.new_default));
+        let local = touched_lines(&TextDiff::from_lines(self.old_default, self.user));
+        let lines: Vec<&str> = self.old_default.lines().collect();
+
+        upstream
+            .intersection(&local)
+            .map(|&i| (i + 1, lines.get(i).copied().unwrap_or_default().trim_end()))
+            .collect()
+    }
+
+    /// 输出官方变化、用户修改与冲突行
+    pub fn render(&self, old_tag: &str, new_tag: &str) -> String {
+        let mut out = String::new();
+
+        out.push_str(&format!(
+            "=== 官方默认配置变化 ({} -> {}) ===\n",
+            old_tag, new_tag
+        ));
+        out.push_str(&unified_diff(
+            self.old_default,
+            self.new_default,
+            old_tag,
+            new_tag,
+        ));
+
+        out.push_str(&format!(
+            "\n=== 您的修改 ({} 默认配置 -> 当前配置) ===\n",
+            old_tag
+        ));
+        out.push_str(&unified_diff(
+            self.old_default,
+            self.user,
+            old_tag,
+            "tron.conf",
+        ));
+
+        let conflicts = self.conflicts();
+        if !conflicts.is_empty() {
+            out.push_str("\n=== 双方都修改过的行，需要手动合并 ===\n");
+            for (line_no, line) in conflicts {
+                out.push_str(&format!("  {:>5}: {}\n", line_no, line));
+            }
+        }
+
+        out
+    }
+}
+
+/// 统一格式的逐行差异
+pub fn unified_diff(old: &str, new: &str, old_name: &str, new_name: &str) -> String {
+    if old == new {
+        return "(无变化)\n".to_string();
+    }
+
+    TextDiff::from_lines(old, new)
+        .unified_diff()
+        .context_radius(2)
+        .header(old_name, new_name)
+        .to_string()
+}
+
+/// 差异中被修改或删除的旧文件行，纯插入记为插入位置所在行
+fn touched_lines(diff: &TextDiff<'_, '_, '_, str>) -> BTreeSet<usize> {
+    let mut lines = BTreeSet::new();
+
+    for op in diff.ops() {
+        if op.tag() == similar::DiffTag::Equal {
+            continue;
+        }
+
+        if let DiffOp::Insert { old_index, .. } = op {
+            lines.insert(*old_index);
+        } else {
+            for change in diff.iter_changes(op) {
+                if change.tag() == ChangeTag::Delete
+                    && let Some(index) = change.old_index()
+                {
+                    lines.insert(index);
+                }
+            }
+        }
+    }
+
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const OLD: &str = "node {\n  listen.port = 18888\n  maxConnections = 30\n}\nvm {\n  supportConstant = false\n}\n";
+
+    #[test]
+    fn test_url() {
+        let template = ConfigTemplate::new();
+        assert_eq!(
+            template.url("GreatVoyage-v4.8.0"),
+            "https://raw.githubusercontent.com/tronprotocol/java-tron/GreatVoyage-v4.8.0/framework/src/main/resources/config.conf"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_load_falls_back_to_tag() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock(
+                "GET",
+                "/tronprotocol/java-tron/v1/framework/src/main/resources/config.conf",
+            )
+            .with_body(OLD)
+            .create_async()
+            .await;
+
+        let temp_dir = TempDir::new().unwrap();
+        let template = ConfigTemplate::with_raw_base(&server.url());
+        let content = template
+            .load(&temp_dir.path().join("missing.jar"), "v1")
+            .await
+            .unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(content, OLD);
+    }
+
+    #[test]
+    fn test_config_diff_conflicts() {
+        // 官方修改 maxConnections 并新增 vm 配置；用户修改 maxConnections 与端口
+        let new = OLD
+            .replace("maxConnections = 30", "maxConnections = 50")
+            .replace(
+                "supportConstant = false\n",
+                "supportConstant = false\n  estimateEnergy = true\n",
+            );
+        let user = OLD
+            .replace("maxConnections = 30", "maxConnections = 100")
+            .replace("18888", "18889");
+
+        let diff = ConfigDiff::new(OLD, &new, &user);
+        assert!(diff.upstream_changed());
+        assert_eq!(diff.conflicts(), vec![(3, "  maxConnections = 30")]);
+
+        let rendered = diff.render("v1", "v2");
+        assert!(rendered.contains("+  estimateEnergy = true"));
+        assert!(rendered.contains("+  listen.port = 18889"));
+        assert!(rendered.contains("手动合并"));
+    }
+
+    #[test]
+    fn test_config_diff_unchanged() {
+        let diff = ConfigDiff::new(OLD, OLD, OLD);
+        assert!(!diff.upstream_changed());
+        assert!(diff.conflicts().is_empty());
+        assert!(diff.render("v1", "v1").contains("(无变化)"));
+    }
+}
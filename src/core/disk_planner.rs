@@ -0,0 +1,200 @@
+use crate::constants::{
+    DISK_BASE_RESERVE_GB, DISK_GROWTH_HEADROOM_PERCENT, RECOMMENDED_DISK_GB,
+    SNAPSHOT_EXTRACT_RATIO_PERCENT,
+};
+use crate::error::{Result, TronCtlError};
+use crate::utils::fs;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+const GB: u64 = 1024 * 1024 * 1024;
+
+/// 快照落盘方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapshotMode {
+    /// 不下载快照，从创世块开始同步
+    None,
+    /// 流式下载并解压，只占用解压后的空间
+    Stream,
+    /// 完整下载并校验后解压，压缩包与解压数据同时占用空间
+    VerifyChecksum,
+}
+
+/// 数据目录所在挂载点的空间规划
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiskPlan {
+    pub mount_point: PathBuf,
+    pub available_bytes: u64,
+    pub required_bytes: u64,
+    pub recommended_bytes: u64,
+}
+
+impl DiskPlan {
+    /// 可用空间满足最低需求
+    pub fn fits(&self) -> bool {
+        self.available_bytes >= self.required_bytes
+    }
+
+    /// 可用空间满足推荐值（含链增长余量）
+    pub fn comfortable(&self) -> bool {
+        self.available_bytes >= self.recommended_bytes
+    }
+
+    /// 空间不足时返回错误，低于推荐值时输出警告
+    pub fn enforce(&self) -> Result<()> {
+        if !self.fits() {
+            return Err(TronCtlError::InsufficientDisk {
+                mount_point: self.mount_point.display().to_string(),
+                required: self.required_bytes.div_ceil(GB),
+                available: self.available_bytes / GB,
+            });
+        }
+
+        if !self.comfortable() {
+            warn!(
+                "磁盘空间偏紧: {} 可用 {}GB，推荐 {}GB 以容纳后续链增长",
+                self.mount_point.display(),
+                self.available_bytes / GB,
+                self.recommended_bytes.div_ceil(GB)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+pub struct DiskPlanner;
+
+impl DiskPlanner {
+    /// 为数据目录生成空间规划，`snapshot_bytes` 为快照压缩包大小
+    pub fn plan(data_dir: &Path, snapshot_bytes: u64, mode: SnapshotMode) -> Result<DiskPlan> {
+        let mount_point = fs::find_mount(data_dir)?
+            .map(|m| m.mount_point)
+            .unwrap_or_else(|| fs::existing_ancestor(data_dir));
+        let available_bytes = fs::get_disk_free_bytes(data_dir)?;
+        let required_bytes = Self::required_bytes(snapshot_bytes, mode);
+
+        let plan = DiskPlan {
+            mount_point,
+            available_bytes,
+            required_bytes,
+            recommended_bytes: Self::recommended_bytes(required_bytes, mode),
+        };
+
+        info!(
+            "磁盘规划: 挂载点 {} 可用 {}GB，需要 {}GB，推荐 {}GB",
+            plan.mount_point.display(),
+            plan.available_bytes / GB,
+            plan.required_bytes.div_ceil(GB),
+            plan.recommended_bytes.div_ceil(GB)
+        );
+
+        Ok(plan)
+    }
+
+    /// 按快照大小与落盘方式计算最低所需空间（字节）
+    pub fn required_bytes(snapshot_bytes: u64, mode: SnapshotMode) -> u64 {
+        let reserve = DISK_BASE_RESERVE_GB * GB;
+        let extracted = snapshot_bytes / 100 * SNAPSHOT_EXTRACT_RATIO_PERCENT;
+
+        match mode {
+            SnapshotMode::None => reserve,
+            SnapshotMode::Stream => extracted + reserve,
+            SnapshotMode::VerifyChecksum => snapshot_bytes + extracted + reserve,
+        }
+    }
+
+    /// 推荐空间：不下载快照时需从头同步完整链，否则在所需空间上预留增长余量
+    pub fn recommended_bytes(required_bytes: u64, mode: SnapshotMode) -> u64 {
+        match mode {
+            SnapshotMode::None => RECOMMENDED_DISK_GB * GB,
+            _ => required_bytes / 100 * (100 + DISK_GROWTH_HEADROOM_PERCENT),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plan(available_gb: u64, required_gb: u64, recommended_gb: u64) -> DiskPlan {
+        DiskPlan {
+            mount_point: PathBuf::from("/data"),
+            available_bytes: available_gb * GB,
+            required_bytes: required_gb * GB,
+            recommended_bytes: recommended_gb * GB,
+        }
+    }
+
+    #[test]
+    fn test_required_bytes_by_mode() {
+        let snapshot = 100 * GB;
+        let extracted = snapshot / 100 * SNAPSHOT_EXTRACT_RATIO_PERCENT;
+        let reserve = DISK_BASE_RESERVE_GB * GB;
+
+        assert_eq!(
+            DiskPlanner::required_bytes(snapshot, SnapshotMode::None),
+            reserve
+        );
+        assert_eq!(
+            DiskPlanner::required_bytes(snapshot, SnapshotMode::Stream),
+            extracted + reserve
+        );
+        // 校验模式需要同时容纳压缩包与解压数据
+        assert_eq!(
+            DiskPlanner::required_bytes(snapshot, SnapshotMode::VerifyChecksum),
+            snapshot + extracted + reserve
+        );
+    }
+
+    #[test]
+    fn test_recommended_bytes() {
+        assert_eq!(
+            DiskPlanner::recommended_bytes(GB, SnapshotMode::None),
+            RECOMMENDED_DISK_GB * GB
+        );
+        assert!(DiskPlanner::recommended_bytes(100 * GB, SnapshotMode::Stream) > 100 * GB);
+    }
+
+    #[test]
+    fn test_enforce_blocks_when_insufficient() {
+        let err = plan(50, 60, 72).enforce().unwrap_err();
+        assert!(matches!(
+            err,
+            TronCtlError::InsufficientDisk {
+                required: 60,
+                available: 50,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_enforce_warns_only_below_recommended() {
+        let tight = plan(65, 60, 72);
+        assert!(tight.fits());
+        assert!(!tight.comfortable());
+        assert!(tight.enforce().is_ok());
+
+        assert!(plan(100, 60, 72).comfortable());
+    }
+
+    #[test]
+    fn test_plan_resolves_mount() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let data_dir = temp_dir.path().join("data");
+
+        let plan = DiskPlanner::plan(&data_dir, GB, SnapshotMode::Stream).unwrap();
+        assert!(
+            temp_dir
+                .path()
+                .canonicalize()
+                .unwrap()
+                .starts_with(&plan.mount_point)
+        );
+        assert_eq!(
+            plan.required_bytes,
+            DiskPlanner::required_bytes(GB, SnapshotMode::Stream)
+        );
+    }
+}
@@ -1,11 +1,23 @@
+use crate::core::ReleaseChecker;
 use crate::error::{Result, TronCtlError};
-use crate::utils::ui;
+use crate::models::checksum::Checksum;
+use crate::models::node_config::DownloadConfig;
+use crate::utils::hash::{self, Hasher};
+use crate::utils::{network, ui};
 use futures::StreamExt;
+use futures::stream::BoxStream;
+use rand::Rng;
 use reqwest::Client;
+use reqwest::header::HeaderMap;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use tokio::fs::File;
 use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+use tokio_util::bytes::Bytes;
 use tracing::{debug, info, warn};
 
 /// 断点续传进度记录
@@ -14,9 +26,53 @@ struct DownloadProgress {
     url: String,
     total_size: u64,
     chunk_size: u64,
+    /// 分块直接写入目标文件；旧版本写入 `.partN` 文件，其进度无法继续
+    #[serde(default)]
+    in_place: bool,
+    /// 开始下载时远程文件的 ETag 与 Last-Modified
+    #[serde(flatten)]
+    validators: Validators,
     chunks: Vec<ChunkProgress>,
 }
 
+/// 远程文件的版本标识，用于确认续传前后是同一个文件
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+struct Validators {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    etag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    last_modified: Option<String>,
+}
+
+impl Validators {
+    fn from_response(response: &reqwest::Response) -> Self {
+        let header = |name| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string)
+        };
+        Self {
+            etag: header(reqwest::header::ETAG),
+            last_modified: header(reqwest::header::LAST_MODIFIED),
+        }
+    }
+
+    /// `If-Range` 的取值：强 ETag 优先，其次 Last-Modified；弱 ETag 不能用于 `If-Range`
+    fn if_range(&self) -> Option<&str> {
+        self.etag
+            .as_deref()
+            .filter(|etag| !etag.starts_with("W/"))
+            .or(self.last_modified.as_deref())
+    }
+
+    /// 远程文件是否仍是记录中的版本；服务器不提供任何标识时无法判断，视为未变化
+    fn unchanged_since(&self, saved: &Validators) -> bool {
+        (self.etag.is_none() && self.last_modified.is_none()) || self == saved
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct ChunkProgress {
     index: usize,
@@ -33,74 +89,106 @@ enum ChunkStatus {
     Completed,
 }
 
+/// 提供同一文件的下载镜像
+#[derive(Debug, Clone)]
+pub struct Mirror {
+    pub url: String,
+    pub headers: HeaderMap,
+    /// 实测吞吐（字节/秒），用于按比例分配连接；0 表示未知
+    pub weight: u64,
+}
+
+#[derive(Clone)]
 pub struct Downloader {
     client: Client,
+    retry: RetryPolicy,
+    /// 分块下载的并发连接数
+    connections: usize,
+    chunk_size: u64,
+    /// 所有连接共享的限速器
+    limiter: Option<Arc<RateLimiter>>,
 }
 
 impl Downloader {
     pub fn new() -> Self {
+        Self::with_headers(HeaderMap::new())
+    }
+
+    /// 每个请求都附加指定 HTTP 头（如快照服务器的认证信息）
+    pub fn with_headers(headers: HeaderMap) -> Self {
         Self {
             client: Client::builder()
                 // 不设置全局超时，允许大文件长时间下载
                 // 只设置连接超时，防止一直连不上服务器
                 .connect_timeout(std::time::Duration::from_secs(60))
                 .user_agent("tronctl/0.1.0")
+                .default_headers(headers)
                 .build()
                 .expect("Failed to build HTTP client"),
+            retry: RetryPolicy::default(),
+            connections: num_cpus::get(),
+            chunk_size: crate::constants::DEFAULT_DOWNLOAD_CHUNK_SIZE,
+            limiter: None,
         }
     }
 
-    /// 从 GitHub Releases 下载 FullNode.jar
-    pub async fn download_fullnode(&self, version: Option<String>, dest: &Path) -> Result<()> {
-        let release_url = if let Some(ver) = version {
-            format!(
-                "https://github.com/{}/releases/download/{}/FullNode.jar",
-                crate::constants::GITHUB_REPO,
-                ver
-            )
-        } else {
-            let latest = self.get_latest_release().await?;
-            info!("使用最新版本: {}", latest);
-            format!(
-                "https://github.com/{}/releases/download/{}/FullNode.jar",
-                crate::constants::GITHUB_REPO,
+    /// 应用 `[download]` 中的并发连接数、分块大小与限速
+    pub fn with_config(mut self, config: &DownloadConfig) -> Self {
+        self.connections = config.connections();
+        self.chunk_size = config.chunk_size();
+        self.limiter = config
+            .limit_rate()
+            .map(|rate| Arc::new(RateLimiter::new(rate)));
+        self
+    }
+
+    /// 从 GitHub Releases（或配置的镜像）下载 FullNode.jar，提供 `expected` 时下载后校验
+    pub async fn download_fullnode(
+        &self,
+        releases: &ReleaseChecker,
+        version: Option<String>,
+        dest: &Path,
+        expected: Option<&Checksum>,
+    ) -> Result<()> {
+        let tag = match version {
+            Some(tag) => tag,
+            None => {
+                let latest = releases.latest().await?.tag_name;
+                info!("使用最新版本: {}", latest);
                 latest
-            )
+            }
         };
 
+        let release_url = releases.asset_url(&tag, crate::constants::FULLNODE_ASSET);
         info!("下载 FullNode.jar: {}", release_url);
 
-        self.download_with_progress(&release_url, dest, None).await
+        self.download_with_progress(&release_url, dest, expected)
+            .await
     }
 
-    /// 获取最新 Release 版本
-    async fn get_latest_release(&self) -> Result<String> {
-        #[derive(serde::Deserialize)]
-        struct Release {
-            tag_name: String,
+    /// 流式下载大文件并显示进度（自动选择单线程或多线程）
+    ///
+    /// 续传过程中远程文件被替换时，丢弃已下载的内容重新开始
+    pub async fn download_with_progress(
+        &self,
+        url: &str,
+        dest: &Path,
+        expected: Option<&Checksum>,
+    ) -> Result<()> {
+        match self.try_download(url, dest, expected).await {
+            Err(TronCtlError::RemoteFileChanged(changed)) => {
+                warn!("远程文件已更新，重新开始下载: {}", changed);
+                self.try_download(url, dest, expected).await
+            }
+            result => result,
         }
-
-        let resp = self
-            .client
-            .get(crate::constants::GITHUB_API_RELEASES)
-            .header("User-Agent", "tronctl/0.1.0")
-            .send()
-            .await?;
-
-        let releases: Vec<Release> = resp.json().await?;
-
-        releases
-            .first()
-            .map(|r| r.tag_name.clone())
-            .ok_or_else(|| TronCtlError::DownloadFailed("未找到可用版本".to_string()))
     }
 
-    /// 流式下载大文件并显示进度（自动选择单线程或多线程）
-    pub async fn download_with_progress(
+    async fn try_download(
         &self,
         url: &str,
         dest: &Path,
-        expected_md5: Option<&str>,
+        expected: Option<&Checksum>,
     ) -> Result<()> {
         debug!("开始下载: {} -> {:?}", url, dest);
 
@@ -114,35 +202,61 @@ impl Downloader {
             )));
         }
 
-        let total_size = head_response
-            .content_length()
+        let total_size = network::head_content_length(&head_response)
+            .or(head_response.content_length())
             .ok_or_else(|| TronCtlError::DownloadFailed("无法获取文件大小".to_string()))?;
 
         // 检查最终响应是否支持 Range 请求（重定向后的实际文件服务器）
-        let supports_range = head_response
-            .headers()
-            .get("accept-ranges")
-            .and_then(|v| v.to_str().ok())
-            .map(|v| v == "bytes")
-            .unwrap_or(false);
+        let supports_range = supports_range(&head_response);
 
         // 如果支持 Range 且文件 > 10MB，使用多线程下载
         if supports_range && total_size > 10 * 1024 * 1024 {
             debug!("使用多线程下载，文件大小: {} bytes", total_size);
-            self.download_multithreaded(url, dest, total_size, expected_md5)
+            let source = ChunkSource {
+                url: url.to_string(),
+                client: self.client.clone(),
+                weight: 0,
+                validators: Validators::from_response(&head_response),
+            };
+            self.download_multithreaded(&[source], dest, total_size, expected)
                 .await
         } else {
             debug!("使用单线程下载");
-            self.download_single_thread(url, dest, expected_md5).await
+            self.download_single_thread(url, dest, expected).await
         }
     }
 
-    /// 单线程下载（支持断点续传）
+    /// 单线程下载（支持断点续传），失败后退避重试并从已下载位置继续，直至用尽重试预算
     async fn download_single_thread(
         &self,
         url: &str,
         dest: &Path,
-        expected_md5: Option<&str>,
+        expected: Option<&Checksum>,
+    ) -> Result<()> {
+        let mut attempt = 0;
+        loop {
+            match self.download_single_thread_once(url, dest, expected).await {
+                Err(e) if is_retryable(&e) && attempt < self.retry.source_budget => {
+                    attempt += 1;
+                    let delay = self.retry.delay(attempt);
+                    warn!(
+                        "下载失败，{:.1} 秒后第 {} 次重试: {}",
+                        delay.as_secs_f64(),
+                        attempt,
+                        e
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    async fn download_single_thread_once(
+        &self,
+        url: &str,
+        dest: &Path,
+        expected: Option<&Checksum>,
     ) -> Result<()> {
         // 检查是否支持断点续传，决定起始位置
         let resume_pos = if dest.exists() {
@@ -157,7 +271,7 @@ impl Downloader {
                 )));
             }
 
-            let total = head_response.content_length().unwrap_or(0);
+            let total = network::head_content_length(&head_response).unwrap_or(0);
             if existing_size > 0 && existing_size < total {
                 info!("检测到未完成的下载 ({} bytes)，继续下载...", existing_size);
                 Some(existing_size)
@@ -168,19 +282,55 @@ impl Downloader {
             None
         };
 
+        // 续传时附带开始下载时记录的版本标识，远程文件已变化时服务器返回完整文件
+        let saved = Self::load_progress(dest)
+            .await
+            .ok()
+            .flatten()
+            .filter(|saved| saved.url == url && saved.chunks.is_empty());
+        let if_range = saved
+            .as_ref()
+            .and_then(|saved| saved.validators.if_range().map(str::to_string));
+
         // 发送 GET 请求获取响应（从断点续传位置或从头开始）
         let response = if let Some(pos) = resume_pos {
             let range = format!("bytes={}-", pos);
-            self.client.get(url).header("Range", range).send().await?
+            let mut request = self.client.get(url).header("Range", range);
+            if let Some(if_range) = &if_range {
+                request = request.header(reqwest::header::IF_RANGE, if_range);
+            }
+            request.send().await?
         } else {
             self.client.get(url).send().await?
         };
 
-        if !response.status().is_success() && response.status().as_u16() != 206 {
-            return Err(TronCtlError::DownloadFailed(format!(
-                "HTTP 状态码: {}",
-                response.status()
-            )));
+        let response = response.error_for_status()?;
+
+        // 服务器返回完整文件而非所请求的范围时，从头写入
+        let resume_pos = match resume_pos {
+            Some(_) if response.status() != reqwest::StatusCode::PARTIAL_CONTENT => {
+                if if_range.is_some() {
+                    warn!("远程文件已更新，重新开始下载: {}", url);
+                } else {
+                    warn!("服务器未返回请求的范围，重新开始下载: {}", url);
+                }
+                tokio::fs::remove_file(dest).await?;
+                None
+            }
+            resume_pos => resume_pos,
+        };
+
+        // 记录远程文件的版本标识，供中断后续传时校验
+        if resume_pos.is_none() {
+            let progress = DownloadProgress {
+                url: url.to_string(),
+                total_size: response.content_length().unwrap_or(0),
+                chunk_size: 0,
+                in_place: true,
+                validators: Validators::from_response(&response),
+                chunks: Vec::new(),
+            };
+            Self::save_progress(dest, &progress).await?;
         }
 
         // 从 GET 响应中获取可靠的 content_length
@@ -194,7 +344,7 @@ impl Downloader {
         let mut file = File::options()
             .write(true)
             .create(true)
-            .truncate(false)
+            .truncate(resume_pos.is_none())
             .open(dest)
             .await?;
 
@@ -203,18 +353,31 @@ impl Downloader {
         }
 
         let mut stream = response.bytes_stream();
-        let mut all_bytes = if resume_pos.is_some() && expected_md5.is_some() {
-            tokio::fs::read(dest).await?
-        } else {
-            Vec::new()
-        };
 
-        while let Some(chunk_result) = stream.next().await {
-            let chunk = chunk_result?;
+        // 边下载边计算摘要；续传时先计算已下载部分
+        let mut hasher = expected.map(|checksum| Hasher::new(checksum.algorithm));
+        if resume_pos.is_some()
+            && let Some(hasher) = hasher.as_mut()
+        {
+            let mut existing = File::open(dest).await?;
+            let mut buffer = vec![0u8; 64 * 1024];
+            loop {
+                let n = existing.read(&mut buffer).await?;
+                if n == 0 {
+                    break;
+                }
+                hasher.update(&buffer[..n]);
+            }
+        }
+
+        while let Some(chunk) = next_bytes(&mut stream, self.retry.stall_timeout).await? {
+            if let Some(limiter) = &self.limiter {
+                limiter.acquire(chunk.len() as u64).await;
+            }
             file.write_all(&chunk).await?;
 
-            if expected_md5.is_some() {
-                all_bytes.extend_from_slice(&chunk);
+            if let Some(hasher) = hasher.as_mut() {
+                hasher.update(&chunk);
             }
 
             pb.inc(chunk.len() as u64);
@@ -222,18 +385,11 @@ impl Downloader {
 
         pb.finish_with_message("下载完成");
         file.flush().await?;
+        Self::remove_progress(dest).await?;
 
-        // MD5 校验
-        if let Some(expected) = expected_md5 {
-            let digest = md5::compute(&all_bytes);
-            let actual = format!("{:x}", digest);
-            if actual != expected {
-                return Err(TronCtlError::Md5Mismatch {
-                    expected: expected.to_string(),
-                    actual,
-                });
-            }
-            info!("MD5 校验通过");
+        if let (Some(expected), Some(hasher)) = (expected, hasher) {
+            expected.verify(&hasher.finish())?;
+            info!("{} 校验通过", expected.algorithm.name());
         }
 
         Ok(())
@@ -244,13 +400,6 @@ impl Downloader {
         dest.with_extension("progress")
     }
 
-    /// 获取分块文件路径
-    fn chunk_file(dest: &Path, index: usize) -> PathBuf {
-        let mut chunk_path = dest.to_path_buf();
-        chunk_path.set_extension(format!("part{}", index));
-        chunk_path
-    }
-
     /// 加载下载进度
     async fn load_progress(dest: &Path) -> Result<Option<DownloadProgress>> {
         let progress_path = Self::progress_file(dest);
@@ -265,12 +414,14 @@ impl Downloader {
         Ok(Some(progress))
     }
 
-    /// 保存下载进度
+    /// 保存下载进度：先写临时文件再重命名，中断时不会留下不完整的进度文件
     async fn save_progress(dest: &Path, progress: &DownloadProgress) -> Result<()> {
         let progress_path = Self::progress_file(dest);
         let content = serde_json::to_string_pretty(progress)
             .map_err(|e| TronCtlError::Other(anyhow::anyhow!("序列化进度失败: {}", e)))?;
-        tokio::fs::write(&progress_path, content).await?;
+        let tmp = progress_path.with_extension("progress.tmp");
+        tokio::fs::write(&tmp, content).await?;
+        tokio::fs::rename(&tmp, &progress_path).await?;
         Ok(())
     }
 
@@ -283,248 +434,386 @@ impl Downloader {
         Ok(())
     }
 
-    /// 检查分块文件是否已下载完成
-    async fn is_chunk_complete(dest: &Path, chunk: &ChunkProgress) -> Result<bool> {
-        let chunk_path = Self::chunk_file(dest, chunk.index);
-        if !chunk_path.exists() {
-            return Ok(false);
+    /// 打开目标文件并预分配空间，各分块按偏移直接写入，无需合并
+    async fn open_preallocated(dest: &Path, total_size: u64) -> Result<Arc<std::fs::File>> {
+        let dest = dest.to_path_buf();
+        let file = tokio::task::spawn_blocking(move || -> std::io::Result<std::fs::File> {
+            use fs2::FileExt;
+
+            let file = std::fs::File::options()
+                .write(true)
+                .create(true)
+                .truncate(false)
+                .open(&dest)?;
+            // 不支持 fallocate 的文件系统退化为稀疏文件
+            if let Err(e) = file.allocate(total_size) {
+                debug!("预分配磁盘空间失败，使用稀疏文件: {}", e);
+            }
+            file.set_len(total_size)?;
+            Ok(file)
+        })
+        .await
+        .map_err(|e| TronCtlError::Other(anyhow::anyhow!("预分配任务失败: {}", e)))??;
+        Ok(Arc::new(file))
+    }
+
+    /// 记录各分块已写入的字节数并保存进度；先将数据落盘，保证进度不超过实际写入的内容
+    async fn checkpoint(
+        dest: &Path,
+        file: &Arc<std::fs::File>,
+        progress: &mut DownloadProgress,
+        written: &[AtomicU64],
+    ) -> Result<()> {
+        for (chunk, written) in progress.chunks.iter_mut().zip(written) {
+            chunk.downloaded = written.load(Ordering::SeqCst);
         }
 
-        let metadata = tokio::fs::metadata(&chunk_path).await?;
-        let expected_size = chunk.end - chunk.start + 1;
-        Ok(metadata.len() == expected_size)
+        let file = Arc::clone(file);
+        tokio::task::spawn_blocking(move || file.sync_data())
+            .await
+            .map_err(|e| TronCtlError::Other(anyhow::anyhow!("写入任务失败: {}", e)))??;
+        Self::save_progress(dest, progress).await
     }
 
-    /// 合并所有分块文件
-    async fn merge_chunks(dest: &Path, progress: &DownloadProgress) -> Result<()> {
-        let mut dest_file = File::create(dest).await?;
+    /// 从多个提供相同文件的镜像并行分块下载（支持断点续传）
+    ///
+    /// 服务器不支持 Range 或文件较小时只从第一个镜像单线程下载
+    pub async fn download_from_mirrors(
+        &self,
+        mirrors: &[Mirror],
+        dest: &Path,
+        expected: Option<&Checksum>,
+    ) -> Result<()> {
+        match self
+            .try_download_from_mirrors(mirrors, dest, expected)
+            .await
+        {
+            Err(TronCtlError::RemoteFileChanged(changed)) => {
+                warn!("远程文件已更新，重新开始下载: {}", changed);
+                self.try_download_from_mirrors(mirrors, dest, expected)
+                    .await
+            }
+            result => result,
+        }
+    }
 
-        for chunk in &progress.chunks {
-            let chunk_path = Self::chunk_file(dest, chunk.index);
-            if chunk_path.exists() {
-                let mut chunk_file = File::open(&chunk_path).await?;
-                let mut buffer = vec![0u8; 8192];
-                loop {
-                    let n = chunk_file.read(&mut buffer).await?;
-                    if n == 0 {
-                        break;
-                    }
-                    dest_file.write_all(&buffer[..n]).await?;
+    async fn try_download_from_mirrors(
+        &self,
+        mirrors: &[Mirror],
+        dest: &Path,
+        expected: Option<&Checksum>,
+    ) -> Result<()> {
+        if mirrors.is_empty() {
+            return Err(TronCtlError::DownloadFailed("没有可用的下载源".to_string()));
+        }
+        if mirrors.len() == 1 {
+            return self.download_with_failover(mirrors, dest, expected).await;
+        }
+
+        // 并发探测各镜像，跳过无法连接或不支持 Range 的镜像
+        let probes = mirrors.iter().map(|mirror| async move {
+            let client = Self::with_headers(mirror.headers.clone()).client;
+            let response = match client.head(&mirror.url).send().await {
+                Ok(response) => response,
+                Err(e) => {
+                    warn!("无法连接镜像，跳过: {}: {}", mirror.url, e);
+                    return None;
                 }
+            };
+            let total_size = network::head_content_length(&response);
+            if !response.status().is_success() || !supports_range(&response) {
+                warn!(
+                    "镜像不支持分块下载，跳过: {} (HTTP {})",
+                    mirror.url,
+                    response.status()
+                );
+                return None;
             }
+            Some((
+                total_size?,
+                ChunkSource {
+                    url: mirror.url.clone(),
+                    client,
+                    weight: mirror.weight,
+                    validators: Validators::from_response(&response),
+                },
+            ))
+        });
+        let probed: Vec<(u64, ChunkSource)> = futures::future::join_all(probes)
+            .await
+            .into_iter()
+            .flatten()
+            .collect();
+
+        // 按镜像顺序以第一个可用的镜像为准，大小不一致的镜像不参与；各镜像的 ETag 通常不同，分别记录
+        let Some(&(total_size, _)) = probed.first() else {
+            debug!("没有支持分块下载的镜像，依次从各镜像单线程下载");
+            return self.download_with_failover(mirrors, dest, expected).await;
+        };
+        if total_size <= 10 * 1024 * 1024 {
+            debug!("文件较小，依次从各镜像单线程下载");
+            return self.download_with_failover(mirrors, dest, expected).await;
         }
+        let sources: Vec<ChunkSource> = probed
+            .into_iter()
+            .filter_map(|(size, source)| {
+                if size != total_size {
+                    warn!("镜像与其他镜像的文件大小不一致，跳过: {}", source.url);
+                    return None;
+                }
+                Some(source)
+            })
+            .collect();
 
-        dest_file.flush().await?;
-        Ok(())
+        info!("从 {} 个镜像并行下载", sources.len());
+        self.download_multithreaded(&sources, dest, total_size, expected)
+            .await
+    }
+
+    /// 依次尝试各镜像，某个镜像用尽重试预算后从已下载的位置切换到下一个镜像
+    async fn download_with_failover(
+        &self,
+        mirrors: &[Mirror],
+        dest: &Path,
+        expected: Option<&Checksum>,
+    ) -> Result<()> {
+        let mut last_error = None;
+        for mirror in mirrors {
+            let downloader = Self {
+                client: Self::with_headers(mirror.headers.clone()).client,
+                ..self.clone()
+            };
+            match downloader
+                .download_with_progress(&mirror.url, dest, expected)
+                .await
+            {
+                Err(e) if is_retryable(&e) => {
+                    warn!("从 {} 下载失败，切换到下一个服务器: {}", mirror.url, e);
+                    last_error = Some(e);
+                }
+                result => return result,
+            }
+        }
+
+        Err(last_error
+            .unwrap_or_else(|| TronCtlError::DownloadFailed("没有可用的下载源".to_string())))
     }
 
     /// 多线程分块下载（支持断点续传）
+    ///
+    /// 各下载源按吞吐比例分配连接，从共享队列领取分块；分块在某个源上失败或明显变慢时，
+    /// 保留已下载的部分，交给其他源继续
     async fn download_multithreaded(
         &self,
-        url: &str,
+        sources: &[ChunkSource],
         dest: &Path,
         total_size: u64,
-        expected_md5: Option<&str>,
+        expected: Option<&Checksum>,
     ) -> Result<()> {
-        use std::sync::Arc;
+        let url = sources[0].url.as_str();
 
-        let num_threads = num_cpus::get();
-        let chunk_size = total_size / num_threads as u64;
-
-        // 尝试加载之前的下载进度
-        let mut progress = if let Some(saved) = Self::load_progress(dest).await? {
-            if saved.url == url && saved.total_size == total_size {
+        // 尝试加载之前的下载进度；各镜像的文件相同，任一镜像记录的进度都可继续
+        let saved = Self::load_progress(dest).await?;
+        if let Some(saved) = saved.as_ref().filter(|saved| !saved.in_place) {
+            Self::remove_part_files(dest, saved).await;
+        }
+        let resumable = saved.filter(|saved| {
+            saved.in_place
+                && !saved.chunks.is_empty()
+                && saved.total_size == total_size
+                && sources.iter().any(|source| {
+                    source.url == saved.url && source.validators.unchanged_since(&saved.validators)
+                })
+                && std::fs::metadata(dest).is_ok_and(|m| m.len() == total_size)
+        });
+        let mut progress = match resumable {
+            Some(saved) => {
                 info!("检测到未完成的下载，继续下载...");
                 saved
-            } else {
-                info!("下载源或文件大小已变化，重新开始下载");
-                Self::create_initial_progress(url, total_size, num_threads, chunk_size)
             }
-        } else {
-            Self::create_initial_progress(url, total_size, num_threads, chunk_size)
+            None => {
+                if dest.exists() {
+                    info!("下载源或远程文件已变化，重新开始下载");
+                    tokio::fs::remove_file(dest).await?;
+                }
+                let mut progress = Self::create_initial_progress(url, total_size, self.chunk_size);
+                progress.validators = sources[0].validators.clone();
+                progress
+            }
         };
 
+        let file = Self::open_preallocated(dest, total_size).await?;
         let pb = Arc::new(ui::create_download_progress_bar(total_size));
 
-        // 计算已下载的字节数
-        let mut downloaded_bytes: u64 = 0;
-        for chunk in &progress.chunks {
-            if chunk.status == ChunkStatus::Completed {
-                downloaded_bytes += chunk.end - chunk.start + 1;
-            }
-        }
-        pb.set_position(downloaded_bytes);
-
         // 保存初始进度，确保可以断点续传
         Self::save_progress(dest, &progress).await?;
 
-        let mut tasks = Vec::new();
-
-        for i in 0..num_threads {
-            let chunk = &progress.chunks[i];
-
-            // 跳过已完成的分块
-            if chunk.status == ChunkStatus::Completed {
-                let is_complete = Self::is_chunk_complete(dest, chunk).await?;
-                if is_complete {
-                    continue;
-                }
-            }
-
-            let client = self.client.clone();
-            let url = url.to_string();
-            let dest = dest.to_path_buf();
-            let pb = Arc::clone(&pb);
-            let chunk = chunk.clone();
-
-            let task = tokio::spawn(async move {
-                let chunk_path = Self::chunk_file(&dest, chunk.index);
-
-                // 检查是否已有部分下载
-                let start_pos = if chunk_path.exists() {
-                    let metadata = tokio::fs::metadata(&chunk_path).await?;
-                    metadata.len()
-                } else {
-                    0
+        // 各分块已写入目标文件的字节数
+        let written: Arc<Vec<AtomicU64>> = Arc::new(
+            progress
+                .chunks
+                .iter()
+                .map(|chunk| match chunk.status {
+                    ChunkStatus::Completed => chunk.end - chunk.start + 1,
+                    _ => chunk.downloaded.min(chunk.end - chunk.start + 1),
+                })
+                .map(AtomicU64::new)
+                .collect(),
+        );
+        pb.set_position(written.iter().map(|w| w.load(Ordering::SeqCst)).sum());
+
+        // 待下载分块队列，记录每个分块已失败过的下载源
+        let queue: VecDeque<ChunkJob> = progress
+            .chunks
+            .iter()
+            .filter(|chunk| chunk.status != ChunkStatus::Completed)
+            .map(|chunk| ChunkJob {
+                chunk: chunk.clone(),
+                failed_sources: BTreeSet::new(),
+                last_error: None,
+                last_source: None,
+                failures: 0,
+            })
+            .collect();
+
+        let pending = queue.len();
+        let queue = Arc::new(Mutex::new(ChunkQueue {
+            jobs: queue,
+            exhausted: BTreeSet::new(),
+        }));
+        let remaining = Arc::new(AtomicU64::new(pending as u64));
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+
+        // 按实测吞吐分配连接数；分块较小，先完成的连接继续领取剩余分块
+        let weights: Vec<u64> = sources.iter().map(|source| source.weight).collect();
+        let shares = split_connections(self.connections.min(pending.max(1)), &weights);
+        // 连接数少于下载源时，未分到连接的下载源不参与本次下载
+        let active: Vec<(&ChunkSource, usize)> = sources
+            .iter()
+            .zip(shares)
+            .filter(|(_, connections)| *connections > 0)
+            .collect();
+        let mut workers = Vec::new();
+        for (source_index, &(source, connections)) in active.iter().enumerate() {
+            debug!("{} 分配 {} 个连接", source.url, connections);
+
+            // 只有存在其他下载源时才因速度过慢而切换；实测吞吐由该源的所有连接分摊
+            let min_rate = (active.len() > 1 && source.weight > 0)
+                .then(|| source.weight / connections as u64 / MIRROR_SLOW_FRACTION);
+
+            // 同一下载源的所有连接共享重试预算
+            let budget = Arc::new(AtomicU32::new(self.retry.source_budget));
+            for _ in 0..connections {
+                let worker = ChunkWorker {
+                    source_index,
+                    source_count: active.len(),
+                    client: source.client.clone(),
+                    url: source.url.clone(),
+                    if_range: source.validators.if_range().map(str::to_string),
+                    min_rate,
+                    retry: self.retry,
+                    limiter: self.limiter.clone(),
+                    budget: Arc::clone(&budget),
+                    file: Arc::clone(&file),
+                    written: Arc::clone(&written),
+                    queue: Arc::clone(&queue),
+                    remaining: Arc::clone(&remaining),
+                    pb: Arc::clone(&pb),
+                    tx: tx.clone(),
                 };
-
-                let range_start = chunk.start + start_pos;
-                let range = format!("bytes={}-{}", range_start, chunk.end);
-
-                let response = client
-                    .get(&url)
-                    .header("Range", range)
-                    .send()
-                    .await
-                    .map_err(|e| TronCtlError::DownloadFailed(format!("分块下载失败: {}", e)))?;
-
-                if !response.status().is_success() && response.status().as_u16() != 206 {
-                    return Err(TronCtlError::DownloadFailed(format!(
-                        "分块下载失败，状态码: {}",
-                        response.status()
-                    )));
-                }
-
-                let mut chunk_file = File::options()
-                    .write(true)
-                    .create(true)
-                    .truncate(false)
-                    .open(&chunk_path)
-                    .await?;
-
-                if start_pos > 0 {
-                    chunk_file.seek(std::io::SeekFrom::End(0)).await?;
-                }
-
-                let mut stream = response.bytes_stream();
-
-                while let Some(chunk_result) = stream.next().await {
-                    let data = chunk_result.map_err(|e| {
-                        TronCtlError::DownloadFailed(format!("读取数据块失败: {}", e))
-                    })?;
-
-                    chunk_file.write_all(&data).await?;
-                    pb.inc(data.len() as u64);
-                }
-
-                chunk_file.flush().await?;
-
-                Ok::<(), TronCtlError>(())
-            });
-
-            tasks.push((i, task));
+                workers.push(tokio::spawn(worker.run()));
+            }
         }
-
-        // 等待所有分块下载完成
-        for (index, task) in tasks {
-            match task.await {
-                Ok(Ok(())) => {
+        drop(tx);
+
+        // 等待所有分块下载完成，期间按间隔保存进度
+        let mut failure = None;
+        let mut completed = 0;
+        let mut last_save = Instant::now();
+        while completed < pending {
+            match tokio::time::timeout(PROGRESS_SAVE_INTERVAL, rx.recv()).await {
+                Ok(Some((index, Ok(())))) => {
                     progress.chunks[index].status = ChunkStatus::Completed;
-                    Self::save_progress(dest, &progress).await?;
-                }
-                Ok(Err(e)) => {
-                    progress.chunks[index].status = ChunkStatus::Pending;
-                    Self::save_progress(dest, &progress).await?;
-                    return Err(TronCtlError::DownloadFailed(format!(
-                        "下载分块 {} 失败: {}\n\n进度已保存，请重新运行命令继续下载",
-                        index, e
-                    )));
+                    completed += 1;
                 }
-                Err(e) => {
-                    progress.chunks[index].status = ChunkStatus::Pending;
-                    Self::save_progress(dest, &progress).await?;
-                    return Err(TronCtlError::DownloadFailed(format!(
-                        "下载分块 {} 失败: {}\n\n进度已保存，请重新运行命令继续下载",
-                        index, e
-                    )));
+                Ok(Some((index, Err(e)))) => {
+                    failure = Some((index, e));
+                    break;
                 }
+                Ok(None) => break,
+                Err(_) => {}
+            }
+            if last_save.elapsed() >= PROGRESS_SAVE_INTERVAL {
+                Self::checkpoint(dest, &file, &mut progress, &written).await?;
+                last_save = Instant::now();
             }
         }
 
-        pb.finish_with_message("下载完成");
-
-        // 合并所有分块文件
-        info!("合并分块文件...");
-        Self::merge_chunks(dest, &progress).await?;
+        for worker in workers {
+            worker.abort();
+        }
 
-        // 清理分块文件
-        for chunk in &progress.chunks {
-            let chunk_path = Self::chunk_file(dest, chunk.index);
-            if chunk_path.exists() {
-                tokio::fs::remove_file(&chunk_path).await.ok();
-            }
+        if let Some((_, e @ TronCtlError::RemoteFileChanged(_))) = failure {
+            drop(file);
+            Self::remove_progress(dest).await?;
+            tokio::fs::remove_file(dest).await.ok();
+            return Err(e);
+        }
+        if completed < pending {
+            Self::checkpoint(dest, &file, &mut progress, &written).await?;
+            return Err(match failure {
+                Some((index, e)) => TronCtlError::DownloadFailed(format!(
+                    "下载分块 {} 失败: {}\n\n进度已保存，请重新运行命令继续下载",
+                    index, e
+                )),
+                None => TronCtlError::DownloadFailed(
+                    "下载任务异常退出\n\n进度已保存，请重新运行命令继续下载".to_string(),
+                ),
+            });
         }
 
+        pb.finish_with_message("下载完成");
+
         // 删除进度文件
         Self::remove_progress(dest).await?;
 
-        // MD5 校验
-        if let Some(expected) = expected_md5 {
-            let file_content = tokio::fs::read(dest).await?;
-            let digest = md5::compute(&file_content);
-            let actual = format!("{:x}", digest);
-            if actual != expected {
-                return Err(TronCtlError::Md5Mismatch {
-                    expected: expected.to_string(),
-                    actual,
-                });
-            }
-            info!("MD5 校验通过");
+        if let Some(expected) = expected {
+            info!("校验 {}...", expected.algorithm.name());
+            hash::verify_file(dest, expected)?;
+            info!("{} 校验通过", expected.algorithm.name());
         }
 
         Ok(())
     }
 
-    /// 创建初始下载进度
-    fn create_initial_progress(
-        url: &str,
-        total_size: u64,
-        num_threads: usize,
-        chunk_size: u64,
-    ) -> DownloadProgress {
-        let mut chunks = Vec::new();
-
-        for i in 0..num_threads {
-            let start = i as u64 * chunk_size;
-            let end = if i == num_threads - 1 {
-                total_size - 1
-            } else {
-                (i as u64 + 1) * chunk_size - 1
-            };
+    /// 删除旧版本下载进度对应的 `.partN` 分块文件
+    async fn remove_part_files(dest: &Path, progress: &DownloadProgress) {
+        info!("清理旧版本下载遗留的分块文件");
+        for chunk in &progress.chunks {
+            let part = dest.with_extension(format!("part{}", chunk.index));
+            tokio::fs::remove_file(&part).await.ok();
+        }
+    }
 
-            chunks.push(ChunkProgress {
-                index: i,
-                start,
-                end,
+    /// 创建初始下载进度；分块过多时增大分块，限制进度文件的大小
+    fn create_initial_progress(url: &str, total_size: u64, chunk_size: u64) -> DownloadProgress {
+        let chunk_size = chunk_size.max(total_size.div_ceil(MAX_DOWNLOAD_CHUNKS));
+        let chunks = (0..total_size.div_ceil(chunk_size))
+            .map(|i| ChunkProgress {
+                index: i as usize,
+                start: i * chunk_size,
+                end: ((i + 1) * chunk_size).min(total_size) - 1,
                 downloaded: 0,
                 status: ChunkStatus::Pending,
-            });
-        }
+            })
+            .collect();
 
         DownloadProgress {
             url: url.to_string(),
             total_size,
             chunk_size,
+            in_place: true,
+            validators: Validators::default(),
             chunks,
         }
     }
@@ -535,27 +824,58 @@ impl Downloader {
         &self.client
     }
 
-    /// 流式下载并解压 .tar.gz 文件
+    /// 流式下载并解压 .tar.gz 文件，同时对压缩数据流计算摘要；校验失败时删除已解压的内容
+    ///
+    /// 连接中断时按重试策略退避，用 Range 请求从已接收的偏移继续，解压器状态保留在内存中；
+    /// 某个镜像用尽重试预算后从同一偏移切换到下一个镜像。
+    /// gzip 解压状态无法保存到磁盘，进程中断后必须重新下载整个压缩包，只跳过断点记录中已完整写入的文件
     pub async fn download_and_extract_tgz(
         &self,
-        url: &str,
+        mirrors: &[Mirror],
         dest_dir: &Path,
-        expected_md5: Option<&str>,
+        expected: Option<&Checksum>,
     ) -> Result<()> {
         use async_compression::tokio::bufread::GzipDecoder;
         use futures::StreamExt;
         use tokio_util::io::StreamReader;
 
-        info!("开始流式下载并解压: {} -> {:?}", url, dest_dir);
-
-        let response = self.client.get(url).send().await?;
-
-        if !response.status().is_success() {
-            return Err(TronCtlError::DownloadFailed(format!(
-                "HTTP 状态码: {}",
-                response.status()
-            )));
+        // 依次连接各镜像，从第一个可用的镜像开始读取
+        let mut sources: Vec<StreamSource> = mirrors
+            .iter()
+            .map(|mirror| StreamSource {
+                url: mirror.url.clone(),
+                client: Self::with_headers(mirror.headers.clone()).client,
+            })
+            .collect();
+        let mut last_error = None;
+        let mut response = None;
+        while !sources.is_empty() {
+            match sources[0].client.get(&sources[0].url).send().await {
+                Ok(resp) if resp.status().is_success() => {
+                    response = Some(resp);
+                    break;
+                }
+                Ok(resp) => {
+                    warn!("连接 {} 失败: HTTP {}", sources[0].url, resp.status());
+                    last_error = Some(TronCtlError::DownloadFailed(format!(
+                        "HTTP 状态码: {}",
+                        resp.status()
+                    )));
+                }
+                Err(e) => {
+                    warn!("连接 {} 失败: {}", sources[0].url, e);
+                    last_error = Some(e.into());
+                }
+            }
+            sources.remove(0);
         }
+        let Some(response) = response else {
+            return Err(last_error
+                .unwrap_or_else(|| TronCtlError::DownloadFailed("没有可用的下载源".to_string())));
+        };
+        let url = sources[0].url.clone();
+
+        info!("开始流式下载并解压: {} -> {:?}", url, dest_dir);
 
         let total_size = response.content_length().unwrap_or(0);
         let pb = ui::create_download_progress_bar(total_size);
@@ -566,13 +886,54 @@ impl Downloader {
             .canonicalize()
             .map_err(|e| TronCtlError::Other(anyhow::anyhow!("无效的目标路径: {}", e)))?;
 
-        // 将字节流转换为 AsyncRead，同时更新进度条
-        let stream = response.bytes_stream().map(move |result| {
+        let checkpoint_path = dest_dir_canonical.join(EXTRACT_CHECKPOINT_FILE);
+        let validators = Validators::from_response(&response);
+        let checkpoint = ExtractCheckpoint::load(&checkpoint_path, &url, &validators);
+        if !checkpoint.completed.is_empty() {
+            warn!(
+                "检测到未完成的流式解压（已写入 {} 个文件）：流式解压无法从中断位置继续，将重新下载整个压缩包，只跳过已写入的文件",
+                checkpoint.completed.len()
+            );
+        }
+
+        // 压缩数据流经过解压器前计算摘要，无需在磁盘上保留压缩包
+        let hasher = Arc::new(Mutex::new(expected.map(|c| Hasher::new(c.algorithm))));
+        let stream_hasher = hasher.clone();
+
+        // 将字节流转换为 AsyncRead，同时限速并更新进度条；连接中断时自动续传
+        let limiter = self.limiter.clone();
+        let offset = Arc::new(AtomicU64::new(0));
+        let stream = ResumableStream {
+            sources,
+            current: 0,
+            if_range: Some(validators.if_range().map(str::to_string)),
+            total: total_size,
+            offset: offset.clone(),
+            body: Some(response.bytes_stream().boxed()),
+            failures: 0,
+            spent: 0,
+            retry: self.retry,
+        }
+        .into_stream()
+        .then(move |result| {
+            let limiter = limiter.clone();
+            async move {
+                if let (Ok(chunk), Some(limiter)) = (&result, limiter) {
+                    limiter.acquire(chunk.len() as u64).await;
+                }
+                result
+            }
+        })
+        .map(move |result| {
             if let Ok(chunk) = &result {
                 pb_clone.inc(chunk.len() as u64);
+                if let Some(hasher) = stream_hasher.lock().unwrap().as_mut() {
+                    hasher.update(chunk);
+                }
             }
-            result.map_err(std::io::Error::other)
-        });
+            result
+        })
+        .boxed();
 
         let reader = StreamReader::new(stream);
 
@@ -580,87 +941,64 @@ impl Downloader {
         let gzip_decoder = GzipDecoder::new(tokio::io::BufReader::new(reader));
 
         // 在独立线程中进行 tar 解压（tar 是阻塞操作）
-        let dest_dir = dest_dir_canonical;
+        let dest_dir = dest_dir_canonical.clone();
         let extract_task = tokio::task::spawn_blocking(move || {
-            use std::path::Component;
             use tokio_util::io::SyncIoBridge;
 
             let sync_reader = SyncIoBridge::new(gzip_decoder);
             let mut archive = tar::Archive::new(sync_reader);
-
-            // 安全解压：验证每个文件的路径
-            for entry in archive.entries()? {
-                let mut entry = entry?;
-                let path = entry.path()?;
-
-                // 1. 检查路径是否包含 .. 组件（父目录引用）
-                for component in path.components() {
-                    if matches!(component, Component::ParentDir) {
-                        return Err(std::io::Error::new(
-                            std::io::ErrorKind::PermissionDenied,
-                            format!("检测到路径遍历攻击（包含 ..）: {:?}", path),
-                        ));
-                    }
-                }
-
-                // 2. 检查是否为绝对路径
-                if path.is_absolute() {
-                    return Err(std::io::Error::new(
-                        std::io::ErrorKind::PermissionDenied,
-                        format!("拒绝解压绝对路径: {:?}", path),
-                    ));
-                }
-
-                // 3. 构造完整路径并验证
-                let full_path = dest_dir.join(&path);
-
-                // 4. 验证解压路径确实在目标目录内
-                let path_to_check = if full_path.exists() {
-                    full_path.canonicalize().map_err(|e| {
-                        std::io::Error::other(format!("无法规范化路径 {:?}: {}", full_path, e))
-                    })?
-                } else {
-                    // 确保父目录存在
-                    if let Some(parent) = full_path.parent() {
-                        std::fs::create_dir_all(parent)?;
-                    }
-                    // 对于不存在的文件，验证其父目录在目标目录内
-                    if let Some(parent) = full_path.parent() {
-                        parent.canonicalize().map_err(|e| {
-                            std::io::Error::other(format!("无法规范化父目录: {}", e))
-                        })?
-                    } else {
-                        return Err(std::io::Error::new(
-                            std::io::ErrorKind::InvalidInput,
-                            "无效的文件路径",
-                        ));
-                    }
-                };
-
-                // 确保路径在目标目录内
-                if !path_to_check.starts_with(&dest_dir) {
-                    return Err(std::io::Error::new(
-                        std::io::ErrorKind::PermissionDenied,
-                        format!("路径在目标目录外: {:?} -> {:?}", path, path_to_check),
-                    ));
-                }
-
-                // 安全解压
-                entry.unpack(&full_path)?;
+            let mut checkpoint = checkpoint;
+            // 记录解压出的顶层路径，校验失败时据此清理
+            let mut extracted = BTreeSet::new();
+
+            let result = unpack_entries(
+                &mut archive,
+                &dest_dir,
+                &mut checkpoint,
+                &checkpoint_path,
+                &mut extracted,
+            );
+            if result.is_err()
+                && let Err(e) = checkpoint.save(&checkpoint_path)
+            {
+                warn!("无法保存解压断点: {}", e);
             }
+            result?;
 
-            Ok::<_, std::io::Error>(())
+            // tar 结束标记之后可能还有填充数据，读完整个流才能得到完整摘要
+            let mut decoder = archive.into_inner();
+            std::io::copy(&mut decoder, &mut std::io::sink())?;
+            let mut remaining = SyncIoBridge::new(decoder.into_inner().into_inner());
+            std::io::copy(&mut remaining, &mut std::io::sink())?;
+
+            Ok::<_, std::io::Error>(extracted)
         });
 
-        extract_task
+        let extracted = extract_task
             .await
             .map_err(|e| TronCtlError::Other(anyhow::anyhow!("解压任务失败: {}", e)))??;
 
         pb.finish_with_message("下载并解压完成");
-
-        // MD5 校验提示
-        if expected_md5.is_some() {
-            warn!("流式解压模式下暂不支持 MD5 校验，建议通过服务器 HTTPS 保证文件完整性");
+        let checkpoint_path = dest_dir_canonical.join(EXTRACT_CHECKPOINT_FILE);
+        tokio::fs::remove_file(&checkpoint_path).await.ok();
+
+        let hasher = hasher.lock().unwrap().take();
+        if let (Some(expected), Some(hasher)) = (expected, hasher) {
+            if let Err(e) = expected.verify(&hasher.finish()) {
+                warn!("{} 校验失败，删除已解压的数据", expected.algorithm.name());
+                for path in &extracted {
+                    let removed = if path.is_dir() {
+                        tokio::fs::remove_dir_all(path).await
+                    } else {
+                        tokio::fs::remove_file(path).await
+                    };
+                    if let Err(err) = removed {
+                        warn!("无法删除 {:?}: {}", path, err);
+                    }
+                }
+                return Err(e);
+            }
+            info!("{} 校验通过", expected.algorithm.name());
         }
 
         info!("流式解压完成");
@@ -668,34 +1006,833 @@ impl Downloader {
     }
 }
 
-impl Default for Downloader {
+/// 重试等待时间的上限
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(30);
+
+/// 下载失败后的重试策略
+#[derive(Debug, Clone, Copy)]
+struct RetryPolicy {
+    /// 同一分块在同一服务器上的最大连续重试次数
+    chunk_retries: u32,
+    /// 单个服务器在一次下载中可消耗的重试总数，用尽后切换到其他服务器
+    source_budget: u32,
+    /// 首次重试前的等待时间，之后每次翻倍
+    base_delay: Duration,
+    /// 超过该时间未收到任何数据即视为连接停滞
+    stall_timeout: Duration,
+}
+
+impl Default for RetryPolicy {
     fn default() -> Self {
-        Self::new()
+        Self {
+            chunk_retries: 3,
+            source_budget: 10,
+            base_delay: Duration::from_secs(1),
+            stall_timeout: Duration::from_secs(30),
+        }
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use tempfile::TempDir;
-
-    #[test]
-    fn test_downloader_new() {
-        let downloader = Downloader::new();
-        assert!(std::ptr::addr_of!(downloader.client) as usize != 0);
+impl RetryPolicy {
+    /// 第 `attempt` 次重试前的等待时间：指数退避并加入最多一半的随机抖动，避免各连接同时重试
+    fn delay(&self, attempt: u32) -> Duration {
+        let backoff = self
+            .base_delay
+            .saturating_mul(1 << attempt.saturating_sub(1).min(16))
+            .min(RETRY_MAX_DELAY);
+        let jitter = rand::rng().random_range(0..=backoff.as_millis() as u64 / 2);
+        backoff + Duration::from_millis(jitter)
     }
+}
 
-    #[test]
-    fn test_downloader_default() {
-        let downloader = Downloader::default();
-        assert!(std::ptr::addr_of!(downloader.client) as usize != 0);
+/// 网络错误、停滞与服务器端错误可以重试；4xx、校验失败与本地 IO 错误重试无意义
+fn is_retryable(e: &TronCtlError) -> bool {
+    match e {
+        TronCtlError::HttpError(e) => e.status().is_none_or(|status| {
+            status.is_server_error()
+                || status == reqwest::StatusCode::REQUEST_TIMEOUT
+                || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+        }),
+        TronCtlError::DownloadFailed(_) => true,
+        _ => false,
     }
+}
 
-    #[test]
-    fn test_downloader_client_ref() {
-        let downloader = Downloader::new();
-        let client = downloader.client();
-        assert!(std::ptr::addr_of!(*client) as usize != 0);
+/// 读取下一个数据块，超过 `stall_timeout` 未收到数据视为连接停滞
+async fn next_bytes<S>(stream: &mut S, stall_timeout: Duration) -> Result<Option<Bytes>>
+where
+    S: futures::Stream<Item = reqwest::Result<Bytes>> + Unpin,
+{
+    match tokio::time::timeout(stall_timeout, stream.next()).await {
+        Ok(Some(Ok(data))) => Ok(Some(data)),
+        Ok(Some(Err(e))) => Err(TronCtlError::DownloadFailed(format!(
+            "读取数据块失败: {}",
+            e
+        ))),
+        Ok(None) => Ok(None),
+        Err(_) => Err(TronCtlError::DownloadFailed(format!(
+            "连接停滞: {} 秒未收到数据",
+            stall_timeout.as_secs()
+        ))),
+    }
+}
+
+/// 分块下载期间两次保存进度的最小间隔；中断后未记录的进度会重新下载
+const PROGRESS_SAVE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// 单次下载的最大分块数
+const MAX_DOWNLOAD_CHUNKS: u64 = 1 << 18;
+
+/// 分块数据累积到该大小后写入目标文件
+const WRITE_BUFFER_SIZE: usize = 256 * 1024;
+
+/// 所有连接共享的令牌桶限速器
+struct RateLimiter {
+    /// 每秒允许的字节数，同时作为突发上限
+    rate: u64,
+    /// 当前可用字节数（可为负，表示需要等待）与上次补充时间
+    state: Mutex<(f64, Instant)>,
+}
+
+impl RateLimiter {
+    fn new(rate: u64) -> Self {
+        Self {
+            rate,
+            state: Mutex::new((rate as f64, Instant::now())),
+        }
+    }
+
+    /// 消耗 `bytes` 字节的额度，额度不足时等待
+    async fn acquire(&self, bytes: u64) {
+        let wait = {
+            let mut state = self.state.lock().unwrap();
+            let (tokens, last) = &mut *state;
+            let now = Instant::now();
+            *tokens = (*tokens + now.duration_since(*last).as_secs_f64() * self.rate as f64)
+                .min(self.rate as f64)
+                - bytes as f64;
+            *last = now;
+            (*tokens < 0.0).then(|| Duration::from_secs_f64(-*tokens / self.rate as f64))
+        };
+        if let Some(wait) = wait {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+/// 按权重将 `total` 个连接分配给各下载源，每个下载源至少一个；权重均为 0 时平均分配。
+/// 连接数少于下载源数时，只有权重最高的 `total` 个下载源各分到一个连接
+fn split_connections(total: usize, weights: &[u64]) -> Vec<usize> {
+    let count = weights.len();
+    if total < count {
+        let mut shares = vec![0; count];
+        let mut order: Vec<usize> = (0..count).collect();
+        order.sort_by_key(|&i| std::cmp::Reverse(weights[i]));
+        for i in order.into_iter().take(total) {
+            shares[i] = 1;
+        }
+        return shares;
+    }
+
+    let spare = total.saturating_sub(count) as u64;
+    let sum: u64 = weights.iter().sum();
+    let share = |weight: u64| match sum {
+        0 => spare / count as u64,
+        _ => spare * weight / sum,
+    };
+
+    let mut shares: Vec<usize> = weights.iter().map(|w| 1 + share(*w) as usize).collect();
+
+    // 取整剩下的连接按权重从高到低补齐
+    let mut order: Vec<usize> = (0..count).collect();
+    order.sort_by_key(|&i| std::cmp::Reverse(weights[i]));
+    let assigned: usize = shares.iter().sum();
+    for i in order
+        .into_iter()
+        .cycle()
+        .take(total.saturating_sub(assigned))
+    {
+        shares[i] += 1;
+    }
+    shares
+}
+
+/// 连接吞吐低于该镜像每个连接应得份额的该分之一时，将分块交给其他镜像
+const MIRROR_SLOW_FRACTION: u64 = 4;
+
+/// 判断镜像是否变慢的统计窗口，不含等待限速的时间
+const MIRROR_SLOW_WINDOW: Duration = Duration::from_secs(30);
+
+/// 没有可领取的分块时，下载任务的等待间隔
+const CHUNK_QUEUE_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// 响应是否声明支持 Range 请求（重定向后的实际文件服务器）
+fn supports_range(response: &reqwest::Response) -> bool {
+    response
+        .headers()
+        .get("accept-ranges")
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v == "bytes")
+        .unwrap_or(false)
+}
+
+/// 分块下载源
+struct ChunkSource {
+    url: String,
+    client: Client,
+    weight: u64,
+    /// HEAD 响应中的远程文件版本标识
+    validators: Validators,
+}
+
+/// 待下载的分块及已在其上失败过的下载源
+struct ChunkJob {
+    chunk: ChunkProgress,
+    failed_sources: BTreeSet<usize>,
+    last_error: Option<String>,
+    /// 最近领取该分块的下载源及其上连续失败的次数，同一下载源再次领取时继续退避
+    last_source: Option<usize>,
+    failures: u32,
+}
+
+/// 各下载任务共享的分块队列
+struct ChunkQueue {
+    jobs: VecDeque<ChunkJob>,
+    /// 重试预算已用尽、不再领取分块的下载源
+    exhausted: BTreeSet<usize>,
+}
+
+enum Claim {
+    Job(ChunkJob),
+    /// 所有下载源都已用尽预算，无法再下载的分块
+    Orphan(ChunkJob),
+    Idle,
+    /// 当前下载源已用尽预算，其他下载源仍可继续
+    Exhausted,
+}
+
+impl ChunkQueue {
+    fn claim(&mut self, source_index: usize, source_count: usize) -> Claim {
+        if self.exhausted.len() >= source_count {
+            return self.jobs.pop_front().map_or(Claim::Idle, Claim::Orphan);
+        }
+        if self.exhausted.contains(&source_index) {
+            return Claim::Exhausted;
+        }
+
+        // 优先领取未在当前下载源上失败过的分块；其他下载源都已失败或用尽预算时，由当前下载源继续重试
+        let preferred = self
+            .jobs
+            .iter()
+            .position(|job| !job.failed_sources.contains(&source_index));
+        let fallback = || {
+            self.jobs.iter().position(|job| {
+                (0..source_count).all(|i| {
+                    i == source_index
+                        || job.failed_sources.contains(&i)
+                        || self.exhausted.contains(&i)
+                })
+            })
+        };
+        preferred
+            .or_else(fallback)
+            .and_then(|pos| self.jobs.remove(pos))
+            .map_or(Claim::Idle, Claim::Job)
+    }
+}
+
+/// 单次分块下载的结果
+enum ChunkOutcome {
+    Completed,
+    /// 吞吐过低且有其他可用下载源，放弃当前分块，记录实际吞吐（字节/秒）
+    Slow(u64),
+}
+
+/// 绑定到单个下载源的下载任务，循环从共享队列领取分块
+struct ChunkWorker {
+    source_index: usize,
+    source_count: usize,
+    client: Client,
+    url: String,
+    /// 随 Range 请求发送的 `If-Range`，远程文件变化时服务器返回完整文件而非 206
+    if_range: Option<String>,
+    /// 低于该吞吐（字节/秒）时放弃当前分块
+    min_rate: Option<u64>,
+    retry: RetryPolicy,
+    limiter: Option<Arc<RateLimiter>>,
+    /// 下载源剩余的重试次数
+    budget: Arc<AtomicU32>,
+    /// 预分配的目标文件，各分块按偏移写入
+    file: Arc<std::fs::File>,
+    /// 各分块已写入的字节数
+    written: Arc<Vec<AtomicU64>>,
+    queue: Arc<Mutex<ChunkQueue>>,
+    remaining: Arc<AtomicU64>,
+    pb: Arc<indicatif::ProgressBar>,
+    tx: tokio::sync::mpsc::UnboundedSender<(usize, Result<()>)>,
+}
+
+impl ChunkWorker {
+    async fn run(self) {
+        while self.remaining.load(Ordering::SeqCst) > 0 {
+            let claim = self
+                .queue
+                .lock()
+                .unwrap()
+                .claim(self.source_index, self.source_count);
+            let mut job = match claim {
+                Claim::Job(job) => job,
+                Claim::Orphan(job) => {
+                    self.remaining.fetch_sub(1, Ordering::SeqCst);
+                    let reason = job
+                        .last_error
+                        .unwrap_or_else(|| "没有可用的下载源".to_string());
+                    self.tx
+                        .send((job.chunk.index, Err(TronCtlError::DownloadFailed(reason))))
+                        .ok();
+                    continue;
+                }
+                Claim::Idle => {
+                    tokio::time::sleep(CHUNK_QUEUE_POLL_INTERVAL).await;
+                    continue;
+                }
+                Claim::Exhausted => return,
+            };
+
+            if job.last_source != Some(self.source_index) {
+                job.last_source = Some(self.source_index);
+                job.failures = 0;
+            }
+            let index = job.chunk.index;
+            let mut attempt = 0;
+            let reason = loop {
+                let e = match self.fetch_chunk(&job).await {
+                    Ok(ChunkOutcome::Completed) => break None,
+                    Ok(ChunkOutcome::Slow(rate)) => {
+                        break Some(format!("下载速度过慢: {} 字节/秒", rate));
+                    }
+                    Err(e @ TronCtlError::RemoteFileChanged(_)) => {
+                        // 远程文件已被替换，已下载的数据不可再用，其他镜像也无法继续
+                        self.remaining.fetch_sub(1, Ordering::SeqCst);
+                        self.tx.send((index, Err(e))).ok();
+                        return;
+                    }
+                    Err(e) => e,
+                };
+                if !is_retryable(&e) {
+                    // 重试无法恢复的错误，不再从该下载源下载
+                    self.budget.store(0, Ordering::SeqCst);
+                    break Some(e.to_string());
+                }
+                if attempt >= self.retry.chunk_retries || !self.take_retry() {
+                    break Some(e.to_string());
+                }
+
+                attempt += 1;
+                job.failures += 1;
+                let delay = self.retry.delay(job.failures);
+                warn!(
+                    "分块 {} 从 {} 下载失败，{:.1} 秒后第 {} 次重试: {}",
+                    index,
+                    self.url,
+                    delay.as_secs_f64(),
+                    job.failures,
+                    e
+                );
+                tokio::time::sleep(delay).await;
+            };
+
+            let Some(reason) = reason else {
+                self.remaining.fetch_sub(1, Ordering::SeqCst);
+                self.tx.send((index, Ok(()))).ok();
+                continue;
+            };
+
+            // 保留已下载的部分放回队列，优先交给其他下载源继续
+            warn!("分块 {} 暂停从 {} 下载: {}", index, self.url, reason);
+            job.failed_sources.insert(self.source_index);
+            job.last_error = Some(reason);
+            let mut queue = self.queue.lock().unwrap();
+            queue.jobs.push_back(job);
+            if self.budget.load(Ordering::SeqCst) == 0 && queue.exhausted.insert(self.source_index)
+            {
+                warn!("{} 的重试次数已用尽，不再从该服务器下载", self.url);
+            }
+        }
+    }
+
+    /// 消耗下载源的一次重试预算
+    fn take_retry(&self) -> bool {
+        self.budget
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |left| {
+                left.checked_sub(1)
+            })
+            .is_ok()
+    }
+
+    /// 是否还有其他未用尽预算、也未放弃过该分块的下载源可以接手
+    fn has_other_source(&self, job: &ChunkJob) -> bool {
+        let queue = self.queue.lock().unwrap();
+        (0..self.source_count).any(|i| {
+            i != self.source_index
+                && !queue.exhausted.contains(&i)
+                && !job.failed_sources.contains(&i)
+        })
+    }
+
+    /// 下载单个分块并写入目标文件的对应位置，从已写入的位置（可能来自其他镜像）继续
+    async fn fetch_chunk(&self, job: &ChunkJob) -> Result<ChunkOutcome> {
+        let chunk = &job.chunk;
+        let written = &self.written[chunk.index];
+        let range_start = chunk.start + written.load(Ordering::SeqCst);
+        if range_start > chunk.end {
+            return Ok(ChunkOutcome::Completed);
+        }
+        let range = format!("bytes={}-{}", range_start, chunk.end);
+
+        let mut request = self.client.get(&self.url).header("Range", range);
+        if let Some(if_range) = &self.if_range {
+            request = request.header(reqwest::header::IF_RANGE, if_range);
+        }
+        let response = request
+            .send()
+            .await
+            .map_err(|e| TronCtlError::DownloadFailed(format!("分块下载失败: {}", e)))?;
+
+        let response = response.error_for_status()?;
+        if response.status() == reqwest::StatusCode::OK && self.if_range.is_some() {
+            return Err(TronCtlError::RemoteFileChanged(self.url.clone()));
+        }
+        // 按偏移写入时必须确认服务器返回的是请求的范围，而非整个文件
+        if response.status() != reqwest::StatusCode::PARTIAL_CONTENT {
+            return Err(TronCtlError::DownloadFailed(format!(
+                "服务器未返回请求的范围，状态码: {}",
+                response.status()
+            )));
+        }
+
+        let mut stream = response.bytes_stream();
+        let mut buffer = Vec::with_capacity(WRITE_BUFFER_SIZE);
+        let mut offset = range_start;
+        let mut window_start = Instant::now();
+        let mut window_bytes: u64 = 0;
+        let mut window_throttled = Duration::ZERO;
+
+        while let Some(data) = next_bytes(&mut stream, self.retry.stall_timeout).await? {
+            if let Some(limiter) = &self.limiter {
+                let wait_start = Instant::now();
+                limiter.acquire(data.len() as u64).await;
+                window_throttled += wait_start.elapsed();
+            }
+
+            // 忽略超出分块范围的数据
+            let room = (chunk.end + 1 - offset) as usize - buffer.len();
+            buffer.extend_from_slice(&data[..data.len().min(room)]);
+            if buffer.len() >= WRITE_BUFFER_SIZE {
+                offset = self.write_at(chunk, offset, &mut buffer).await?;
+            }
+
+            if let Some(min_rate) = self.min_rate {
+                window_bytes += data.len() as u64;
+                let elapsed = window_start.elapsed().saturating_sub(window_throttled);
+                if elapsed >= MIRROR_SLOW_WINDOW {
+                    let rate = window_bytes / elapsed.as_secs().max(1);
+                    // 没有更快的下载源可以接手时继续从当前下载源下载
+                    if rate < min_rate && self.has_other_source(job) {
+                        self.write_at(chunk, offset, &mut buffer).await?;
+                        return Ok(ChunkOutcome::Slow(rate));
+                    }
+                    window_start = Instant::now();
+                    window_bytes = 0;
+                    window_throttled = Duration::ZERO;
+                }
+            }
+        }
+
+        let offset = self.write_at(chunk, offset, &mut buffer).await?;
+        if offset <= chunk.end {
+            return Err(TronCtlError::DownloadFailed(format!(
+                "分块数据不完整: 缺少 {} 字节",
+                chunk.end + 1 - offset
+            )));
+        }
+        Ok(ChunkOutcome::Completed)
+    }
+
+    /// 将缓冲区写入目标文件的 `offset` 处并记录进度，返回下一个写入位置
+    async fn write_at(
+        &self,
+        chunk: &ChunkProgress,
+        offset: u64,
+        buffer: &mut Vec<u8>,
+    ) -> Result<u64> {
+        if buffer.is_empty() {
+            return Ok(offset);
+        }
+
+        let data = std::mem::replace(buffer, Vec::with_capacity(WRITE_BUFFER_SIZE));
+        let len = data.len() as u64;
+        let file = Arc::clone(&self.file);
+        tokio::task::spawn_blocking(move || {
+            use std::os::unix::fs::FileExt;
+            file.write_all_at(&data, offset)
+        })
+        .await
+        .map_err(|e| TronCtlError::Other(anyhow::anyhow!("写入任务失败: {}", e)))??;
+
+        self.written[chunk.index].fetch_add(len, Ordering::SeqCst);
+        self.pb.inc(len);
+        Ok(offset + len)
+    }
+}
+
+/// 流式解压的断点文件，位于解压目标目录中
+const EXTRACT_CHECKPOINT_FILE: &str = ".tronctl-extract.json";
+
+/// 两次保存解压断点的最小间隔
+const EXTRACT_CHECKPOINT_INTERVAL: Duration = Duration::from_secs(10);
+
+/// 流式解压断点：已完整写入的文件（相对路径 -> 大小）
+///
+/// 不记录压缩数据的偏移：gzip 解压状态无法跨进程恢复，进程重启后总是重新下载整个压缩包，只跳过已写入的文件
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ExtractCheckpoint {
+    url: String,
+    #[serde(flatten)]
+    validators: Validators,
+    completed: BTreeMap<String, u64>,
+}
+
+impl ExtractCheckpoint {
+    /// 读取同一 URL 且远程文件未变化的断点，否则从头开始
+    fn load(path: &Path, url: &str, validators: &Validators) -> Self {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str::<Self>(&content).ok())
+            .filter(|checkpoint| checkpoint.url == url)
+            .filter(|checkpoint| {
+                let unchanged = validators.unchanged_since(&checkpoint.validators);
+                if !unchanged {
+                    warn!("远程文件已变化，丢弃上次的解压断点");
+                }
+                unchanged
+            })
+            .unwrap_or_else(|| Self {
+                url: url.to_string(),
+                validators: validators.clone(),
+                ..Self::default()
+            })
+    }
+
+    /// 先写临时文件再重命名，避免中断时留下不完整的断点文件
+    fn save(&self, path: &Path) -> std::io::Result<()> {
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, serde_json::to_vec(self)?)?;
+        std::fs::rename(&tmp, path)
+    }
+
+    /// 文件已在上次解压中完整写入且大小一致
+    fn is_completed(&self, path: &str, full_path: &Path, size: u64) -> bool {
+        self.completed.get(path) == Some(&size)
+            && std::fs::metadata(full_path).is_ok_and(|m| m.is_file() && m.len() == size)
+    }
+}
+
+/// 流式下载的一个镜像
+struct StreamSource {
+    url: String,
+    client: Client,
+}
+
+/// 连接中断或提前结束时，用 Range 请求从当前偏移继续的字节流；
+/// 当前镜像用尽重试预算后切换到下一个镜像
+struct ResumableStream {
+    sources: Vec<StreamSource>,
+    current: usize,
+    /// 当前镜像续传时携带的 `If-Range`，确保拼接的是同一个文件；
+    /// 为 None 表示刚切换到该镜像，尚未通过 HEAD 获取其版本标识
+    if_range: Option<Option<String>>,
+    total: u64,
+    offset: Arc<AtomicU64>,
+    body: Option<BoxStream<'static, reqwest::Result<Bytes>>>,
+    /// 连续失败次数，决定下次重连前的退避时间；收到数据后清零
+    failures: u32,
+    /// 当前镜像已消耗的重试次数，超过重试预算后切换镜像
+    spent: u32,
+    /// 停滞超时、重试预算与退避时间
+    retry: RetryPolicy,
+}
+
+impl ResumableStream {
+    fn into_stream(self) -> BoxStream<'static, std::io::Result<Bytes>> {
+        futures::stream::unfold(self, |mut state| async move {
+            loop {
+                if state.current >= state.sources.len() {
+                    return None;
+                }
+
+                let Some(body) = state.body.as_mut() else {
+                    tokio::time::sleep(state.retry.delay(state.failures)).await;
+                    match state.reconnect().await {
+                        Ok(body) => state.body = Some(body),
+                        Err(e) => {
+                            warn!("续传连接失败: {}", e);
+                            // 远程文件已变化等错误在同一镜像上重试无意义
+                            let exhausted = !is_retryable(&e) || !state.record_failure();
+                            if exhausted && !state.next_source() {
+                                return Some((Err(std::io::Error::other(e)), state));
+                            }
+                        }
+                    }
+                    continue;
+                };
+
+                match next_bytes(body, state.retry.stall_timeout).await {
+                    Ok(Some(chunk)) => {
+                        state
+                            .offset
+                            .fetch_add(chunk.len() as u64, Ordering::Relaxed);
+                        state.failures = 0;
+                        return Some((Ok(chunk), state));
+                    }
+                    Err(e) => {
+                        warn!(
+                            "连接中断（已接收 {} 字节）: {}，尝试续传",
+                            state.offset.load(Ordering::Relaxed),
+                            e
+                        );
+                    }
+                    Ok(None) if state.offset.load(Ordering::Relaxed) < state.total => {
+                        warn!(
+                            "连接提前结束（已接收 {}/{} 字节），尝试续传",
+                            state.offset.load(Ordering::Relaxed),
+                            state.total
+                        );
+                    }
+                    Ok(None) => return None,
+                }
+
+                state.body = None;
+                if !state.record_failure() && !state.next_source() {
+                    return Some((
+                        Err(std::io::Error::other(
+                            "连接多次中断，所有下载源均已用尽重试预算",
+                        )),
+                        state,
+                    ));
+                }
+            }
+        })
+        .boxed()
+    }
+
+    /// 记录一次失败，返回当前镜像是否仍在重试预算内
+    fn record_failure(&mut self) -> bool {
+        self.failures += 1;
+        self.spent += 1;
+        self.spent <= self.retry.source_budget
+    }
+
+    /// 切换到下一个镜像，没有剩余镜像时返回 false
+    fn next_source(&mut self) -> bool {
+        self.current += 1;
+        self.spent = 0;
+        self.failures = 0;
+        self.if_range = None;
+        match self.sources.get(self.current) {
+            Some(source) => {
+                warn!("切换到下一个下载源继续: {}", source.url);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// 从当前偏移处重新请求剩余数据，服务器必须返回 206
+    ///
+    /// 刚切换到的镜像先用 HEAD 确认文件大小一致并取得其版本标识
+    async fn reconnect(&mut self) -> Result<BoxStream<'static, reqwest::Result<Bytes>>> {
+        let position = self.offset.load(Ordering::Relaxed);
+        let StreamSource { url, client } = &self.sources[self.current];
+        let (url, client) = (url.clone(), client.clone());
+
+        let if_range = match &self.if_range {
+            Some(if_range) => if_range.clone(),
+            None => {
+                let head = client.head(&url).send().await?;
+                if !head.status().is_success() {
+                    return Err(TronCtlError::DownloadFailed(format!(
+                        "HEAD 请求失败，状态码: {}",
+                        head.status()
+                    )));
+                }
+                // 大小不一致说明不是同一个文件，不能拼接
+                let size = network::head_content_length(&head).or(head.content_length());
+                if self.total > 0 && size != Some(self.total) {
+                    return Err(TronCtlError::RemoteFileChanged(url.clone()));
+                }
+                let if_range = Validators::from_response(&head)
+                    .if_range()
+                    .map(str::to_string);
+                self.if_range = Some(if_range.clone());
+                if_range
+            }
+        };
+
+        let mut request = client
+            .get(&url)
+            .header("Range", format!("bytes={}-", position));
+        if let Some(if_range) = &if_range {
+            request = request.header("If-Range", if_range);
+        }
+        let response = request.send().await?;
+
+        if response.status().is_success() && response.status().as_u16() != 206 && if_range.is_some()
+        {
+            return Err(TronCtlError::RemoteFileChanged(url.clone()));
+        }
+        if response.status().as_u16() != 206 {
+            return Err(TronCtlError::DownloadFailed(format!(
+                "服务器不支持 Range 请求，无法续传: HTTP {}",
+                response.status()
+            )));
+        }
+
+        info!("从 {} 字节处继续下载: {}", position, url);
+        Ok(response.bytes_stream().boxed())
+    }
+}
+
+/// 安全解压所有条目：校验路径，跳过断点中已完整写入的文件，并定期保存断点
+fn unpack_entries<R: std::io::Read>(
+    archive: &mut tar::Archive<R>,
+    dest_dir: &Path,
+    checkpoint: &mut ExtractCheckpoint,
+    checkpoint_path: &Path,
+    extracted: &mut BTreeSet<PathBuf>,
+) -> std::io::Result<()> {
+    use std::path::Component;
+
+    let mut last_save = Instant::now();
+    let mut skipped = 0usize;
+
+    // 安全解压：验证每个文件的路径
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let path = entry.path()?.into_owned();
+
+        // 1. 检查路径是否包含 .. 组件（父目录引用）
+        for component in path.components() {
+            if matches!(component, Component::ParentDir) {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::PermissionDenied,
+                    format!("检测到路径遍历攻击（包含 ..）: {:?}", path),
+                ));
+            }
+        }
+
+        // 2. 检查是否为绝对路径
+        if path.is_absolute() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::PermissionDenied,
+                format!("拒绝解压绝对路径: {:?}", path),
+            ));
+        }
+
+        // 3. 构造完整路径并验证
+        let full_path = dest_dir.join(&path);
+
+        // 4. 验证解压路径确实在目标目录内
+        let path_to_check = if full_path.exists() {
+            full_path.canonicalize().map_err(|e| {
+                std::io::Error::other(format!("无法规范化路径 {:?}: {}", full_path, e))
+            })?
+        } else {
+            // 确保父目录存在
+            if let Some(parent) = full_path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            // 对于不存在的文件，验证其父目录在目标目录内
+            if let Some(parent) = full_path.parent() {
+                parent
+                    .canonicalize()
+                    .map_err(|e| std::io::Error::other(format!("无法规范化父目录: {}", e)))?
+            } else {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    "无效的文件路径",
+                ));
+            }
+        };
+
+        // 确保路径在目标目录内
+        if !path_to_check.starts_with(dest_dir) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::PermissionDenied,
+                format!("路径在目标目录外: {:?} -> {:?}", path, path_to_check),
+            ));
+        }
+
+        if let Some(Component::Normal(top)) = path.components().next() {
+            extracted.insert(dest_dir.join(top));
+        }
+
+        // 上次已完整写入的文件直接跳过，tar 读取下一个条目时会丢弃其数据
+        let is_file = entry.header().entry_type().is_file();
+        let size = entry.header().size()?;
+        let key = path.to_string_lossy().into_owned();
+        if is_file && checkpoint.is_completed(&key, &full_path, size) {
+            skipped += 1;
+            continue;
+        }
+
+        // 安全解压
+        entry.unpack(&full_path)?;
+
+        if is_file {
+            checkpoint.completed.insert(key, size);
+        }
+        if last_save.elapsed() >= EXTRACT_CHECKPOINT_INTERVAL {
+            checkpoint.save(checkpoint_path)?;
+            last_save = Instant::now();
+        }
+    }
+
+    if skipped > 0 {
+        info!("跳过 {} 个已解压的文件", skipped);
+    }
+    Ok(())
+}
+
+impl Default for Downloader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::checksum::ChecksumAlgorithm;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_downloader_new() {
+        let downloader = Downloader::new();
+        assert!(std::ptr::addr_of!(downloader.client) as usize != 0);
+    }
+
+    #[test]
+    fn test_downloader_default() {
+        let downloader = Downloader::default();
+        assert!(std::ptr::addr_of!(downloader.client) as usize != 0);
+    }
+
+    #[test]
+    fn test_downloader_client_ref() {
+        let downloader = Downloader::new();
+        let client = downloader.client();
+        assert!(std::ptr::addr_of!(*client) as usize != 0);
     }
 
     #[tokio::test]
@@ -737,7 +1874,7 @@ mod tests {
     async fn test_download_with_progress_md5_match() {
         let mut server = mockito::Server::new_async().await;
         let content = b"test";
-        let md5_hash = format!("{:x}", md5::compute(content));
+        let md5_hash = Checksum::parse(&format!("{:x}", md5::compute(content))).unwrap();
 
         // Mock HEAD 请求
         let _head_mock = server
@@ -793,14 +1930,15 @@ mod tests {
         let dest = temp_dir.path().join("test.file");
 
         let url = format!("{}/test.file", server.url());
+        let wrong = Checksum::parse(&"0".repeat(32)).unwrap();
         let result = downloader
-            .download_with_progress(&url, &dest, Some("wrongmd5"))
+            .download_with_progress(&url, &dest, Some(&wrong))
             .await;
 
         assert!(result.is_err());
         assert!(matches!(
             result.unwrap_err(),
-            TronCtlError::Md5Mismatch { .. }
+            TronCtlError::ChecksumMismatch { algorithm, .. } if algorithm == "MD5"
         ));
     }
 
@@ -843,4 +1981,1098 @@ mod tests {
 
         assert!(result.is_err());
     }
+
+    fn build_tgz(files: &[(&str, &[u8])]) -> Vec<u8> {
+        use flate2::Compression;
+        use flate2::write::GzEncoder;
+
+        let encoder = GzEncoder::new(Vec::new(), Compression::fast());
+        let mut builder = tar::Builder::new(encoder);
+        for (name, content) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder.append_data(&mut header, name, *content).unwrap();
+        }
+        builder.into_inner().unwrap().finish().unwrap()
+    }
+
+    fn mirror(url: &str) -> Mirror {
+        Mirror {
+            url: url.to_string(),
+            headers: HeaderMap::new(),
+            weight: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_download_and_extract_tgz_checksum_match() {
+        let mut server = mockito::Server::new_async().await;
+        let body = build_tgz(&[("output-directory/database/a.sst", b"data")]);
+        let sha256 = Checksum::new(
+            ChecksumAlgorithm::Sha256,
+            &hash::to_hex(aws_lc_rs::digest::digest(&aws_lc_rs::digest::SHA256, &body).as_ref()),
+        )
+        .unwrap();
+
+        let _mock = server
+            .mock("GET", "/snapshot.tgz")
+            .with_status(200)
+            .with_body(&body)
+            .create_async()
+            .await;
+
+        let temp_dir = TempDir::new().unwrap();
+        let url = format!("{}/snapshot.tgz", server.url());
+        Downloader::new()
+            .download_and_extract_tgz(&[mirror(&url)], temp_dir.path(), Some(&sha256))
+            .await
+            .unwrap();
+
+        let extracted = temp_dir.path().join("output-directory/database/a.sst");
+        assert_eq!(std::fs::read(extracted).unwrap(), b"data");
+    }
+
+    #[tokio::test]
+    async fn test_download_and_extract_tgz_checksum_mismatch() {
+        let mut server = mockito::Server::new_async().await;
+        let body = build_tgz(&[("output-directory/database/a.sst", b"data")]);
+        let wrong = Checksum::parse("900150983cd24fb0d6963f7d28e17f72").unwrap();
+
+        let _mock = server
+            .mock("GET", "/snapshot.tgz")
+            .with_status(200)
+            .with_body(&body)
+            .create_async()
+            .await;
+
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::write(temp_dir.path().join("keep.txt"), b"keep").unwrap();
+        let url = format!("{}/snapshot.tgz", server.url());
+        let result = Downloader::new()
+            .download_and_extract_tgz(&[mirror(&url)], temp_dir.path(), Some(&wrong))
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(TronCtlError::ChecksumMismatch { ref algorithm, .. }) if algorithm == "MD5"
+        ));
+        // 已解压的数据被删除，目录中原有文件保留
+        assert!(!temp_dir.path().join("output-directory").exists());
+        assert!(temp_dir.path().join("keep.txt").exists());
+    }
+
+    #[tokio::test]
+    async fn test_download_and_extract_tgz_skips_unreachable_mirror() {
+        let mut server = mockito::Server::new_async().await;
+        let body = build_tgz(&[("output-directory/database/a.sst", b"data")]);
+        let _mock = server
+            .mock("GET", "/snapshot.tgz")
+            .with_status(200)
+            .with_body(&body)
+            .create_async()
+            .await;
+
+        let temp_dir = TempDir::new().unwrap();
+        let mirrors = [
+            mirror("http://127.0.0.1:1/snapshot.tgz"),
+            mirror(&format!("{}/snapshot.tgz", server.url())),
+        ];
+        Downloader::new()
+            .download_and_extract_tgz(&mirrors, temp_dir.path(), None)
+            .await
+            .unwrap();
+
+        let extracted = temp_dir.path().join("output-directory/database/a.sst");
+        assert_eq!(std::fs::read(extracted).unwrap(), b"data");
+    }
+
+    #[tokio::test]
+    async fn test_resumable_stream_fails_over_to_next_mirror() {
+        let mut primary = mockito::Server::new_async().await;
+        let mut backup = mockito::Server::new_async().await;
+        let _first = primary
+            .mock("GET", "/snapshot.tgz")
+            .match_header("range", mockito::Matcher::Missing)
+            .with_status(200)
+            .with_body("0123")
+            .create_async()
+            .await;
+        // 主服务器持续故障，用尽重试预算（3 次）后切换到备用镜像
+        let outage = primary
+            .mock("GET", "/snapshot.tgz")
+            .match_header("range", "bytes=4-")
+            .with_status(503)
+            .expect(3)
+            .create_async()
+            .await;
+        let _head = backup
+            .mock("HEAD", "/snapshot.tgz")
+            .with_status(200)
+            .with_header("content-length", "8")
+            .with_header("etag", "\"b1\"")
+            .create_async()
+            .await;
+        let resumed = backup
+            .mock("GET", "/snapshot.tgz")
+            .match_header("range", "bytes=4-")
+            .match_header("if-range", "\"b1\"")
+            .with_status(206)
+            .with_body("4567")
+            .create_async()
+            .await;
+
+        let client = Client::new();
+        let url = format!("{}/snapshot.tgz", primary.url());
+        let response = client.get(&url).send().await.unwrap();
+        let stream = ResumableStream {
+            sources: vec![
+                StreamSource { url, client },
+                StreamSource {
+                    url: format!("{}/snapshot.tgz", backup.url()),
+                    client: Client::new(),
+                },
+            ],
+            current: 0,
+            if_range: Some(None),
+            total: 8,
+            offset: Arc::new(AtomicU64::new(0)),
+            body: Some(response.bytes_stream().boxed()),
+            failures: 0,
+            spent: 0,
+            retry: fast_retry_downloader().retry,
+        }
+        .into_stream();
+
+        let chunks: Vec<Bytes> = stream.map(|chunk| chunk.unwrap()).collect().await;
+        outage.assert_async().await;
+        resumed.assert_async().await;
+        assert_eq!(chunks.concat(), b"01234567");
+    }
+
+    #[tokio::test]
+    async fn test_resumable_stream_reconnects_with_range() {
+        let mut server = mockito::Server::new_async().await;
+        let _first = server
+            .mock("GET", "/snapshot.tgz")
+            .match_header("range", mockito::Matcher::Missing)
+            .with_status(200)
+            .with_body("0123")
+            .create_async()
+            .await;
+        let resumed = server
+            .mock("GET", "/snapshot.tgz")
+            .match_header("range", "bytes=4-")
+            .with_status(206)
+            .with_body("4567")
+            .create_async()
+            .await;
+
+        let client = Client::new();
+        let url = format!("{}/snapshot.tgz", server.url());
+        let response = client.get(&url).send().await.unwrap();
+        let offset = Arc::new(AtomicU64::new(0));
+        let stream = ResumableStream {
+            sources: vec![StreamSource { url, client }],
+            current: 0,
+            if_range: Some(None),
+            total: 8,
+            offset: offset.clone(),
+            body: Some(response.bytes_stream().boxed()),
+            failures: 0,
+            spent: 0,
+            retry: fast_retry_downloader().retry,
+        }
+        .into_stream();
+
+        let chunks: Vec<Bytes> = stream.map(|chunk| chunk.unwrap()).collect().await;
+        resumed.assert_async().await;
+        assert_eq!(chunks.concat(), b"01234567");
+        assert_eq!(offset.load(Ordering::Relaxed), 8);
+    }
+
+    #[tokio::test]
+    async fn test_resumable_stream_reconnects_after_stall() {
+        let mut server = mockito::Server::new_async().await;
+        let resumed = server
+            .mock("GET", "/snapshot.tgz")
+            .match_header("range", "bytes=0-")
+            .with_status(206)
+            .with_body("0123")
+            .create_async()
+            .await;
+
+        // 连接未关闭但不再发送数据
+        let stream = ResumableStream {
+            sources: vec![StreamSource {
+                url: format!("{}/snapshot.tgz", server.url()),
+                client: Client::new(),
+            }],
+            current: 0,
+            if_range: Some(None),
+            total: 4,
+            offset: Arc::new(AtomicU64::new(0)),
+            body: Some(futures::stream::pending().boxed()),
+            failures: 0,
+            spent: 0,
+            retry: RetryPolicy {
+                stall_timeout: Duration::from_millis(50),
+                ..fast_retry_downloader().retry
+            },
+        }
+        .into_stream();
+
+        let chunks: Vec<Bytes> = stream.map(|chunk| chunk.unwrap()).collect().await;
+        resumed.assert_async().await;
+        assert_eq!(chunks.concat(), b"0123");
+    }
+
+    #[tokio::test]
+    async fn test_resumable_stream_gives_up_after_budget() {
+        let mut server = mockito::Server::new_async().await;
+        let _first = server
+            .mock("GET", "/snapshot.tgz")
+            .match_header("range", mockito::Matcher::Missing)
+            .with_status(200)
+            .with_body("0123")
+            .create_async()
+            .await;
+        // fast_retry_downloader 的重试预算为 3
+        let outage = server
+            .mock("GET", "/snapshot.tgz")
+            .match_header("range", "bytes=4-")
+            .with_status(503)
+            .expect(3)
+            .create_async()
+            .await;
+
+        let client = Client::new();
+        let url = format!("{}/snapshot.tgz", server.url());
+        let response = client.get(&url).send().await.unwrap();
+        let stream = ResumableStream {
+            sources: vec![StreamSource { url, client }],
+            current: 0,
+            if_range: Some(None),
+            total: 8,
+            offset: Arc::new(AtomicU64::new(0)),
+            body: Some(response.bytes_stream().boxed()),
+            failures: 0,
+            spent: 0,
+            retry: fast_retry_downloader().retry,
+        }
+        .into_stream();
+
+        let results: Vec<std::io::Result<Bytes>> = stream.collect().await;
+        outage.assert_async().await;
+        assert_eq!(results.len(), 2);
+        assert!(results[1].is_err());
+    }
+
+    #[tokio::test]
+    async fn test_resumable_stream_fails_when_remote_changed() {
+        let mut server = mockito::Server::new_async().await;
+        let _first = server
+            .mock("GET", "/snapshot.tgz")
+            .match_header("range", mockito::Matcher::Missing)
+            .with_status(200)
+            .with_body("0123")
+            .create_async()
+            .await;
+        // If-Range 不匹配时服务器返回整个新文件
+        let replaced = server
+            .mock("GET", "/snapshot.tgz")
+            .match_header("range", "bytes=4-")
+            .match_header("if-range", "\"v1\"")
+            .with_status(200)
+            .with_body("abcdefgh")
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = Client::new();
+        let url = format!("{}/snapshot.tgz", server.url());
+        let response = client.get(&url).send().await.unwrap();
+        let stream = ResumableStream {
+            sources: vec![StreamSource { url, client }],
+            current: 0,
+            if_range: Some(Some("\"v1\"".to_string())),
+            total: 8,
+            offset: Arc::new(AtomicU64::new(0)),
+            body: Some(response.bytes_stream().boxed()),
+            failures: 0,
+            spent: 0,
+            retry: fast_retry_downloader().retry,
+        }
+        .into_stream();
+
+        let results: Vec<std::io::Result<Bytes>> = stream.collect().await;
+        replaced.assert_async().await;
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].as_ref().unwrap().as_ref(), b"0123");
+        assert!(
+            results[1]
+                .as_ref()
+                .unwrap_err()
+                .to_string()
+                .contains("远程文件已变化")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_download_and_extract_tgz_skips_completed_entries() {
+        let mut server = mockito::Server::new_async().await;
+        let body = build_tgz(&[
+            ("output-directory/database/a.sst", b"aaaa"),
+            ("output-directory/database/b.sst", b"bbbb"),
+        ]);
+        let _mock = server
+            .mock("GET", "/snapshot.tgz")
+            .with_status(200)
+            .with_body(&body)
+            .create_async()
+            .await;
+
+        let temp_dir = TempDir::new().unwrap();
+        let url = format!("{}/snapshot.tgz", server.url());
+        let database = temp_dir.path().join("output-directory/database");
+        std::fs::create_dir_all(&database).unwrap();
+        // 上次中断前已写入 a.sst，内容不同以便确认未被重写
+        std::fs::write(database.join("a.sst"), b"done").unwrap();
+        let checkpoint_path = temp_dir.path().join(EXTRACT_CHECKPOINT_FILE);
+        ExtractCheckpoint {
+            url: url.clone(),
+            validators: Validators::default(),
+            completed: BTreeMap::from([("output-directory/database/a.sst".to_string(), 4)]),
+        }
+        .save(&checkpoint_path)
+        .unwrap();
+
+        Downloader::new()
+            .download_and_extract_tgz(&[mirror(&url)], temp_dir.path(), None)
+            .await
+            .unwrap();
+
+        assert_eq!(std::fs::read(database.join("a.sst")).unwrap(), b"done");
+        assert_eq!(std::fs::read(database.join("b.sst")).unwrap(), b"bbbb");
+        assert!(!checkpoint_path.exists());
+    }
+
+    #[test]
+    fn test_extract_checkpoint_ignores_other_url() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join(EXTRACT_CHECKPOINT_FILE);
+        ExtractCheckpoint {
+            url: "http://a/snapshot.tgz".to_string(),
+            validators: Validators::default(),
+            completed: BTreeMap::from([("a.sst".to_string(), 4)]),
+        }
+        .save(&path)
+        .unwrap();
+
+        assert_eq!(
+            ExtractCheckpoint::load(&path, "http://a/snapshot.tgz", &Validators::default())
+                .completed
+                .len(),
+            1
+        );
+        assert!(
+            ExtractCheckpoint::load(&path, "http://b/snapshot.tgz", &Validators::default())
+                .completed
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_extract_checkpoint_ignores_changed_remote() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join(EXTRACT_CHECKPOINT_FILE);
+        let url = "http://a/snapshot.tgz";
+        let v1 = Validators {
+            etag: Some("\"v1\"".to_string()),
+            last_modified: None,
+        };
+        ExtractCheckpoint {
+            url: url.to_string(),
+            validators: v1.clone(),
+            completed: BTreeMap::from([("a.sst".to_string(), 4)]),
+        }
+        .save(&path)
+        .unwrap();
+
+        assert_eq!(ExtractCheckpoint::load(&path, url, &v1).completed.len(), 1);
+        let v2 = Validators {
+            etag: Some("\"v2\"".to_string()),
+            last_modified: None,
+        };
+        let checkpoint = ExtractCheckpoint::load(&path, url, &v2);
+        assert!(checkpoint.completed.is_empty());
+        assert_eq!(checkpoint.validators, v2);
+    }
+
+    /// 按 Range 头返回对应片段的 GET 响应
+    async fn mock_ranges(
+        server: &mut mockito::ServerGuard,
+        path: &str,
+        content: &'static [u8],
+    ) -> mockito::Mock {
+        server
+            .mock("GET", path)
+            .with_status(206)
+            .with_body_from_request(move |request| {
+                let range = request.header("range")[0].to_str().unwrap().to_string();
+                let (start, end) = range.trim_start_matches("bytes=").split_once('-').unwrap();
+                content[start.parse().unwrap()..=end.parse().unwrap()].to_vec()
+            })
+            .create_async()
+            .await
+    }
+
+    /// 重试间隔极短、分块较小的下载器，避免测试等待退避
+    fn fast_retry_downloader() -> Downloader {
+        Downloader {
+            retry: RetryPolicy {
+                chunk_retries: 2,
+                source_budget: 3,
+                base_delay: Duration::from_millis(1),
+                stall_timeout: Duration::from_millis(200),
+            },
+            connections: 4,
+            chunk_size: 4096,
+            ..Downloader::new()
+        }
+    }
+
+    fn chunk_source(server: &mockito::ServerGuard, path: &str) -> ChunkSource {
+        ChunkSource {
+            url: format!("{}{}", server.url(), path),
+            client: Downloader::new().client,
+            weight: 0,
+            validators: Validators::default(),
+        }
+    }
+
+    static MIRROR_CONTENT: [u8; 64 * 1024] = {
+        let mut content = [0u8; 64 * 1024];
+        let mut i = 0;
+        while i < content.len() {
+            content[i] = (i % 251) as u8;
+            i += 1;
+        }
+        content
+    };
+
+    #[tokio::test]
+    async fn test_download_multithreaded_from_mirrors() {
+        let mut first = mockito::Server::new_async().await;
+        let mut second = mockito::Server::new_async().await;
+        let _first = mock_ranges(&mut first, "/snapshot.tgz", &MIRROR_CONTENT).await;
+        let _second = mock_ranges(&mut second, "/mirror/snapshot.tgz", &MIRROR_CONTENT).await;
+
+        let temp_dir = TempDir::new().unwrap();
+        let dest = temp_dir.path().join("snapshot.tgz");
+        let sources = [
+            chunk_source(&first, "/snapshot.tgz"),
+            chunk_source(&second, "/mirror/snapshot.tgz"),
+        ];
+        let mut hasher = Hasher::new(ChecksumAlgorithm::Sha256);
+        hasher.update(&MIRROR_CONTENT);
+        let expected = Checksum::new(ChecksumAlgorithm::Sha256, &hasher.finish()).unwrap();
+
+        Downloader::new()
+            .download_multithreaded(
+                &sources,
+                &dest,
+                MIRROR_CONTENT.len() as u64,
+                Some(&expected),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(std::fs::read(&dest).unwrap(), MIRROR_CONTENT);
+        assert!(!Downloader::progress_file(&dest).exists());
+    }
+
+    #[tokio::test]
+    async fn test_download_multithreaded_fails_over_to_other_mirror() {
+        let mut broken = mockito::Server::new_async().await;
+        let mut healthy = mockito::Server::new_async().await;
+        let _broken = broken
+            .mock("GET", "/snapshot.tgz")
+            .with_status(500)
+            .create_async()
+            .await;
+        let _healthy = mock_ranges(&mut healthy, "/snapshot.tgz", &MIRROR_CONTENT).await;
+
+        let temp_dir = TempDir::new().unwrap();
+        let dest = temp_dir.path().join("snapshot.tgz");
+        let sources = [
+            chunk_source(&broken, "/snapshot.tgz"),
+            chunk_source(&healthy, "/snapshot.tgz"),
+        ];
+
+        fast_retry_downloader()
+            .download_multithreaded(&sources, &dest, MIRROR_CONTENT.len() as u64, None)
+            .await
+            .unwrap();
+
+        assert_eq!(std::fs::read(&dest).unwrap(), MIRROR_CONTENT);
+    }
+
+    #[tokio::test]
+    async fn test_download_multithreaded_all_mirrors_fail_keeps_progress() {
+        let mut server = mockito::Server::new_async().await;
+        let _mock = server
+            .mock("GET", "/snapshot.tgz")
+            .with_status(500)
+            .create_async()
+            .await;
+
+        let temp_dir = TempDir::new().unwrap();
+        let dest = temp_dir.path().join("snapshot.tgz");
+        let sources = [chunk_source(&server, "/snapshot.tgz")];
+
+        let result = fast_retry_downloader()
+            .download_multithreaded(&sources, &dest, MIRROR_CONTENT.len() as u64, None)
+            .await;
+
+        assert!(matches!(result, Err(TronCtlError::DownloadFailed(_))));
+        assert!(Downloader::progress_file(&dest).exists());
+    }
+
+    #[tokio::test]
+    async fn test_download_multithreaded_retries_failed_chunk() {
+        let mut server = mockito::Server::new_async().await;
+        // 前两次请求失败，之后恢复
+        let failing = server
+            .mock("GET", "/snapshot.tgz")
+            .with_status(503)
+            .expect(2)
+            .create_async()
+            .await;
+        let _ranges = mock_ranges(&mut server, "/snapshot.tgz", &MIRROR_CONTENT).await;
+
+        let temp_dir = TempDir::new().unwrap();
+        let dest = temp_dir.path().join("snapshot.tgz");
+        let sources = [chunk_source(&server, "/snapshot.tgz")];
+
+        fast_retry_downloader()
+            .download_multithreaded(&sources, &dest, MIRROR_CONTENT.len() as u64, None)
+            .await
+            .unwrap();
+
+        failing.assert_async().await;
+        assert_eq!(std::fs::read(&dest).unwrap(), MIRROR_CONTENT);
+    }
+
+    #[tokio::test]
+    async fn test_download_multithreaded_outage_within_source_budget() {
+        let mut server = mockito::Server::new_async().await;
+        // 故障持续超过单个分块的重试次数，但未超出下载源的重试预算
+        let failing = server
+            .mock("GET", "/snapshot.tgz")
+            .with_status(503)
+            .expect(6)
+            .create_async()
+            .await;
+        let _ranges = mock_ranges(&mut server, "/snapshot.tgz", &MIRROR_CONTENT).await;
+
+        let temp_dir = TempDir::new().unwrap();
+        let dest = temp_dir.path().join("snapshot.tgz");
+        let sources = [chunk_source(&server, "/snapshot.tgz")];
+        let downloader = Downloader {
+            retry: RetryPolicy {
+                source_budget: 10,
+                ..fast_retry_downloader().retry
+            },
+            connections: 1,
+            ..fast_retry_downloader()
+        };
+
+        downloader
+            .download_multithreaded(&sources, &dest, MIRROR_CONTENT.len() as u64, None)
+            .await
+            .unwrap();
+
+        failing.assert_async().await;
+        assert_eq!(std::fs::read(&dest).unwrap(), MIRROR_CONTENT);
+    }
+
+    #[tokio::test]
+    async fn test_download_single_thread_retries() {
+        let mut server = mockito::Server::new_async().await;
+        let content = b"test file content";
+        let _head = server
+            .mock("HEAD", "/test.file")
+            .with_status(200)
+            .with_header("content-length", &content.len().to_string())
+            .create_async()
+            .await;
+        let failing = server
+            .mock("GET", "/test.file")
+            .with_status(502)
+            .expect(1)
+            .create_async()
+            .await;
+        let _ok = server
+            .mock("GET", "/test.file")
+            .with_status(200)
+            .with_body(content)
+            .create_async()
+            .await;
+
+        let temp_dir = TempDir::new().unwrap();
+        let dest = temp_dir.path().join("test.file");
+        let url = format!("{}/test.file", server.url());
+        fast_retry_downloader()
+            .download_with_progress(&url, &dest, None)
+            .await
+            .unwrap();
+
+        failing.assert_async().await;
+        assert_eq!(std::fs::read(&dest).unwrap(), content);
+    }
+
+    #[tokio::test]
+    async fn test_download_single_thread_does_not_retry_client_error() {
+        let mut server = mockito::Server::new_async().await;
+        let _head = server
+            .mock("HEAD", "/test.file")
+            .with_status(200)
+            .with_header("content-length", "4")
+            .create_async()
+            .await;
+        let missing = server
+            .mock("GET", "/test.file")
+            .with_status(404)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let temp_dir = TempDir::new().unwrap();
+        let dest = temp_dir.path().join("test.file");
+        let url = format!("{}/test.file", server.url());
+        let result = fast_retry_downloader()
+            .download_with_progress(&url, &dest, None)
+            .await;
+
+        assert!(result.is_err());
+        missing.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_download_from_mirrors_fails_over_after_budget() {
+        let mut broken = mockito::Server::new_async().await;
+        let mut healthy = mockito::Server::new_async().await;
+        let content = b"snapshot content";
+        for server in [&mut broken, &mut healthy] {
+            server
+                .mock("HEAD", "/snapshot.tgz")
+                .with_status(200)
+                .with_header("content-length", &content.len().to_string())
+                .create_async()
+                .await;
+        }
+        let _broken = broken
+            .mock("GET", "/snapshot.tgz")
+            .with_status(500)
+            .create_async()
+            .await;
+        let _healthy = healthy
+            .mock("GET", "/snapshot.tgz")
+            .with_status(200)
+            .with_body(content)
+            .create_async()
+            .await;
+
+        let mirrors: Vec<Mirror> = [&broken, &healthy]
+            .iter()
+            .map(|server| Mirror {
+                url: format!("{}/snapshot.tgz", server.url()),
+                headers: HeaderMap::new(),
+                weight: 0,
+            })
+            .collect();
+
+        let temp_dir = TempDir::new().unwrap();
+        let dest = temp_dir.path().join("snapshot.tgz");
+        fast_retry_downloader()
+            .download_from_mirrors(&mirrors, &dest, None)
+            .await
+            .unwrap();
+
+        assert_eq!(std::fs::read(&dest).unwrap(), content);
+    }
+
+    #[tokio::test]
+    async fn test_download_from_mirrors_skips_unreachable_primary() {
+        // 大于单线程阈值，走多镜像分块下载
+        let content: &'static [u8] = Vec::leak(
+            (0..11 * 1024 * 1024)
+                .map(|i| (i % 251) as u8)
+                .collect::<Vec<u8>>(),
+        );
+        let mut healthy = mockito::Server::new_async().await;
+        let _head = healthy
+            .mock("HEAD", "/snapshot.tgz")
+            .with_status(200)
+            .with_header("content-length", &content.len().to_string())
+            .with_header("accept-ranges", "bytes")
+            .create_async()
+            .await;
+        let _ranges = mock_ranges(&mut healthy, "/snapshot.tgz", content).await;
+
+        let mirrors = [
+            mirror("http://127.0.0.1:1/snapshot.tgz"),
+            mirror(&format!("{}/snapshot.tgz", healthy.url())),
+        ];
+        let temp_dir = TempDir::new().unwrap();
+        let dest = temp_dir.path().join("snapshot.tgz");
+        Downloader {
+            chunk_size: 1024 * 1024,
+            ..fast_retry_downloader()
+        }
+        .download_from_mirrors(&mirrors, &dest, None)
+        .await
+        .unwrap();
+
+        assert_eq!(std::fs::read(&dest).unwrap(), content);
+    }
+
+    #[tokio::test]
+    async fn test_next_bytes_detects_stall() {
+        let mut stream = futures::stream::pending::<reqwest::Result<Bytes>>();
+        let result = next_bytes(&mut stream, Duration::from_millis(10)).await;
+        assert!(matches!(result, Err(TronCtlError::DownloadFailed(_))));
+    }
+
+    #[test]
+    fn test_retry_policy_delay_backs_off_with_jitter() {
+        let policy = RetryPolicy::default();
+        for attempt in 1..=3 {
+            let backoff = policy.base_delay * (1 << (attempt - 1));
+            let delay = policy.delay(attempt);
+            assert!(delay >= backoff && delay <= backoff * 3 / 2);
+        }
+        assert!(policy.delay(20) <= RETRY_MAX_DELAY * 3 / 2);
+    }
+
+    #[test]
+    fn test_create_initial_progress_small_chunks() {
+        let progress = Downloader::create_initial_progress("http://a/f", 10_000, 4096);
+        let ranges: Vec<(u64, u64)> = progress.chunks.iter().map(|c| (c.start, c.end)).collect();
+        assert_eq!(ranges, vec![(0, 4095), (4096, 8191), (8192, 9999)]);
+    }
+
+    #[test]
+    fn test_create_initial_progress_caps_chunk_count() {
+        let total = 3 * 1024 * 1024 * 1024 * 1024;
+        let progress = Downloader::create_initial_progress("http://a/f", total, 1024 * 1024);
+        assert_eq!(progress.chunks.len() as u64, MAX_DOWNLOAD_CHUNKS);
+        assert_eq!(progress.chunk_size, 12 * 1024 * 1024);
+    }
+
+    #[test]
+    fn test_split_connections() {
+        assert_eq!(split_connections(8, &[0, 0]), vec![4, 4]);
+        assert_eq!(split_connections(8, &[300, 100]), vec![6, 2]);
+        assert_eq!(split_connections(5, &[1, 1, 1]), vec![2, 2, 1]);
+        // 连接数少于下载源时只分给权重最高的下载源，不超过用户设置的连接数
+        assert_eq!(split_connections(1, &[100, 1]), vec![1, 0]);
+        assert_eq!(split_connections(1, &[1, 300, 100]), vec![0, 1, 0]);
+        assert_eq!(split_connections(2, &[1, 300, 100]), vec![0, 1, 1]);
+        assert_eq!(split_connections(3, &[1, 300, 100]), vec![1, 1, 1]);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limiter_throttles() {
+        let limiter = RateLimiter::new(100_000);
+        let start = Instant::now();
+        // 初始额度允许一秒的突发
+        limiter.acquire(100_000).await;
+        assert!(start.elapsed() < Duration::from_millis(100));
+
+        limiter.acquire(20_000).await;
+        assert!(start.elapsed() >= Duration::from_millis(150));
+    }
+
+    #[tokio::test]
+    async fn test_download_multithreaded_with_rate_limit() {
+        let mut server = mockito::Server::new_async().await;
+        let _ranges = mock_ranges(&mut server, "/snapshot.tgz", &MIRROR_CONTENT).await;
+
+        let temp_dir = TempDir::new().unwrap();
+        let dest = temp_dir.path().join("snapshot.tgz");
+        let sources = [chunk_source(&server, "/snapshot.tgz")];
+        let downloader = Downloader {
+            chunk_size: 8192,
+            ..fast_retry_downloader().with_config(&DownloadConfig {
+                connections: Some(3),
+                chunk_size: None,
+                limit_rate: Some(crate::models::node_config::ByteSize(256 * 1024)),
+            })
+        };
+
+        downloader
+            .download_multithreaded(&sources, &dest, MIRROR_CONTENT.len() as u64, None)
+            .await
+            .unwrap();
+
+        assert_eq!(std::fs::read(&dest).unwrap(), MIRROR_CONTENT);
+    }
+
+    #[tokio::test]
+    async fn test_download_multithreaded_resumes_in_place() {
+        let mut server = mockito::Server::new_async().await;
+        let resumed = server
+            .mock("GET", "/snapshot.tgz")
+            .match_header("range", "bytes=2048-4095")
+            .with_status(206)
+            .with_body(&MIRROR_CONTENT[2048..4096])
+            .expect(1)
+            .create_async()
+            .await;
+        let refetched = server
+            .mock("GET", "/snapshot.tgz")
+            .match_header("range", mockito::Matcher::Regex("^bytes=4096-".to_string()))
+            .expect(0)
+            .create_async()
+            .await;
+        let _ranges = mock_ranges(&mut server, "/snapshot.tgz", &MIRROR_CONTENT).await;
+
+        let temp_dir = TempDir::new().unwrap();
+        let dest = temp_dir.path().join("snapshot.tgz");
+        let url = format!("{}/snapshot.tgz", server.url());
+        let total = MIRROR_CONTENT.len() as u64;
+
+        // 分块 0 写入了一半，分块 1 已完成，其余位置为未写入的数据
+        let mut partial = vec![0xffu8; MIRROR_CONTENT.len()];
+        partial[..2048].copy_from_slice(&MIRROR_CONTENT[..2048]);
+        partial[4096..8192].copy_from_slice(&MIRROR_CONTENT[4096..8192]);
+        std::fs::write(&dest, &partial).unwrap();
+        let mut progress = Downloader::create_initial_progress(&url, total, 4096);
+        progress.chunks[0].downloaded = 2048;
+        progress.chunks[1].status = ChunkStatus::Completed;
+        Downloader::save_progress(&dest, &progress).await.unwrap();
+
+        fast_retry_downloader()
+            .download_multithreaded(
+                &[chunk_source(&server, "/snapshot.tgz")],
+                &dest,
+                total,
+                None,
+            )
+            .await
+            .unwrap();
+
+        resumed.assert_async().await;
+        refetched.assert_async().await;
+        assert_eq!(std::fs::read(&dest).unwrap(), MIRROR_CONTENT);
+        assert!(!Downloader::progress_file(&dest).exists());
+    }
+
+    #[tokio::test]
+    async fn test_download_multithreaded_discards_part_file_progress() {
+        let mut server = mockito::Server::new_async().await;
+        let _ranges = mock_ranges(&mut server, "/snapshot.tgz", &MIRROR_CONTENT).await;
+
+        let temp_dir = TempDir::new().unwrap();
+        let dest = temp_dir.path().join("snapshot.tgz");
+        let url = format!("{}/snapshot.tgz", server.url());
+        let total = MIRROR_CONTENT.len() as u64;
+
+        // 旧版本的进度记录：已完成分块的数据位于 .partN 文件中
+        std::fs::write(&dest, vec![0u8; MIRROR_CONTENT.len()]).unwrap();
+        let mut progress = Downloader::create_initial_progress(&url, total, 4096);
+        progress.in_place = false;
+        progress.chunks[0].status = ChunkStatus::Completed;
+        Downloader::save_progress(&dest, &progress).await.unwrap();
+        let parts: Vec<PathBuf> = (0..2)
+            .map(|i| dest.with_extension(format!("part{}", i)))
+            .collect();
+        for part in &parts {
+            std::fs::write(part, b"old").unwrap();
+        }
+
+        fast_retry_downloader()
+            .download_multithreaded(
+                &[chunk_source(&server, "/snapshot.tgz")],
+                &dest,
+                total,
+                None,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(std::fs::read(&dest).unwrap(), MIRROR_CONTENT);
+        assert!(parts.iter().all(|part| !part.exists()));
+    }
+
+    #[tokio::test]
+    async fn test_download_multithreaded_rejects_full_response() {
+        let mut server = mockito::Server::new_async().await;
+        let _full = server
+            .mock("GET", "/snapshot.tgz")
+            .with_status(200)
+            .with_body(&MIRROR_CONTENT[..])
+            .create_async()
+            .await;
+
+        let temp_dir = TempDir::new().unwrap();
+        let dest = temp_dir.path().join("snapshot.tgz");
+        let result = fast_retry_downloader()
+            .download_multithreaded(
+                &[chunk_source(&server, "/snapshot.tgz")],
+                &dest,
+                MIRROR_CONTENT.len() as u64,
+                None,
+            )
+            .await;
+
+        assert!(matches!(result, Err(TronCtlError::DownloadFailed(_))));
+    }
+
+    #[test]
+    fn test_validators_if_range() {
+        let strong = Validators {
+            etag: Some("\"abc\"".to_string()),
+            last_modified: Some("Fri, 09 Jan 2026 00:00:00 GMT".to_string()),
+        };
+        assert_eq!(strong.if_range(), Some("\"abc\""));
+
+        // 弱 ETag 不能用于 If-Range
+        let weak = Validators {
+            etag: Some("W/\"abc\"".to_string()),
+            ..strong.clone()
+        };
+        assert_eq!(weak.if_range(), Some("Fri, 09 Jan 2026 00:00:00 GMT"));
+
+        assert!(strong.unchanged_since(&strong.clone()));
+        assert!(!strong.unchanged_since(&weak));
+        assert!(!strong.unchanged_since(&Validators::default()));
+        // 服务器不提供版本标识时无法判断
+        assert!(Validators::default().unchanged_since(&strong));
+    }
+
+    #[tokio::test]
+    async fn test_download_multithreaded_restarts_when_etag_changed() {
+        let mut server = mockito::Server::new_async().await;
+        let refetched = server
+            .mock("GET", "/snapshot.tgz")
+            .match_header("range", "bytes=4096-8191")
+            .with_status(206)
+            .with_body(&MIRROR_CONTENT[4096..8192])
+            .expect(1)
+            .create_async()
+            .await;
+        let _ranges = mock_ranges(&mut server, "/snapshot.tgz", &MIRROR_CONTENT).await;
+
+        let temp_dir = TempDir::new().unwrap();
+        let dest = temp_dir.path().join("snapshot.tgz");
+        let url = format!("{}/snapshot.tgz", server.url());
+        let total = MIRROR_CONTENT.len() as u64;
+
+        // 上次下载的是同名同大小的旧文件
+        std::fs::write(&dest, vec![0u8; MIRROR_CONTENT.len()]).unwrap();
+        let mut progress = Downloader::create_initial_progress(&url, total, 4096);
+        progress.validators.etag = Some("\"old\"".to_string());
+        progress.chunks[1].status = ChunkStatus::Completed;
+        Downloader::save_progress(&dest, &progress).await.unwrap();
+
+        let source = ChunkSource {
+            validators: Validators {
+                etag: Some("\"new\"".to_string()),
+                last_modified: None,
+            },
+            ..chunk_source(&server, "/snapshot.tgz")
+        };
+        fast_retry_downloader()
+            .download_multithreaded(&[source], &dest, total, None)
+            .await
+            .unwrap();
+
+        refetched.assert_async().await;
+        assert_eq!(std::fs::read(&dest).unwrap(), MIRROR_CONTENT);
+    }
+
+    #[tokio::test]
+    async fn test_download_multithreaded_detects_replaced_file() {
+        let mut server = mockito::Server::new_async().await;
+        // If-Range 不匹配时服务器返回完整的新文件
+        let _replaced = server
+            .mock("GET", "/snapshot.tgz")
+            .match_header("if-range", "\"v1\"")
+            .with_status(200)
+            .with_body(&MIRROR_CONTENT[..])
+            .create_async()
+            .await;
+
+        let temp_dir = TempDir::new().unwrap();
+        let dest = temp_dir.path().join("snapshot.tgz");
+        let source = ChunkSource {
+            validators: Validators {
+                etag: Some("\"v1\"".to_string()),
+                last_modified: None,
+            },
+            ..chunk_source(&server, "/snapshot.tgz")
+        };
+
+        let result = fast_retry_downloader()
+            .download_multithreaded(&[source], &dest, MIRROR_CONTENT.len() as u64, None)
+            .await;
+
+        assert!(matches!(result, Err(TronCtlError::RemoteFileChanged(_))));
+        assert!(!dest.exists());
+        assert!(!Downloader::progress_file(&dest).exists());
+    }
+
+    #[tokio::test]
+    async fn test_download_single_thread_restarts_when_remote_changed() {
+        let mut server = mockito::Server::new_async().await;
+        let content = b"new snapshot content";
+        let _head = server
+            .mock("HEAD", "/test.file")
+            .with_status(200)
+            .with_header("content-length", &content.len().to_string())
+            .create_async()
+            .await;
+        let replaced = server
+            .mock("GET", "/test.file")
+            .match_header("range", "bytes=4-")
+            .match_header("if-range", "\"old\"")
+            .with_status(200)
+            .with_header("etag", "\"new\"")
+            .with_body(content)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let temp_dir = TempDir::new().unwrap();
+        let dest = temp_dir.path().join("test.file");
+        let url = format!("{}/test.file", server.url());
+
+        // 上次中断时只下载了旧文件的前 4 个字节
+        std::fs::write(&dest, b"old ").unwrap();
+        let progress = DownloadProgress {
+            url: url.clone(),
+            total_size: content.len() as u64,
+            chunk_size: 0,
+            in_place: true,
+            validators: Validators {
+                etag: Some("\"old\"".to_string()),
+                last_modified: None,
+            },
+            chunks: Vec::new(),
+        };
+        Downloader::save_progress(&dest, &progress).await.unwrap();
+
+        fast_retry_downloader()
+            .download_with_progress(&url, &dest, None)
+            .await
+            .unwrap();
+
+        replaced.assert_async().await;
+        assert_eq!(std::fs::read(&dest).unwrap(), content);
+        assert!(!Downloader::progress_file(&dest).exists());
+    }
 }
@@ -1,17 +1,18 @@
-use crate::constants::{RECOMMENDED_DISK_GB, RECOMMENDED_MEMORY_GB, REQUIRED_JAVA_VERSION};
-use crate::error::{Result, TronCtlError};
+use crate::constants::{DATA_DIR, RECOMMENDED_MEMORY_GB};
+use crate::core::{JavaDiscovery, JavaRequirement};
+use crate::error::Result;
+use crate::models::java_info::JavaInstallation;
 use crate::utils::{fs, permissions};
 use std::path::Path;
-use std::process::Command;
 use sysinfo::System;
 use tracing::{info, warn};
 
 pub struct EnvironmentChecker;
 
 impl EnvironmentChecker {
+    /// Java 由调用方通过 [`JavaDiscovery`] 选择后单独检查
     pub fn check_all() -> Result<()> {
         Self::check_permissions()?;
-        Self::check_java_version()?;
         Self::check_memory()?;
         Self::check_disk_space()?;
         Ok(())
@@ -23,25 +24,14 @@ impl EnvironmentChecker {
         Ok(())
     }
 
-    fn check_java_version() -> Result<()> {
-        let output = Command::new("java")
-            .arg("-version")
-            .output()
-            .map_err(|_| TronCtlError::ConfigError("Java 未安装或不在 PATH 中".to_string()))?;
-
-        let version_str = String::from_utf8_lossy(&output.stderr);
-
-        if version_str.contains("1.8") || version_str.contains("\"8\"") {
-            info!("Java 版本检查通过 (1.8)");
-            Ok(())
-        } else {
-            let current_version = version_str.lines().next().unwrap_or("unknown").to_string();
-
-            Err(TronCtlError::IncompatibleJavaVersion {
-                required: REQUIRED_JAVA_VERSION.to_string(),
-                current: current_version,
-            })
-        }
+    /// 检查配置的 java_path 是否满足 FullNode.jar 要求的 JDK 版本
+    pub fn check_java_version(
+        java_path: &Path,
+        requirement: &JavaRequirement,
+    ) -> Result<JavaInstallation> {
+        let java = JavaDiscovery::check_path(java_path, requirement)?;
+        info!("Java 版本检查通过: {}", java.summary());
+        Ok(java)
     }
 
     fn check_memory() -> Result<()> {
@@ -62,18 +52,10 @@ impl EnvironmentChecker {
         Ok(())
     }
 
+    /// 只报告数据目录可用空间，是否足够由选择快照后的磁盘规划判断
     fn check_disk_space() -> Result<()> {
-        let available_gb = fs::get_disk_free_space(Path::new("/"))?;
-
-        if available_gb < RECOMMENDED_DISK_GB {
-            warn!(
-                "磁盘空间不足: 推荐 {}GB, 当前 {}GB",
-                RECOMMENDED_DISK_GB, available_gb
-            );
-        } else {
-            info!("磁盘空间检查通过 ({}GB)", available_gb);
-        }
-
+        let available_gb = fs::get_disk_free_space(Path::new(DATA_DIR))?;
+        info!("数据目录可用磁盘空间: {}GB", available_gb);
         Ok(())
     }
 }
@@ -103,8 +85,10 @@ mod tests {
 
     #[test]
     fn test_check_java_version_consistency() {
-        let result1 = EnvironmentChecker::check_java_version();
-        let result2 = EnvironmentChecker::check_java_version();
+        let java_path = Path::new("/usr/bin/java");
+        let requirement = JavaRequirement::for_jar(Path::new("/nonexistent/FullNode.jar"));
+        let result1 = EnvironmentChecker::check_java_version(java_path, &requirement);
+        let result2 = EnvironmentChecker::check_java_version(java_path, &requirement);
         assert_eq!(result1.is_ok(), result2.is_ok());
     }
 }
@@ -2,20 +2,29 @@ use crate::constants::{BLOCK_HEIGHT_CHECK_COUNT, HEALTH_CHECK_INTERVAL_SECS, RPC
 use crate::core::ProcessManager;
 use crate::error::{Result, TronCtlError};
 use crate::models::health_status::{BlockInfo, HealthStatus};
+use crate::models::node_state::NodeStatus;
 use reqwest::Client;
+use std::time::{Duration, Instant};
 use tracing::{debug, info};
 
 pub struct HealthChecker {
     client: Client,
+    endpoint: String,
 }
 
 impl HealthChecker {
     pub fn new() -> Self {
+        Self::with_endpoint(RPC_ENDPOINT)
+    }
+
+    /// 指定 `getnowblock` 地址
+    pub fn with_endpoint(endpoint: &str) -> Self {
         Self {
             client: Client::builder()
                 .timeout(std::time::Duration::from_secs(5))
                 .build()
                 .expect("Failed to build HTTP client"),
+            endpoint: endpoint.to_string(),
         }
     }
 
@@ -74,11 +83,74 @@ impl HealthChecker {
         Ok(is_syncing)
     }
 
+    /// 等待节点就绪：进程存活、RPC 响应且区块高度开始增长，超时或进程退出时返回错误
+    pub async fn wait_healthy(
+        &self,
+        pid: i32,
+        timeout: Duration,
+        interval: Duration,
+    ) -> Result<u64> {
+        let deadline = Instant::now() + timeout;
+        let mut first_block = None;
+
+        loop {
+            if !ProcessManager::is_process_alive(pid) {
+                return Err(TronCtlError::HealthCheckFailed(format!(
+                    "进程 {} 已退出",
+                    pid
+                )));
+            }
+
+            if let Ok(block) = self.get_current_block().await {
+                match first_block {
+                    None => {
+                        info!("RPC 已响应，当前区块: {}", block);
+                        first_block = Some(block);
+                    }
+                    Some(first) if block > first => {
+                        info!("区块高度增长: {} -> {}", first, block);
+                        return Ok(block);
+                    }
+                    Some(_) => debug!("区块高度未变化: {}", block),
+                }
+            }
+
+            if Instant::now() >= deadline {
+                let reason = match first_block {
+                    None => "RPC 未响应",
+                    Some(_) => "区块高度未增长",
+                };
+                return Err(TronCtlError::HealthCheckFailed(format!(
+                    "{} 秒内{}",
+                    timeout.as_secs(),
+                    reason
+                )));
+            }
+
+            tokio::time::sleep(interval).await;
+        }
+    }
+
+    /// 对等节点规则: 持续无连接超过 timeout 判定为异常
+    pub fn evaluate_peers(
+        pid: i32,
+        zero_peers_for: Option<Duration>,
+        timeout: Duration,
+    ) -> NodeStatus {
+        match zero_peers_for {
+            Some(elapsed) if elapsed >= timeout => NodeStatus::Unhealthy {
+                pid,
+                reason: format!("已持续 {} 秒无对等节点连接", elapsed.as_secs()),
+            },
+            _ => NodeStatus::Running { pid },
+        }
+    }
+
     /// 获取当前区块高度
     async fn get_current_block(&self) -> Result<u64> {
-        debug!("查询当前区块: {}", RPC_ENDPOINT);
+        debug!("查询当前区块: {}", self.endpoint);
 
-        let resp = self.client.get(RPC_ENDPOINT).send().await?;
+        let resp = self.client.get(&self.endpoint).send().await?;
 
         if !resp.status().is_success() {
             return Err(TronCtlError::RpcCallFailed(format!(
@@ -114,6 +186,105 @@ mod tests {
         assert!(std::ptr::addr_of!(checker.client) as usize != 0);
     }
 
+    #[test]
+    fn test_evaluate_peers_with_peers() {
+        let status = HealthChecker::evaluate_peers(1, None, Duration::from_secs(600));
+        assert!(matches!(status, NodeStatus::Running { pid: 1 }));
+    }
+
+    #[test]
+    fn test_evaluate_peers_zero_within_timeout() {
+        let status = HealthChecker::evaluate_peers(
+            1,
+            Some(Duration::from_secs(599)),
+            Duration::from_secs(600),
+        );
+        assert!(matches!(status, NodeStatus::Running { .. }));
+    }
+
+    #[test]
+    fn test_evaluate_peers_zero_exceeds_timeout() {
+        let status = HealthChecker::evaluate_peers(
+            7,
+            Some(Duration::from_secs(600)),
+            Duration::from_secs(600),
+        );
+        match status {
+            NodeStatus::Unhealthy { pid, reason } => {
+                assert_eq!(pid, 7);
+                assert!(reason.contains("600"));
+            }
+            _ => panic!("Expected Unhealthy status"),
+        }
+    }
+
+    fn block_body(number: u64) -> String {
+        format!(
+            r#"{{"block_header":{{"raw_data":{{"number":{},"timestamp":0}}}}}}"#,
+            number
+        )
+    }
+
+    #[tokio::test]
+    async fn test_wait_healthy_block_progress() {
+        use std::sync::Arc;
+        use std::sync::atomic::{AtomicU64, Ordering};
+
+        let mut server = mockito::Server::new_async().await;
+        let height = Arc::new(AtomicU64::new(100));
+        let counter = Arc::clone(&height);
+        let _mock = server
+            .mock("GET", "/wallet/getnowblock")
+            .with_status(200)
+            .with_body_from_request(move |_| {
+                block_body(counter.fetch_add(1, Ordering::SeqCst)).into_bytes()
+            })
+            .expect_at_least(2)
+            .create_async()
+            .await;
+
+        let checker = HealthChecker::with_endpoint(&format!("{}/wallet/getnowblock", server.url()));
+        let pid = std::process::id() as i32;
+        let block = checker
+            .wait_healthy(pid, Duration::from_secs(5), Duration::from_millis(10))
+            .await
+            .unwrap();
+
+        assert!(block > 100);
+    }
+
+    #[tokio::test]
+    async fn test_wait_healthy_timeout_without_progress() {
+        let mut server = mockito::Server::new_async().await;
+        let _mock = server
+            .mock("GET", "/wallet/getnowblock")
+            .with_status(200)
+            .with_body(block_body(100))
+            .create_async()
+            .await;
+
+        let checker = HealthChecker::with_endpoint(&format!("{}/wallet/getnowblock", server.url()));
+        let pid = std::process::id() as i32;
+        let result = checker
+            .wait_healthy(pid, Duration::from_millis(100), Duration::from_millis(20))
+            .await;
+
+        match result {
+            Err(TronCtlError::HealthCheckFailed(reason)) => assert!(reason.contains("未增长")),
+            other => panic!("Expected HealthCheckFailed, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_wait_healthy_dead_process() {
+        let checker = HealthChecker::with_endpoint("http://127.0.0.1:1/wallet/getnowblock");
+        let result = checker
+            .wait_healthy(999999, Duration::from_secs(1), Duration::from_millis(10))
+            .await;
+
+        assert!(matches!(result, Err(TronCtlError::HealthCheckFailed(_))));
+    }
+
     #[tokio::test]
     async fn test_check_dead_process() {
         let checker = HealthChecker::new();
@@ -0,0 +1,674 @@
+use crate::constants::{
+    MIN_CPU_CORES, MIN_MEMORY_GB, RECOMMENDED_CPU_CORES, RECOMMENDED_DISK_GB,
+    RECOMMENDED_MAX_MAP_COUNT, RECOMMENDED_MEMORY_GB, RECOMMENDED_NOFILE, RECOMMENDED_NPROC,
+    SYSTEMD_SERVICE_FILE,
+};
+use crate::core::{EnvironmentChecker, JavaRequirement, Supervisor};
+use crate::models::TronCtlConfig;
+use crate::models::check_result::CheckResult;
+use crate::utils::{fs, node_conf, permissions};
+use std::path::Path;
+use std::process::Command;
+use std::time::Duration;
+use sysinfo::System;
+
+/// 用于检测出站 DNS 的域名（FullNode.jar 与参考节点所在域名）
+const DNS_PROBE_HOSTS: &[&str] = &["api.github.com", "api.trongrid.io"];
+
+/// 主机环境审计，每项检查给出 pass/warn/fail 及修复建议
+pub struct HostAuditor;
+
+impl HostAuditor {
+    pub async fn audit(config: &TronCtlConfig) -> Vec<CheckResult> {
+        let mut results = vec![
+            Self::check_root(),
+            Self::check_java(
+                &config.java_path,
+                &JavaRequirement::for_jar(&config.fullnode_jar),
+            ),
+            Self::check_cpu(num_cpus::get()),
+            Self::check_memory(),
+            Self::check_disk(&config.data_dir),
+        ];
+
+        results.extend(Self::check_limits(&NodeLimits::detect()));
+
+        let meminfo = std::fs::read_to_string("/proc/meminfo").unwrap_or_default();
+        let swappiness = read_trimmed("/proc/sys/vm/swappiness");
+        results.push(Self::check_swap(&meminfo, swappiness.as_deref()));
+
+        results.push(Self::check_thp(
+            read_trimmed("/sys/kernel/mm/transparent_hugepage/enabled").as_deref(),
+        ));
+        results.push(Self::check_max_map_count(
+            read_trimmed("/proc/sys/vm/max_map_count").as_deref(),
+        ));
+        results.push(Self::check_clock_sync());
+        results.extend(Self::check_storage(&config.data_dir));
+        results.push(Self::check_ipv6(&config.node_config));
+        results.push(Self::check_dns().await);
+
+        results
+    }
+
+    fn check_root() -> CheckResult {
+        if permissions::is_root() {
+            CheckResult::pass("root 权限", "以 root 运行")
+        } else {
+            CheckResult::fail("root 权限", "非 root 用户", "使用 sudo 运行 tronctl")
+        }
+    }
+
+    fn check_java(java_path: &Path, requirement: &JavaRequirement) -> CheckResult {
+        match EnvironmentChecker::check_java_version(java_path, requirement) {
+            Ok(java) => CheckResult::pass("Java", java.summary()),
+            Err(e) => CheckResult::fail(
+                "Java",
+                e.to_string(),
+                format!(
+                    "安装 JDK {}，或运行 'tronctl java use' 选择已安装的 JDK",
+                    requirement
+                ),
+            ),
+        }
+    }
+
+    fn check_cpu(cores: usize) -> CheckResult {
+        let name = "CPU 核心数";
+        let message = format!("{} 核", cores);
+
+        if cores < MIN_CPU_CORES {
+            CheckResult::fail(
+                name,
+                message,
+                format!(
+                    "至少需要 {} 核，推荐 {} 核以上",
+                    MIN_CPU_CORES, RECOMMENDED_CPU_CORES
+                ),
+            )
+        } else if cores < RECOMMENDED_CPU_CORES {
+            CheckResult::warn(
+                name,
+                message,
+                format!("推荐 {} 核以上以保证同步速度", RECOMMENDED_CPU_CORES),
+            )
+        } else {
+            CheckResult::pass(name, message)
+        }
+    }
+
+    fn check_memory() -> CheckResult {
+        let mut sys = System::new();
+        sys.refresh_memory();
+        let total_gb = sys.total_memory() / (1024 * 1024 * 1024);
+        let name = "内存";
+        let message = format!("{}GB", total_gb);
+
+        if total_gb < MIN_MEMORY_GB {
+            CheckResult::fail(name, message, format!("至少需要 {}GB 内存", MIN_MEMORY_GB))
+        } else if total_gb < RECOMMENDED_MEMORY_GB {
+            CheckResult::warn(
+                name,
+                message,
+                format!("推荐 {}GB 内存", RECOMMENDED_MEMORY_GB),
+            )
+        } else {
+            CheckResult::pass(name, message)
+        }
+    }
+
+    fn check_disk(data_dir: &Path) -> CheckResult {
+        let name = "磁盘空间";
+
+        match fs::get_disk_free_space(data_dir) {
+            Ok(gb) if gb < RECOMMENDED_DISK_GB => CheckResult::warn(
+                name,
+                format!("数据目录可用 {}GB", gb),
+                format!("Full 快照推荐 {}GB 以上可用空间", RECOMMENDED_DISK_GB),
+            ),
+            Ok(gb) => CheckResult::pass(name, format!("数据目录可用 {}GB", gb)),
+            Err(e) => CheckResult::warn(name, format!("无法检测: {}", e), "检查数据目录是否存在"),
+        }
+    }
+
+    /// 检查节点进程可用的 nofile 与 nproc 软限制
+    fn check_limits(limits: &NodeLimits) -> Vec<CheckResult> {
+        [
+            (
+                "ulimit nofile",
+                limits.nofile,
+                RECOMMENDED_NOFILE,
+                "nofile",
+                "LimitNOFILE",
+            ),
+            (
+                "ulimit nproc",
+                limits.nproc,
+                RECOMMENDED_NPROC,
+                "nproc",
+                "LimitNPROC",
+            ),
+        ]
+        .into_iter()
+        .map(|(name, soft, recommended, item, directive)| match soft {
+            Some(soft) if soft >= recommended => {
+                CheckResult::pass(name, format!("{} ({})", soft, limits.source))
+            }
+            Some(soft) => CheckResult::warn(
+                name,
+                format!("{} (推荐 >= {}，{})", soft, recommended, limits.source),
+                format!(
+                    "在 /etc/security/limits.conf 中设置 '* - {} {}'，systemd 服务使用 {}={}",
+                    item, recommended, directive, recommended
+                ),
+            ),
+            None => CheckResult::warn(
+                name,
+                format!("{}: 未设置或无法读取", limits.source),
+                format!("systemd 服务使用 {}={}", directive, recommended),
+            ),
+        })
+        .collect()
+    }
+
+    fn check_swap(meminfo: &str, swappiness: Option<&str>) -> CheckResult {
+        let name = "swap";
+        let swap_kb = meminfo
+            .lines()
+            .find_map(|l| l.strip_prefix("SwapTotal:"))
+            .and_then(|v| v.split_whitespace().next())
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(0);
+        let swappiness: Option<u64> = swappiness.and_then(|v| v.parse().ok());
+
+        if swap_kb == 0 {
+            return CheckResult::pass(name, "未启用");
+        }
+
+        match swappiness {
+            Some(s) if s > 10 => CheckResult::warn(
+                name,
+                format!("已启用 {}MB, swappiness={}", swap_kb / 1024, s),
+                "JVM 堆被换出会导致长时间停顿: sysctl -w vm.swappiness=1",
+            ),
+            Some(s) => CheckResult::pass(
+                name,
+                format!("已启用 {}MB, swappiness={}", swap_kb / 1024, s),
+            ),
+            None => CheckResult::pass(name, format!("已启用 {}MB", swap_kb / 1024)),
+        }
+    }
+
+    /// 透明大页设置为 always 时 JVM 可能出现延迟抖动
+    fn check_thp(enabled: Option<&str>) -> CheckResult {
+        let name = "透明大页 (THP)";
+        let mode = enabled.and_then(|v| {
+            v.split_whitespace()
+                .find(|m| m.starts_with('['))
+                .map(|m| m.trim_matches(['[', ']']))
+        });
+
+        match mode {
+            Some("always") => CheckResult::warn(
+                name,
+                "always",
+                "echo madvise > /sys/kernel/mm/transparent_hugepage/enabled",
+            ),
+            Some(mode) => CheckResult::pass(name, mode.to_string()),
+            None => CheckResult::pass(name, "内核未启用"),
+        }
+    }
+
+    fn check_max_map_count(value: Option<&str>) -> CheckResult {
+        let name = "vm.max_map_count";
+
+        match value.and_then(|v| v.parse::<u64>().ok()) {
+            Some(v) if v >= RECOMMENDED_MAX_MAP_COUNT => CheckResult::pass(name, v.to_string()),
+            Some(v) => CheckResult::warn(
+                name,
+                format!("{} (推荐 >= {})", v, RECOMMENDED_MAX_MAP_COUNT),
+                format!("sysctl -w vm.max_map_count={}", RECOMMENDED_MAX_MAP_COUNT),
+            ),
+            None => CheckResult::warn(name, "无法读取", "检查 /proc/sys/vm/max_map_count"),
+        }
+    }
+
+    fn check_clock_sync() -> CheckResult {
+        let timedatectl =
+            command_output("timedatectl", &["show", "-p", "NTPSynchronized", "--value"]);
+        let chrony = command_output("chronyc", &["tracking"]);
+        Self::evaluate_clock_sync(timedatectl.as_deref(), chrony.as_deref())
+    }
+
+    fn evaluate_clock_sync(timedatectl: Option<&str>, chrony: Option<&str>) -> CheckResult {
+        let name = "时钟同步";
+        let hint = "启用 chrony 或 systemd-timesyncd: timedatectl set-ntp true";
+
+        if let Some(chrony) = chrony
+            && chrony
+                .lines()
+                .any(|l| l.starts_with("Leap status") && l.contains("Normal"))
+        {
+            return CheckResult::pass(name, "chrony 已同步");
+        }
+
+        match timedatectl.map(str::trim) {
+            Some("yes") => CheckResult::pass(name, "NTP 已同步"),
+            Some(_) => CheckResult::fail(name, "NTP 未同步，区块时间戳校验可能失败", hint),
+            None => CheckResult::warn(name, "未检测到 chrony 或 timesyncd", hint),
+        }
+    }
+
+    fn check_storage(data_dir: &Path) -> Vec<CheckResult> {
+        let mount = match fs::find_mount(data_dir) {
+            Ok(Some(m)) => m,
+            _ => {
+                return vec![CheckResult::warn(
+                    "文件系统",
+                    "无法确定数据目录所在挂载点",
+                    "检查 /proc/self/mounts",
+                )];
+            }
+        };
+
+        let rotational = block_device_name(&mount.device).and_then(|dev| read_rotational(&dev));
+
+        vec![
+            Self::evaluate_filesystem(&mount.fs_type, &mount.mount_point.to_string_lossy()),
+            Self::evaluate_mount_options(&mount.options),
+            Self::evaluate_rotational(&mount.device, rotational),
+        ]
+    }
+
+    fn evaluate_filesystem(fs_type: &str, mount_point: &str) -> CheckResult {
+        let name = "文件系统";
+        let message = format!("{} ({})", fs_type, mount_point);
+
+        match fs_type {
+            "ext4" | "xfs" => CheckResult::pass(name, message),
+            "btrfs" | "zfs" => CheckResult::warn(
+                name,
+                message,
+                "写时复制文件系统会显著降低数据库写入性能，建议为数据目录禁用 CoW 或使用 ext4/xfs",
+            ),
+            "tmpfs" | "overlay" | "nfs" | "nfs4" | "cifs" | "fuse" | "fuseblk" => {
+                CheckResult::fail(name, message, "将数据目录放在本地 ext4/xfs 磁盘上")
+            }
+            _ => CheckResult::warn(name, message, "推荐使用 ext4 或 xfs"),
+        }
+    }
+
+    fn evaluate_mount_options(options: &[String]) -> CheckResult {
+        let name = "挂载选项";
+        let message = options.join(",");
+        let has = |opt: &str| options.iter().any(|o| o == opt);
+
+        if has("ro") {
+            CheckResult::fail(name, message, "数据目录所在分区为只读")
+        } else if has("sync") || has("strictatime") {
+            CheckResult::warn(name, message, "移除 sync/strictatime 选项，推荐 noatime")
+        } else {
+            CheckResult::pass(name, message)
+        }
+    }
+
+    fn evaluate_rotational(device: &str, rotational: Option<bool>) -> CheckResult {
+        let name = "存储介质";
+
+        match rotational {
+            Some(false) => CheckResult::pass(name, format!("{} (SSD)", device)),
+            Some(true) => CheckResult::fail(
+                name,
+                format!("{} (机械硬盘)", device),
+                "机械硬盘无法跟上区块同步，请将数据目录放在 SSD/NVMe 上",
+            ),
+            None => CheckResult::warn(
+                name,
+                format!("{} (未知)", device),
+                "确认数据目录位于 SSD 上",
+            ),
+        }
+    }
+
+    fn check_ipv6(node_config: &Path) -> CheckResult {
+        let name = "IPv6";
+        let has_global = std::fs::read_to_string("/proc/net/if_inet6")
+            .map(|c| has_global_ipv6(&c))
+            .unwrap_or(false);
+        let enabled_in_node = std::fs::read_to_string(node_config)
+            .ok()
+            .and_then(|c| node_conf::get_value(&c, "node.enableIpv6"))
+            .is_some_and(|v| v == "true");
+
+        match (enabled_in_node, has_global) {
+            (true, false) => CheckResult::fail(
+                name,
+                "节点配置启用了 IPv6，但主机没有全局 IPv6 地址",
+                "配置 IPv6 地址，或在 tron.conf 中设置 node.enableIpv6 = false",
+            ),
+            (_, true) => CheckResult::pass(name, "有全局 IPv6 地址"),
+            (false, false) => CheckResult::pass(name, "无全局 IPv6 地址（节点仅使用 IPv4）"),
+        }
+    }
+
+    async fn check_dns() -> CheckResult {
+        let name = "出站 DNS";
+
+        for host in DNS_PROBE_HOSTS {
+            let lookup = tokio::net::lookup_host(format!("{}:443", host));
+            let resolved = match tokio::time::timeout(Duration::from_secs(5), lookup).await {
+                Ok(Ok(mut addrs)) => addrs.next().is_some(),
+                _ => false,
+            };
+            if !resolved {
+                return CheckResult::fail(
+                    name,
+                    format!("无法解析 {}", host),
+                    "检查 /etc/resolv.conf 与出站 53 端口",
+                );
+            }
+        }
+
+        CheckResult::pass(name, format!("{} 解析正常", DNS_PROBE_HOSTS.join(", ")))
+    }
+}
+
+/// 节点进程可用的 nofile / nproc 软限制及其来源
+struct NodeLimits {
+    source: String,
+    nofile: Option<u64>,
+    nproc: Option<u64>,
+}
+
+impl NodeLimits {
+    /// 节点运行中时读取其 /proc/<pid>/limits；否则读取 systemd 服务的 Limit* 设置；
+    /// 都没有时为当前 shell 的限制，`tronctl start` 启动的节点继承该限制
+    fn detect() -> Self {
+        if let Ok(Some(pid)) = Supervisor::detect().running_pid() {
+            let limits =
+                std::fs::read_to_string(format!("/proc/{}/limits", pid)).unwrap_or_default();
+            return Self::from_proc(format!("节点进程 {}", pid), &limits);
+        }
+
+        match std::fs::read_to_string(SYSTEMD_SERVICE_FILE) {
+            Ok(unit) => Self::from_unit(&unit),
+            Err(_) => {
+                let limits = std::fs::read_to_string("/proc/self/limits").unwrap_or_default();
+                Self::from_proc(
+                    "当前 shell，tronctl start 启动的节点继承".to_string(),
+                    &limits,
+                )
+            }
+        }
+    }
+
+    fn from_proc(source: String, limits: &str) -> Self {
+        Self {
+            source,
+            nofile: parse_soft_limit(limits, "Max open files"),
+            nproc: parse_soft_limit(limits, "Max processes"),
+        }
+    }
+
+    fn from_unit(unit: &str) -> Self {
+        Self {
+            source: "systemd 服务".to_string(),
+            nofile: parse_unit_limit(unit, "LimitNOFILE"),
+            nproc: parse_unit_limit(unit, "LimitNPROC"),
+        }
+    }
+}
+
+/// 解析 systemd 服务中 `LimitXXX=soft[:hard]` 的软限制，`infinity` 视为不限
+fn parse_unit_limit(unit: &str, directive: &str) -> Option<u64> {
+    let value = unit.lines().rev().find_map(|line| {
+        let (key, value) = line.trim().split_once('=')?;
+        (key.trim() == directive).then(|| value.trim())
+    })?;
+    let soft = value.split(':').next()?.trim();
+
+    if soft == "infinity" {
+        Some(u64::MAX)
+    } else {
+        soft.parse().ok()
+    }
+}
+
+fn parse_soft_limit(limits: &str, label: &str) -> Option<u64> {
+    let line = limits.lines().find(|l| l.starts_with(label))?;
+    let soft = line[label.len()..].split_whitespace().next()?;
+
+    if soft == "unlimited" {
+        Some(u64::MAX)
+    } else {
+        soft.parse().ok()
+    }
+}
+
+/// /proc/net/if_inet6 中 scope 为 00 的地址为全局地址
+fn has_global_ipv6(if_inet6: &str) -> bool {
+    if_inet6
+        .lines()
+        .any(|l| l.split_whitespace().nth(3) == Some("00"))
+}
+
+/// 将挂载设备解析为 /sys/class/block 下的名称（跟随 /dev/mapper 等符号链接）
+fn block_device_name(device: &str) -> Option<String> {
+    if !device.starts_with("/dev/") {
+        return None;
+    }
+
+    let resolved = Path::new(device).canonicalize().ok()?;
+    resolved
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+}
+
+/// 分区没有 queue 目录，需要回到所属磁盘读取
+fn read_rotational(block_name: &str) -> Option<bool> {
+    let sys_path = Path::new("/sys/class/block")
+        .join(block_name)
+        .canonicalize()
+        .ok()?;
+
+    [
+        sys_path.join("queue/rotational"),
+        sys_path.join("../queue/rotational"),
+    ]
+    .iter()
+    .find_map(|p| std::fs::read_to_string(p).ok())
+    .map(|v| v.trim() == "1")
+}
+
+fn read_trimmed(path: &str) -> Option<String> {
+    std::fs::read_to_string(path)
+        .ok()
+        .map(|s| s.trim().to_string())
+}
+
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::check_result::CheckLevel;
+
+    const LIMITS: &str = "Limit                     Soft Limit           Hard Limit           Units\n\
+        Max processes             4096                 24001                processes\n\
+        Max open files            unlimited            unlimited            files\n";
+
+    #[test]
+    fn test_parse_soft_limit() {
+        assert_eq!(parse_soft_limit(LIMITS, "Max processes"), Some(4096));
+        assert_eq!(parse_soft_limit(LIMITS, "Max open files"), Some(u64::MAX));
+        assert_eq!(parse_soft_limit(LIMITS, "Max locked memory"), None);
+    }
+
+    #[test]
+    fn test_check_limits() {
+        let limits = NodeLimits::from_proc("节点进程 1".to_string(), LIMITS);
+        let results = HostAuditor::check_limits(&limits);
+        assert_eq!(results[0].level, CheckLevel::Pass);
+        assert_eq!(results[1].level, CheckLevel::Warn);
+    }
+
+    #[test]
+    fn test_limits_from_unit() {
+        let unit = "[Service]\nLimitNOFILE=1048576\nLimitNPROC=4096:65536\n";
+        let limits = NodeLimits::from_unit(unit);
+        assert_eq!(limits.nofile, Some(1048576));
+        assert_eq!(limits.nproc, Some(4096));
+
+        let limits = NodeLimits::from_unit("[Service]\nLimitNOFILE=infinity\n");
+        assert_eq!(limits.nofile, Some(u64::MAX));
+        assert_eq!(limits.nproc, None);
+        assert_eq!(
+            HostAuditor::check_limits(&limits)[1].level,
+            CheckLevel::Warn
+        );
+    }
+
+    #[test]
+    fn test_check_cpu() {
+        assert_eq!(HostAuditor::check_cpu(2).level, CheckLevel::Fail);
+        assert_eq!(HostAuditor::check_cpu(6).level, CheckLevel::Warn);
+        assert_eq!(HostAuditor::check_cpu(16).level, CheckLevel::Pass);
+    }
+
+    #[test]
+    fn test_check_swap() {
+        let no_swap = "MemTotal: 32000000 kB\nSwapTotal: 0 kB\n";
+        assert_eq!(
+            HostAuditor::check_swap(no_swap, Some("60")).level,
+            CheckLevel::Pass
+        );
+
+        let swap = "SwapTotal: 8388608 kB\n";
+        assert_eq!(
+            HostAuditor::check_swap(swap, Some("60")).level,
+            CheckLevel::Warn
+        );
+        assert_eq!(
+            HostAuditor::check_swap(swap, Some("1")).level,
+            CheckLevel::Pass
+        );
+    }
+
+    #[test]
+    fn test_check_thp() {
+        assert_eq!(
+            HostAuditor::check_thp(Some("[always] madvise never")).level,
+            CheckLevel::Warn
+        );
+        let madvise = HostAuditor::check_thp(Some("always [madvise] never"));
+        assert_eq!(madvise.level, CheckLevel::Pass);
+        assert_eq!(madvise.message, "madvise");
+    }
+
+    #[test]
+    fn test_check_max_map_count() {
+        assert_eq!(
+            HostAuditor::check_max_map_count(Some("65530")).level,
+            CheckLevel::Warn
+        );
+        assert_eq!(
+            HostAuditor::check_max_map_count(Some("1048576")).level,
+            CheckLevel::Pass
+        );
+        assert_eq!(
+            HostAuditor::check_max_map_count(None).level,
+            CheckLevel::Warn
+        );
+    }
+
+    #[test]
+    fn test_evaluate_clock_sync() {
+        assert_eq!(
+            HostAuditor::evaluate_clock_sync(Some("yes\n"), None).level,
+            CheckLevel::Pass
+        );
+        assert_eq!(
+            HostAuditor::evaluate_clock_sync(Some("no\n"), None).level,
+            CheckLevel::Fail
+        );
+        assert_eq!(
+            HostAuditor::evaluate_clock_sync(None, Some("Leap status     : Normal\n")).level,
+            CheckLevel::Pass
+        );
+        assert_eq!(
+            HostAuditor::evaluate_clock_sync(None, None).level,
+            CheckLevel::Warn
+        );
+    }
+
+    #[test]
+    fn test_evaluate_filesystem() {
+        assert_eq!(
+            HostAuditor::evaluate_filesystem("xfs", "/").level,
+            CheckLevel::Pass
+        );
+        assert_eq!(
+            HostAuditor::evaluate_filesystem("btrfs", "/").level,
+            CheckLevel::Warn
+        );
+        assert_eq!(
+            HostAuditor::evaluate_filesystem("nfs4", "/").level,
+            CheckLevel::Fail
+        );
+    }
+
+    #[test]
+    fn test_evaluate_mount_options() {
+        let opts = |s: &str| s.split(',').map(str::to_string).collect::<Vec<_>>();
+        assert_eq!(
+            HostAuditor::evaluate_mount_options(&opts("rw,noatime")).level,
+            CheckLevel::Pass
+        );
+        assert_eq!(
+            HostAuditor::evaluate_mount_options(&opts("rw,sync")).level,
+            CheckLevel::Warn
+        );
+        assert_eq!(
+            HostAuditor::evaluate_mount_options(&opts("ro,relatime")).level,
+            CheckLevel::Fail
+        );
+    }
+
+    #[test]
+    fn test_evaluate_rotational() {
+        assert_eq!(
+            HostAuditor::evaluate_rotational("/dev/sda1", Some(true)).level,
+            CheckLevel::Fail
+        );
+        assert_eq!(
+            HostAuditor::evaluate_rotational("/dev/nvme0n1p1", Some(false)).level,
+            CheckLevel::Pass
+        );
+    }
+
+    #[test]
+    fn test_has_global_ipv6() {
+        let link_local_only = "fe800000000000000000000000000001 02 40 20 80 eth0\n";
+        assert!(!has_global_ipv6(link_local_only));
+
+        let global = "20010db8000000000000000000000001 02 40 00 80 eth0\n";
+        assert!(has_global_ipv6(global));
+    }
+
+    #[test]
+    fn test_block_device_name_non_device() {
+        assert!(block_device_name("tmpfs").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_audit_executes() {
+        let results = HostAuditor::audit(&TronCtlConfig::default()).await;
+        assert!(results.len() >= 14);
+    }
+}
@@ -0,0 +1,296 @@
+use crate::constants::{
+    FULLNODE_MAIN_CLASS, JAVA_RUNTIME_DIR, JAVA_SEARCH_DIRS, REQUIRED_JAVA_MAJOR_AARCH64,
+    REQUIRED_JAVA_MAJOR_X86_64,
+};
+use crate::error::{Result, TronCtlError};
+use crate::models::java_info::{JavaInstallation, normalize_arch, parse_major};
+use crate::utils::archive;
+use std::collections::HashSet;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tracing::{debug, warn};
+
+/// FullNode.jar 对 Java 运行时的要求
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JavaRequirement {
+    /// 要求的 JDK 主版本，架构不受 java-tron 支持时为 None
+    pub major: Option<u32>,
+    pub arch: String,
+}
+
+impl JavaRequirement {
+    /// 由 jar 的字节码版本与本机架构共同决定；jar 不存在或无法读取时只按架构判断
+    pub fn for_jar(jar: &Path) -> Self {
+        let bytecode_major = if jar.exists() {
+            archive::class_java_major(jar, FULLNODE_MAIN_CLASS)
+                .inspect_err(|e| warn!("无法读取 {:?} 的字节码版本，按本机架构判断: {}", jar, e))
+                .ok()
+        } else {
+            debug!("{:?} 不存在，按本机架构判断 Java 版本", jar);
+            None
+        };
+        Self::new(&JavaDiscovery::host_arch(), bytecode_major)
+    }
+
+    /// 只按本机架构判断的要求
+    pub fn for_host() -> Self {
+        Self::new(&JavaDiscovery::host_arch(), None)
+    }
+
+    /// 架构决定最低版本（x86_64 为 8，ARM 为 17），jar 编译目标更高时以 jar 为准
+    fn new(arch: &str, bytecode_major: Option<u32>) -> Self {
+        let arch = normalize_arch(arch);
+        let arch_major = match arch.as_str() {
+            "x86_64" => Some(REQUIRED_JAVA_MAJOR_X86_64),
+            "aarch64" => Some(REQUIRED_JAVA_MAJOR_AARCH64),
+            _ => None,
+        };
+
+        Self {
+            major: arch_major.map(|major| bytecode_major.map_or(major, |b| major.max(b))),
+            arch,
+        }
+    }
+
+    /// 检查运行时的主版本与架构是否满足要求
+    pub fn check(&self, java: &JavaInstallation) -> Result<()> {
+        if java.arch == self.arch && self.major == Some(java.major) {
+            Ok(())
+        } else {
+            Err(TronCtlError::IncompatibleJavaVersion {
+                required: self.to_string(),
+                current: java.summary(),
+            })
+        }
+    }
+}
+
+/// 要求描述，如 `8 (x86_64)`
+impl fmt::Display for JavaRequirement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.major {
+            Some(major) => write!(f, "{} ({})", major, self.arch),
+            None => write!(f, "java-tron 不支持的架构 ({})", self.arch),
+        }
+    }
+}
+
+/// 查找并识别本机的 Java 运行时
+pub struct JavaDiscovery;
+
+impl JavaDiscovery {
+    /// 本机 CPU 架构
+    pub fn host_arch() -> String {
+        normalize_arch(std::env::consts::ARCH)
+    }
+
+    /// 探测指定路径的 java，并检查是否满足要求
+    pub fn check_path(java_path: &Path, requirement: &JavaRequirement) -> Result<JavaInstallation> {
+        let java = Self::probe(java_path).ok_or_else(|| {
+            TronCtlError::ConfigError(format!("无法执行 Java: {}", java_path.display()))
+        })?;
+        requirement.check(&java)?;
+        Ok(java)
+    }
+
+    /// 列出所有找到的 Java 运行时，已配置的路径排在最前
+    pub fn discover(configured: &Path) -> Vec<JavaInstallation> {
+        let mut seen = HashSet::new();
+
+        Self::candidates(configured)
+            .into_iter()
+            .filter(|path| seen.insert(path.canonicalize().unwrap_or_else(|_| path.clone())))
+            .filter_map(|path| Self::probe(&path))
+            .collect()
+    }
+
+    /// 候选路径：配置的 java_path、JAVA_HOME、PATH、alternatives、tronctl 管理的运行时与常见 JDK 安装目录
+    fn candidates(configured: &Path) -> Vec<PathBuf> {
+        let mut paths = vec![configured.to_path_buf()];
+
+        if let Some(home) = std::env::var_os("JAVA_HOME") {
+            paths.push(PathBuf::from(home).join("bin/java"));
+        }
+
+        if let Some(path_var) = std::env::var_os("PATH") {
+            paths.extend(std::env::split_paths(&path_var).map(|dir| dir.join("java")));
+        }
+
+        if let Ok(output) = Command::new("update-alternatives")
+            .args(["--list", "java"])
+            .output()
+        {
+            paths.extend(
+                String::from_utf8_lossy(&output.stdout)
+                    .lines()
+                    .map(|line| PathBuf::from(line.trim())),
+            );
+        }
+
+        for dir in std::iter::once(&JAVA_RUNTIME_DIR).chain(JAVA_SEARCH_DIRS) {
+            if let Ok(entries) = std::fs::read_dir(dir) {
+                let mut homes: Vec<PathBuf> = entries.flatten().map(|e| e.path()).collect();
+                homes.sort();
+                paths.extend(homes.into_iter().map(|home| home.join("bin/java")));
+            }
+        }
+
+        paths.retain(|p| p.is_file());
+        paths
+    }
+
+    /// 执行 `java -XshowSettings:properties -version` 读取厂商、版本与架构
+    pub fn probe(java_path: &Path) -> Option<JavaInstallation> {
+        let output = Command::new(java_path)
+            .args(["-XshowSettings:properties", "-version"])
+            .output()
+            .ok()?;
+
+        let text = String::from_utf8_lossy(&output.stderr);
+        let java = parse_properties(java_path, &text);
+        if java.is_none() {
+            debug!("无法识别 Java: {}", java_path.display());
+        }
+        java
+    }
+}
+
+/// 解析 `-XshowSettings:properties` 输出中的 java.version、java.vendor 与 os.arch
+fn parse_properties(java_path: &Path, output: &str) -> Option<JavaInstallation> {
+    let property = |key: &str| {
+        output.lines().find_map(|line| {
+            let (k, v) = line.trim().split_once(" = ")?;
+            (k == key).then(|| v.trim().to_string())
+        })
+    };
+
+    let version = property("java.version")?;
+
+    Some(JavaInstallation {
+        path: java_path.to_path_buf(),
+        vendor: property("java.vendor").unwrap_or_else(|| "unknown".to_string()),
+        major: parse_major(&version)?,
+        version,
+        arch: normalize_arch(&property("os.arch")?),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const JDK8_OUTPUT: &str = r#"Property settings:
+    awt.toolkit = sun.awt.X11.XToolkit
+    java.class.path =
+    java.library.path = /usr/java/packages/lib/amd64
+        /usr/lib64
+        /lib64
+    java.vendor = Temurin
+    java.version = 1.8.0_392
+    os.arch = amd64
+    os.name = Linux
+
+openjdk version "1.8.0_392"
+OpenJDK Runtime Environment (Temurin)(build 1.8.0_392-b08)
+"#;
+
+    const JDK17_ARM_OUTPUT: &str = r#"Property settings:
+    java.vendor = Eclipse Adoptium
+    java.version = 17.0.9
+    os.arch = aarch64
+
+openjdk version "17.0.9" 2023-10-17
+"#;
+
+    fn parse(output: &str) -> JavaInstallation {
+        parse_properties(Path::new("/opt/jdk/bin/java"), output).unwrap()
+    }
+
+    #[test]
+    fn test_parse_properties_jdk8() {
+        let java = parse(JDK8_OUTPUT);
+        assert_eq!(java.vendor, "Temurin");
+        assert_eq!(java.version, "1.8.0_392");
+        assert_eq!(java.major, 8);
+        assert_eq!(java.arch, "x86_64");
+    }
+
+    #[test]
+    fn test_parse_properties_jdk17_arm() {
+        let java = parse(JDK17_ARM_OUTPUT);
+        assert_eq!(java.vendor, "Eclipse Adoptium");
+        assert_eq!(java.major, 17);
+        assert_eq!(java.arch, "aarch64");
+    }
+
+    #[test]
+    fn test_parse_properties_invalid() {
+        assert!(parse_properties(Path::new("/bin/false"), "Error: not java").is_none());
+    }
+
+    #[test]
+    fn test_requirement_by_arch() {
+        assert_eq!(JavaRequirement::new("amd64", None).major, Some(8));
+        assert_eq!(JavaRequirement::new("aarch64", None).major, Some(17));
+        assert_eq!(JavaRequirement::new("riscv64", None).major, None);
+        assert_eq!(
+            JavaRequirement::new("amd64", None).to_string(),
+            "8 (x86_64)"
+        );
+    }
+
+    #[test]
+    fn test_requirement_follows_jar() {
+        // 以 Java 17 编译的 jar 在 x86_64 上也需要 JDK 17
+        assert_eq!(JavaRequirement::new("x86_64", Some(17)).major, Some(17));
+        // ARM 至少需要 JDK 17，即使 jar 以 Java 8 编译
+        assert_eq!(JavaRequirement::new("aarch64", Some(8)).major, Some(17));
+        assert_eq!(JavaRequirement::new("riscv64", Some(8)).major, None);
+    }
+
+    #[test]
+    fn test_requirement_for_missing_jar() {
+        let requirement = JavaRequirement::for_jar(Path::new("/nonexistent/FullNode.jar"));
+        assert_eq!(requirement, JavaRequirement::for_host());
+    }
+
+    #[test]
+    fn test_check_compatible_for_arch() {
+        let jdk8 = parse(JDK8_OUTPUT);
+        let jdk17 = parse(JDK17_ARM_OUTPUT);
+
+        assert!(JavaRequirement::new("x86_64", None).check(&jdk8).is_ok());
+        assert!(JavaRequirement::new("aarch64", None).check(&jdk17).is_ok());
+        // ARM 构建需要 JDK 17，x86_64 需要 JDK 8
+        assert!(JavaRequirement::new("aarch64", None).check(&jdk8).is_err());
+        let x86_jdk17 = JavaInstallation {
+            major: 17,
+            ..jdk8.clone()
+        };
+        assert!(
+            JavaRequirement::new("x86_64", None)
+                .check(&x86_jdk17)
+                .is_err()
+        );
+        // jar 以 Java 17 编译时 x86_64 上的 JDK 8 不再兼容
+        assert!(
+            JavaRequirement::new("x86_64", Some(17))
+                .check(&jdk8)
+                .is_err()
+        );
+        assert!(
+            JavaRequirement::new("x86_64", Some(17))
+                .check(&x86_jdk17)
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_check_path_missing() {
+        let requirement = JavaRequirement::new("x86_64", None);
+        assert!(
+            JavaDiscovery::check_path(Path::new("/nonexistent/bin/java"), &requirement).is_err()
+        );
+    }
+}
@@ -0,0 +1,434 @@
+use crate::constants::{ADOPTIUM_API, JAVA_RUNTIME_DIR};
+use crate::core::{Downloader, JavaDiscovery, JavaRequirement};
+use crate::error::{Result, TronCtlError};
+use crate::models::checksum::{Checksum, ChecksumAlgorithm};
+use crate::models::java_info::JavaInstallation;
+use crate::utils::{archive, fs, hash};
+use reqwest::Client;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// Adoptium API 返回的 JDK 安装包信息
+#[derive(Debug, Clone, Deserialize)]
+pub struct JdkPackage {
+    pub name: String,
+    pub link: String,
+    /// SHA-256
+    pub checksum: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct AdoptiumAsset {
+    binary: AdoptiumBinary,
+    release_name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct AdoptiumBinary {
+    package: JdkPackage,
+}
+
+/// 将 JDK 安装到 tronctl 管理的运行时目录
+pub struct JdkInstaller {
+    client: Client,
+    downloader: Downloader,
+    api_base: String,
+    runtime_dir: PathBuf,
+    requirement: JavaRequirement,
+}
+
+impl JdkInstaller {
+    pub fn new() -> Self {
+        Self::with_endpoints(ADOPTIUM_API, Path::new(JAVA_RUNTIME_DIR))
+    }
+
+    pub fn with_endpoints(api_base: &str, runtime_dir: &Path) -> Self {
+        Self {
+            client: Client::builder()
+                .timeout(Duration::from_secs(30))
+                .user_agent("tronctl/0.1.0")
+                .build()
+                .expect("Failed to build HTTP client"),
+            downloader: Downloader::new(),
+            api_base: api_base.trim_end_matches('/').to_string(),
+            runtime_dir: runtime_dir.to_path_buf(),
+            requirement: JavaRequirement::for_host(),
+        }
+    }
+
+    /// 按 FullNode.jar 的要求选择与校验 JDK 版本，默认只按本机架构判断
+    pub fn with_requirement(mut self, requirement: JavaRequirement) -> Self {
+        self.requirement = requirement;
+        self
+    }
+
+    /// 安装 JDK：`source` 为本地 .tar.gz 或 URL，未提供时从 Adoptium 下载所需版本
+    pub async fn install(
+        &self,
+        source: Option<&str>,
+        checksum: Option<&Checksum>,
+    ) -> Result<JavaInstallation> {
+        fs::ensure_dir_exists(&self.runtime_dir).await?;
+
+        let (archive_path, expected, downloaded) = match source {
+            None => {
+                let package = self.resolve_latest().await?;
+                let expected = Checksum::new(ChecksumAlgorithm::Sha256, &package.checksum)?;
+                let dest = self.runtime_dir.join(&package.name);
+                self.downloader
+                    .download_with_progress(&package.link, &dest, None)
+                    .await?;
+                (dest, expected, true)
+            }
+            Some(url) if url.starts_with("http://") || url.starts_with("https://") => {
+                let expected = match checksum {
+                    Some(checksum) => checksum.clone(),
+                    None => self.fetch_sidecar_checksum(url).await?,
+                };
+                let name = url.rsplit('/').next().unwrap_or("jdk.tar.gz");
+                let dest = self.runtime_dir.join(name);
+                self.downloader
+                    .download_with_progress(url, &dest, None)
+                    .await?;
+                (dest, expected, true)
+            }
+            Some(path) => {
+                let path = PathBuf::from(path);
+                let expected = checksum
+                    .cloned()
+                    .or_else(|| local_sidecar_checksum(&path))
+                    .ok_or_else(|| {
+                        TronCtlError::ConfigError(format!(
+                            "本地归档缺少校验值，请通过 --checksum 指定或在归档旁放置 .sha256.txt 校验文件: {:?}",
+                            path
+                        ))
+                    })?;
+                (path, expected, false)
+            }
+        };
+
+        let algorithm = expected.algorithm.name();
+        info!("校验 {}...", algorithm);
+        let verified = hash::verify_file(&archive_path, &expected);
+        if verified.is_err() && downloaded {
+            tokio::fs::remove_file(&archive_path).await.ok();
+        }
+        verified?;
+        info!("{} 校验通过", algorithm);
+
+        let java = self.unpack(&archive_path).await;
+
+        if downloaded {
+            tokio::fs::remove_file(&archive_path).await.ok();
+        }
+
+        java
+    }
+
+    /// 查询 Adoptium 上所需主版本的最新 JDK
+    pub async fn resolve_latest(&self) -> Result<JdkPackage> {
+        let arch = &self.requirement.arch;
+        let major = self.requirement.major.ok_or_else(|| {
+            TronCtlError::ConfigError(format!("java-tron 不支持的架构: {}", arch))
+        })?;
+
+        let url = format!(
+            "{}/assets/latest/{}/hotspot?architecture={}&image_type=jdk&os=linux&vendor=eclipse",
+            self.api_base,
+            major,
+            adoptium_arch(arch)
+        );
+
+        let resp = self.client.get(&url).send().await?;
+        if !resp.status().is_success() {
+            return Err(TronCtlError::DownloadFailed(format!(
+                "查询 JDK {} 失败: HTTP {}",
+                major,
+                resp.status()
+            )));
+        }
+
+        let assets: Vec<AdoptiumAsset> = resp.json().await?;
+        let asset = assets.into_iter().next().ok_or_else(|| {
+            TronCtlError::DownloadFailed(format!("未找到 {} 架构的 JDK {}", arch, major))
+        })?;
+
+        info!(
+            "使用 JDK: {} ({})",
+            asset.release_name, asset.binary.package.name
+        );
+        Ok(asset.binary.package)
+    }
+
+    /// 读取远程归档旁的 `.sha256.txt` 校验文件
+    async fn fetch_sidecar_checksum(&self, url: &str) -> Result<Checksum> {
+        for suffix in [".sha256.txt", ".sha256"] {
+            let resp = self.client.get(format!("{}{}", url, suffix)).send().await;
+            if let Ok(resp) = resp
+                && resp.status().is_success()
+                && let Some(checksum) =
+                    Checksum::from_sidecar(ChecksumAlgorithm::Sha256, &resp.text().await?)
+            {
+                return Ok(checksum);
+            }
+        }
+
+        Err(TronCtlError::DownloadFailed(
+            "未找到远程校验文件，请通过 --checksum 指定校验值".to_string(),
+        ))
+    }
+
+    /// 解压到临时目录，确认 JDK 可用后移动到运行时目录
+    async fn unpack(&self, archive_path: &Path) -> Result<JavaInstallation> {
+        let staging = self
+            .runtime_dir
+            .join(format!(".staging-{}", std::process::id()));
+        if staging.exists() {
+            tokio::fs::remove_dir_all(&staging).await?;
+        }
+        fs::ensure_dir_exists(&staging).await?;
+
+        let result = self.install_from_staging(archive_path, &staging).await;
+        tokio::fs::remove_dir_all(&staging).await.ok();
+        result
+    }
+
+    async fn install_from_staging(
+        &self,
+        archive_path: &Path,
+        staging: &Path,
+    ) -> Result<JavaInstallation> {
+        archive::extract_tgz(archive_path, staging).await?;
+
+        let home = find_java_home(staging).ok_or_else(|| {
+            TronCtlError::ConfigError("归档中未找到 bin/java，不是有效的 JDK".to_string())
+        })?;
+        let name = home
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| "jdk".to_string());
+
+        let target = self.runtime_dir.join(&name);
+        if target.exists() {
+            warn!("覆盖已安装的 JDK: {:?}", target);
+            tokio::fs::remove_dir_all(&target).await?;
+        }
+        tokio::fs::rename(&home, &target).await?;
+
+        let java_path = target.join("bin/java");
+        match JavaDiscovery::check_path(&java_path, &self.requirement) {
+            Ok(java) => {
+                info!("JDK 已安装: {}", java.summary());
+                Ok(java)
+            }
+            Err(e) => {
+                tokio::fs::remove_dir_all(&target).await.ok();
+                Err(e)
+            }
+        }
+    }
+}
+
+impl Default for JdkInstaller {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Adoptium 使用的架构名称
+fn adoptium_arch(arch: &str) -> &str {
+    match arch {
+        "x86_64" => "x64",
+        other => other,
+    }
+}
+
+/// 本地归档旁的 `.sha256.txt` / `.sha256` 校验文件
+fn local_sidecar_checksum(archive: &Path) -> Option<Checksum> {
+    [".sha256.txt", ".sha256"].iter().find_map(|suffix| {
+        let mut sidecar = archive.as_os_str().to_owned();
+        sidecar.push(suffix);
+        Checksum::from_sidecar(
+            ChecksumAlgorithm::Sha256,
+            &std::fs::read_to_string(sidecar).ok()?,
+        )
+    })
+}
+
+/// 解压目录本身或其唯一的子目录中包含 bin/java 即为 JAVA_HOME
+fn find_java_home(dir: &Path) -> Option<PathBuf> {
+    if dir.join("bin/java").is_file() {
+        return Some(dir.to_path_buf());
+    }
+
+    std::fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .find(|path| path.join("bin/java").is_file())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const ASSETS_JSON: &str = r#"[{
+        "binary": {
+            "architecture": "x64",
+            "image_type": "jdk",
+            "os": "linux",
+            "package": {
+                "checksum": "2ef9f1d2b3a4",
+                "link": "https://github.com/adoptium/temurin8-binaries/releases/download/jdk8u392-b08/OpenJDK8U-jdk_x64_linux_hotspot_8u392b08.tar.gz",
+                "name": "OpenJDK8U-jdk_x64_linux_hotspot_8u392b08.tar.gz",
+                "size": 103022622
+            }
+        },
+        "release_name": "jdk8u392-b08",
+        "vendor": "eclipse"
+    }]"#;
+
+    #[tokio::test]
+    async fn test_resolve_latest() {
+        let mut server = mockito::Server::new_async().await;
+        let requirement = JavaRequirement::for_host();
+        let Some(major) = requirement.major else {
+            return;
+        };
+
+        let mock = server
+            .mock("GET", format!("/assets/latest/{}/hotspot", major).as_str())
+            .match_query(mockito::Matcher::UrlEncoded(
+                "architecture".into(),
+                adoptium_arch(&requirement.arch).into(),
+            ))
+            .with_status(200)
+            .with_body(ASSETS_JSON)
+            .create_async()
+            .await;
+
+        let temp_dir = TempDir::new().unwrap();
+        let installer = JdkInstaller::with_endpoints(&server.url(), temp_dir.path());
+        let package = installer.resolve_latest().await.unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(package.checksum, "2ef9f1d2b3a4");
+        assert!(package.name.ends_with(".tar.gz"));
+    }
+
+    #[tokio::test]
+    async fn test_resolve_latest_follows_requirement() {
+        let mut server = mockito::Server::new_async().await;
+        // 以 Java 17 编译的 FullNode.jar 在 x86_64 上也需要 JDK 17
+        let mock = server
+            .mock("GET", "/assets/latest/17/hotspot")
+            .match_query(mockito::Matcher::UrlEncoded(
+                "architecture".into(),
+                "x64".into(),
+            ))
+            .with_status(200)
+            .with_body(ASSETS_JSON)
+            .create_async()
+            .await;
+
+        let temp_dir = TempDir::new().unwrap();
+        let installer = JdkInstaller::with_endpoints(&server.url(), temp_dir.path())
+            .with_requirement(JavaRequirement {
+                major: Some(17),
+                arch: "x86_64".to_string(),
+            });
+        installer.resolve_latest().await.unwrap();
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_fetch_sidecar_checksum() {
+        let mut server = mockito::Server::new_async().await;
+        let _missing = server
+            .mock("GET", "/jdk.tar.gz.sha256.txt")
+            .with_status(404)
+            .create_async()
+            .await;
+        let _sidecar = server
+            .mock("GET", "/jdk.tar.gz.sha256")
+            .with_status(200)
+            .with_body(format!("{}  jdk.tar.gz\n", "AB".repeat(32)))
+            .create_async()
+            .await;
+
+        let temp_dir = TempDir::new().unwrap();
+        let installer = JdkInstaller::with_endpoints(&server.url(), temp_dir.path());
+        let sum = installer
+            .fetch_sidecar_checksum(&format!("{}/jdk.tar.gz", server.url()))
+            .await
+            .unwrap();
+        assert_eq!(sum.value, "ab".repeat(32));
+    }
+
+    #[tokio::test]
+    async fn test_install_rejects_checksum_mismatch() {
+        let temp_dir = TempDir::new().unwrap();
+        let archive_path = temp_dir.path().join("jdk.tar.gz");
+        std::fs::write(&archive_path, b"not a jdk").unwrap();
+
+        let installer =
+            JdkInstaller::with_endpoints("http://127.0.0.1:1", &temp_dir.path().join("runtime"));
+        let result = installer
+            .install(
+                Some(archive_path.to_str().unwrap()),
+                Some(&Checksum::parse(&"0".repeat(64)).unwrap()),
+            )
+            .await;
+
+        assert!(matches!(result, Err(TronCtlError::ChecksumMismatch { .. })));
+        // 本地归档不应被删除
+        assert!(archive_path.exists());
+    }
+
+    #[tokio::test]
+    async fn test_install_requires_checksum_for_local_archive() {
+        let temp_dir = TempDir::new().unwrap();
+        let archive_path = temp_dir.path().join("jdk.tar.gz");
+        std::fs::write(&archive_path, b"not a jdk").unwrap();
+
+        let installer =
+            JdkInstaller::with_endpoints("http://127.0.0.1:1", &temp_dir.path().join("runtime"));
+        let result = installer
+            .install(Some(archive_path.to_str().unwrap()), None)
+            .await;
+
+        assert!(matches!(result, Err(TronCtlError::ConfigError(_))));
+    }
+
+    #[test]
+    fn test_find_java_home() {
+        let temp_dir = TempDir::new().unwrap();
+        let home = temp_dir.path().join("jdk8u392-b08");
+        std::fs::create_dir_all(home.join("bin")).unwrap();
+        std::fs::write(home.join("bin/java"), b"").unwrap();
+
+        assert_eq!(find_java_home(temp_dir.path()), Some(home));
+        assert_eq!(find_java_home(&temp_dir.path().join("missing")), None);
+    }
+
+    #[test]
+    fn test_local_sidecar_checksum() {
+        let temp_dir = TempDir::new().unwrap();
+        let archive_path = temp_dir.path().join("jdk.tar.gz");
+        assert_eq!(local_sidecar_checksum(&archive_path), None);
+
+        std::fs::write(
+            temp_dir.path().join("jdk.tar.gz.sha256.txt"),
+            format!("{}  jdk.tar.gz", "c".repeat(64)),
+        )
+        .unwrap();
+        assert_eq!(
+            local_sidecar_checksum(&archive_path),
+            Checksum::new(ChecksumAlgorithm::Sha256, &"c".repeat(64)).ok()
+        );
+    }
+}
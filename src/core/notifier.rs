@@ -0,0 +1,85 @@
+use crate::error::{Result, TronCtlError};
+use reqwest::Client;
+use serde_json::json;
+use std::time::Duration;
+use tracing::info;
+
+/// 向配置的 webhook 发送 JSON 通知
+pub struct Notifier {
+    client: Client,
+    url: String,
+}
+
+impl Notifier {
+    pub fn new(url: &str) -> Self {
+        Self {
+            client: Client::builder()
+                .timeout(Duration::from_secs(10))
+                .user_agent("tronctl/0.1.0")
+                .build()
+                .expect("Failed to build HTTP client"),
+            url: url.to_string(),
+        }
+    }
+
+    /// 发送 `{"event": ..., "text": ...}`，`text` 字段兼容 Slack 等常见 webhook
+    pub async fn send(&self, event: &str, text: &str) -> Result<()> {
+        let resp = self
+            .client
+            .post(&self.url)
+            .json(&json!({ "event": event, "text": text }))
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            return Err(TronCtlError::NotificationFailed(format!(
+                "{} HTTP {}",
+                self.url,
+                resp.status()
+            )));
+        }
+
+        info!("已发送通知: {}", event);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_send() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/hook")
+            .match_body(mockito::Matcher::PartialJson(json!({
+                "event": "mandatory_release",
+                "text": "hello",
+            })))
+            .with_status(200)
+            .create_async()
+            .await;
+
+        Notifier::new(&format!("{}/hook", server.url()))
+            .send("mandatory_release", "hello")
+            .await
+            .unwrap();
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_send_failure() {
+        let mut server = mockito::Server::new_async().await;
+        let _mock = server
+            .mock("POST", "/hook")
+            .with_status(500)
+            .create_async()
+            .await;
+
+        let result = Notifier::new(&format!("{}/hook", server.url()))
+            .send("mandatory_release", "hello")
+            .await;
+        assert!(matches!(result, Err(TronCtlError::NotificationFailed(_))));
+    }
+}
@@ -0,0 +1,177 @@
+use crate::error::{Result, TronCtlError};
+use crate::models::peer_info::{ListNodesResponse, NodeInfo, PeerState};
+use reqwest::Client;
+use std::path::Path;
+use std::time::Duration;
+use tracing::debug;
+
+pub struct PeerMonitor {
+    client: Client,
+    endpoint: String,
+}
+
+impl PeerMonitor {
+    pub fn new(endpoint: &str) -> Self {
+        Self {
+            client: Client::builder()
+                .timeout(Duration::from_secs(10))
+                .build()
+                .expect("Failed to build HTTP client"),
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// 查询节点信息（连接数、对等节点列表、节点配置）
+    pub async fn node_info(&self) -> Result<NodeInfo> {
+        self.get_json("/wallet/getnodeinfo").await
+    }
+
+    /// 查询节点发现表中的已知节点
+    pub async fn list_nodes(&self) -> Result<ListNodesResponse> {
+        self.get_json("/wallet/listnodes").await
+    }
+
+    async fn get_json<T: serde::de::DeserializeOwned>(&self, path: &str) -> Result<T> {
+        let url = format!("{}{}", self.endpoint, path);
+        debug!("查询: {}", url);
+
+        let resp = self.client.get(&url).send().await?;
+
+        if !resp.status().is_success() {
+            return Err(TronCtlError::RpcCallFailed(format!(
+                "{} HTTP {}",
+                path,
+                resp.status()
+            )));
+        }
+
+        Ok(resp.json().await?)
+    }
+}
+
+/// 记录进程 `pid` 本次观测到的连接数，返回节点已持续无连接的时长（有连接时返回 None）
+pub fn record_peer_count(
+    state_file: &Path,
+    pid: i32,
+    peer_count: u32,
+    now: i64,
+) -> Result<Option<Duration>> {
+    let mut state: PeerState = std::fs::read_to_string(state_file)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .filter(|state: &PeerState| state.pid == Some(pid))
+        .unwrap_or(PeerState {
+            pid: Some(pid),
+            zero_peers_since: None,
+        });
+
+    let zero_for = if peer_count > 0 {
+        state.zero_peers_since = None;
+        None
+    } else {
+        let since = *state.zero_peers_since.get_or_insert(now);
+        Some(Duration::from_secs(now.saturating_sub(since).max(0) as u64))
+    };
+
+    if let Some(parent) = state_file.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(state_file, serde_json::to_string(&state)?)?;
+
+    Ok(zero_for)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_node_info_success() {
+        let mut server = mockito::Server::new_async().await;
+        let _mock = server
+            .mock("GET", "/wallet/getnodeinfo")
+            .with_status(200)
+            .with_body(r#"{"currentConnectCount": 5, "block": "Num:42,ID:abc"}"#)
+            .create_async()
+            .await;
+
+        let monitor = PeerMonitor::new(&server.url());
+        let info = monitor.node_info().await.unwrap();
+
+        assert_eq!(info.peer_count(), 5);
+        assert_eq!(info.head_block(), Some(42));
+    }
+
+    #[tokio::test]
+    async fn test_list_nodes_success() {
+        let mut server = mockito::Server::new_async().await;
+        let _mock = server
+            .mock("GET", "/wallet/listnodes")
+            .with_status(200)
+            .with_body(r#"{"nodes": [{"address": {"host": "31", "port": 18888}}]}"#)
+            .create_async()
+            .await;
+
+        let monitor = PeerMonitor::new(&server.url());
+        let nodes = monitor.list_nodes().await.unwrap();
+
+        assert_eq!(nodes.nodes.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_node_info_http_error() {
+        let mut server = mockito::Server::new_async().await;
+        let _mock = server
+            .mock("GET", "/wallet/getnodeinfo")
+            .with_status(500)
+            .create_async()
+            .await;
+
+        let monitor = PeerMonitor::new(&server.url());
+        let result = monitor.node_info().await;
+
+        assert!(matches!(result, Err(TronCtlError::RpcCallFailed(_))));
+    }
+
+    #[test]
+    fn test_record_peer_count_tracks_zero_duration() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("peers.state");
+
+        assert_eq!(
+            record_peer_count(&state_file, 42, 0, 1000).unwrap(),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(
+            record_peer_count(&state_file, 42, 0, 1300).unwrap(),
+            Some(Duration::from_secs(300))
+        );
+    }
+
+    #[test]
+    fn test_record_peer_count_resets_on_restart() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("peers.state");
+
+        record_peer_count(&state_file, 42, 0, 1000).unwrap();
+        // 节点重启后进程号变化，旧的计时不再适用
+        assert_eq!(
+            record_peer_count(&state_file, 43, 0, 5000).unwrap(),
+            Some(Duration::ZERO)
+        );
+    }
+
+    #[test]
+    fn test_record_peer_count_resets_on_peers() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("peers.state");
+
+        record_peer_count(&state_file, 42, 0, 1000).unwrap();
+        assert_eq!(record_peer_count(&state_file, 42, 3, 1100).unwrap(), None);
+        assert_eq!(
+            record_peer_count(&state_file, 42, 0, 2000).unwrap(),
+            Some(Duration::ZERO)
+        );
+    }
+}
@@ -0,0 +1,513 @@
+use crate::constants::{
+    DEFAULT_GRPC_PORT, DEFAULT_HTTP_PORT, DEFAULT_JSONRPC_PORT, DEFAULT_P2P_PORT,
+};
+use crate::error::{Result, TronCtlError};
+use crate::models::check_result::{CheckLevel, CheckResult};
+use crate::utils::node_conf;
+use std::collections::HashSet;
+use std::net::{TcpListener, UdpSocket};
+use std::path::Path;
+use std::process::Command;
+use tracing::debug;
+
+/// 节点配置中声明的监听端口
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodePorts {
+    pub p2p: u16,
+    pub http: u16,
+    pub grpc: u16,
+    /// 仅在 `node.jsonrpc.httpFullNodeEnable = true` 时启用
+    pub jsonrpc: Option<u16>,
+}
+
+impl Default for NodePorts {
+    fn default() -> Self {
+        Self {
+            p2p: DEFAULT_P2P_PORT,
+            http: DEFAULT_HTTP_PORT,
+            grpc: DEFAULT_GRPC_PORT,
+            jsonrpc: None,
+        }
+    }
+}
+
+impl NodePorts {
+    /// 从节点配置文件读取端口，文件不存在时使用 java-tron 默认值
+    pub fn load(node_config: &Path) -> Result<Self> {
+        if !node_config.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = std::fs::read_to_string(node_config)?;
+        Ok(Self::from_config_str(&content))
+    }
+
+    pub fn from_config_str(content: &str) -> Self {
+        let port = |key: &str, default: u16| {
+            node_conf::get_value(content, key)
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(default)
+        };
+
+        let jsonrpc_enabled = node_conf::get_value(content, "node.jsonrpc.httpFullNodeEnable")
+            .is_some_and(|v| v == "true");
+
+        Self {
+            p2p: port("node.listen.port", DEFAULT_P2P_PORT),
+            http: port("node.http.fullNodePort", DEFAULT_HTTP_PORT),
+            grpc: port("node.rpc.port", DEFAULT_GRPC_PORT),
+            jsonrpc: jsonrpc_enabled
+                .then(|| port("node.jsonrpc.httpFullNodePort", DEFAULT_JSONRPC_PORT)),
+        }
+    }
+
+    /// 需要监听的 TCP 端口（名称, 端口）
+    pub fn tcp_ports(&self) -> Vec<(&'static str, u16)> {
+        let mut ports = vec![("P2P", self.p2p), ("HTTP", self.http), ("gRPC", self.grpc)];
+        if let Some(port) = self.jsonrpc {
+            ports.push(("JSON-RPC", port));
+        }
+        ports
+    }
+}
+
+pub struct PortChecker;
+
+impl PortChecker {
+    /// 启动前检查：所有端口应当空闲
+    pub fn check_free(ports: &NodePorts) -> Vec<CheckResult> {
+        let mut results: Vec<CheckResult> = ports
+            .tcp_ports()
+            .into_iter()
+            .map(|(name, port)| {
+                let check_name = format!("{} 端口 {}/tcp", name, port);
+                match TcpListener::bind(("0.0.0.0", port)) {
+                    Ok(_) => CheckResult::pass(&check_name, "空闲"),
+                    Err(e) => Self::bind_error(&check_name, port, e),
+                }
+            })
+            .collect();
+
+        let check_name = format!("P2P 端口 {}/udp", ports.p2p);
+        results.push(match UdpSocket::bind(("0.0.0.0", ports.p2p)) {
+            Ok(_) => CheckResult::pass(&check_name, "空闲"),
+            Err(e) => Self::bind_error(&check_name, ports.p2p, e),
+        });
+
+        results
+    }
+
+    /// 启动后检查：所有端口应当已被监听
+    pub fn check_bound(ports: &NodePorts) -> Vec<CheckResult> {
+        let tcp = Self::read_proc_ports(&["/proc/net/tcp", "/proc/net/tcp6"], "0A");
+        let udp = Self::read_proc_ports(&["/proc/net/udp", "/proc/net/udp6"], "07");
+
+        let mut results: Vec<CheckResult> = ports
+            .tcp_ports()
+            .into_iter()
+            .map(|(name, port)| {
+                Self::bound_result(&format!("{} 端口 {}/tcp", name, port), &tcp, port)
+            })
+            .collect();
+
+        results.push(Self::bound_result(
+            &format!("P2P 端口 {}/udp", ports.p2p),
+            &udp,
+            ports.p2p,
+        ));
+
+        results
+    }
+
+    /// 启动前检查端口，任何端口被占用都返回错误
+    pub fn ensure_free(ports: &NodePorts) -> Result<()> {
+        let occupied: Vec<String> = Self::check_free(ports)
+            .into_iter()
+            .filter(|r| r.level == CheckLevel::Fail)
+            .map(|r| r.name)
+            .collect();
+
+        if occupied.is_empty() {
+            Ok(())
+        } else {
+            Err(TronCtlError::PortInUse(occupied.join(", ")))
+        }
+    }
+
+    /// 检查 iptables / nftables / ufw 是否可能拦截入站 P2P 流量
+    pub fn check_firewall(p2p_port: u16) -> Vec<CheckResult> {
+        let name = "防火墙 (入站 P2P)";
+        let mut results = Vec::new();
+
+        if let Some(rules) = Self::command_output("iptables", &["-S", "INPUT"]) {
+            results.push(match Self::analyze_iptables(&rules, p2p_port) {
+                Some(rule) => CheckResult::warn(
+                    name,
+                    format!("iptables 可能拦截 {} 端口: {}", p2p_port, rule),
+                    format!(
+                        "iptables -I INPUT -p tcp --dport {0} -j ACCEPT && iptables -I INPUT -p udp --dport {0} -j ACCEPT",
+                        p2p_port
+                    ),
+                ),
+                None => CheckResult::pass(name, "iptables 未拦截 P2P 端口"),
+            });
+        }
+
+        if let Some(ruleset) = Self::command_output("nft", &["list", "ruleset"]) {
+            results.push(match Self::analyze_nftables(&ruleset, p2p_port) {
+                Some(rule) => CheckResult::warn(
+                    name,
+                    format!("nftables 可能拦截 {} 端口: {}", p2p_port, rule),
+                    format!(
+                        "在 input 链中添加: tcp dport {0} accept; udp dport {0} accept",
+                        p2p_port
+                    ),
+                ),
+                None => CheckResult::pass(name, "nftables 未拦截 P2P 端口"),
+            });
+        }
+
+        if let Some(status) = Self::command_output("ufw", &["status", "verbose"]) {
+            results.push(match Self::analyze_ufw(&status, p2p_port) {
+                Some(reason) => CheckResult::warn(
+                    name,
+                    reason,
+                    format!("ufw allow {}/tcp && ufw allow {}/udp", p2p_port, p2p_port),
+                ),
+                None => CheckResult::pass(name, "ufw 未拦截 P2P 端口"),
+            });
+        }
+
+        if results.is_empty() {
+            results.push(CheckResult::pass(name, "未检测到本机防火墙规则"));
+        }
+
+        results
+    }
+
+    /// 按顺序模拟 INPUT 链，返回会拦截该端口的规则
+    pub fn analyze_iptables(rules: &str, port: u16) -> Option<String> {
+        let mut policy_drop = None;
+
+        for line in rules.lines().map(str::trim) {
+            if let Some(policy) = line.strip_prefix("-P INPUT ") {
+                if policy == "DROP" || policy == "REJECT" {
+                    policy_drop = Some(line.to_string());
+                }
+                continue;
+            }
+
+            let Some(rule) = line.strip_prefix("-A INPUT ") else {
+                continue;
+            };
+
+            let target = rule
+                .split_whitespace()
+                .skip_while(|t| *t != "-j")
+                .nth(1)
+                .unwrap_or("");
+            let blocks = target == "DROP" || target == "REJECT";
+
+            if rule.contains("--dport") {
+                if Self::mentions_port(rule, port) {
+                    if target == "ACCEPT" {
+                        return None;
+                    }
+                    if blocks {
+                        return Some(line.to_string());
+                    }
+                }
+                continue;
+            }
+
+            // 不带任何匹配条件的拦截规则会拦截所有剩余流量
+            let unconditional = !["-s ", "-i ", "-m ", "-p ", "--state", "--ctstate"]
+                .iter()
+                .any(|m| rule.contains(m));
+            if blocks && unconditional {
+                return Some(line.to_string());
+            }
+        }
+
+        policy_drop
+    }
+
+    /// 检查挂在 input hook 上的链：默认丢弃且没有放行该端口的规则时视为拦截
+    pub fn analyze_nftables(ruleset: &str, port: u16) -> Option<String> {
+        let mut in_input_chain = false;
+        let mut policy_drop = None;
+        let mut accepted = false;
+
+        for line in ruleset.lines().map(str::trim) {
+            if line.starts_with("chain ") {
+                in_input_chain = false;
+                continue;
+            }
+
+            if line.contains("hook input") {
+                in_input_chain = true;
+                if line.contains("policy drop") {
+                    policy_drop = Some(line.to_string());
+                }
+                continue;
+            }
+
+            if !in_input_chain || !line.contains("dport") || !Self::mentions_port(line, port) {
+                continue;
+            }
+
+            if line.ends_with("accept") {
+                accepted = true;
+            } else if line.ends_with("drop") || line.contains("reject") {
+                return Some(line.to_string());
+            }
+        }
+
+        if accepted { None } else { policy_drop }
+    }
+
+    /// ufw 启用且默认拒绝入站时，需要显式放行 P2P 端口
+    pub fn analyze_ufw(status: &str, port: u16) -> Option<String> {
+        if !status.contains("Status: active") {
+            return None;
+        }
+
+        let default_deny = status.lines().any(|l| {
+            l.starts_with("Default:")
+                && (l.contains("deny (incoming)") || l.contains("reject (incoming)"))
+        });
+        if !default_deny {
+            return None;
+        }
+
+        let allowed = status.lines().any(|l| {
+            let mut parts = l.split_whitespace();
+            let target = parts.next().unwrap_or("");
+            let action = parts.next().unwrap_or("");
+            action.starts_with("ALLOW")
+                && target
+                    .split('/')
+                    .next()
+                    .is_some_and(|p| Self::port_matches(p, port))
+        });
+
+        if allowed {
+            None
+        } else {
+            Some(format!("ufw 默认拒绝入站且未放行 {} 端口", port))
+        }
+    }
+
+    /// 规则中是否包含该端口（支持 a:b / a-b 范围与逗号列表）
+    fn mentions_port(rule: &str, port: u16) -> bool {
+        rule.split(|c: char| c.is_whitespace() || c == ',' || c == '{' || c == '}')
+            .any(|token| Self::port_matches(token, port))
+    }
+
+    fn port_matches(token: &str, port: u16) -> bool {
+        if let Some((start, end)) = token.split_once([':', '-'])
+            && let (Ok(start), Ok(end)) = (start.parse::<u16>(), end.parse::<u16>())
+        {
+            return (start..=end).contains(&port);
+        }
+        token.parse::<u16>() == Ok(port)
+    }
+
+    fn bind_error(check_name: &str, port: u16, e: std::io::Error) -> CheckResult {
+        if e.kind() == std::io::ErrorKind::AddrInUse {
+            CheckResult::fail(
+                check_name,
+                "已被占用",
+                format!(
+                    "使用 ss -lntup 'sport = :{}' 查找并停止占用进程，或修改节点配置中的端口",
+                    port
+                ),
+            )
+        } else {
+            CheckResult::warn(
+                check_name,
+                format!("无法检测: {}", e),
+                "以 root 身份运行检查",
+            )
+        }
+    }
+
+    fn bound_result(check_name: &str, bound: &HashSet<u16>, port: u16) -> CheckResult {
+        if bound.contains(&port) {
+            CheckResult::pass(check_name, "已监听")
+        } else {
+            CheckResult::fail(
+                check_name,
+                "未监听",
+                "节点可能仍在启动中，使用 'tronctl logs' 查看日志",
+            )
+        }
+    }
+
+    fn read_proc_ports(files: &[&str], state: &str) -> HashSet<u16> {
+        files
+            .iter()
+            .filter_map(|f| std::fs::read_to_string(f).ok())
+            .flat_map(|content| Self::parse_proc_net(&content, state))
+            .collect()
+    }
+
+    /// 解析 /proc/net/{tcp,udp}[6]，返回处于指定状态的本地端口
+    fn parse_proc_net(content: &str, state: &str) -> HashSet<u16> {
+        content
+            .lines()
+            .skip(1)
+            .filter_map(|line| {
+                let fields: Vec<&str> = line.split_whitespace().collect();
+                let local = fields.get(1)?;
+                if fields.get(3)? != &state {
+                    return None;
+                }
+                let (_, port_hex) = local.rsplit_once(':')?;
+                u16::from_str_radix(port_hex, 16).ok()
+            })
+            .collect()
+    }
+
+    fn command_output(program: &str, args: &[&str]) -> Option<String> {
+        let output = Command::new(program).args(args).output().ok()?;
+        if !output.status.success() {
+            debug!("{} {:?} 执行失败", program, args);
+            return None;
+        }
+        Some(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_node_ports_from_config() {
+        let conf = r#"
+node {
+  listen.port = 19999
+  http {
+    fullNodePort = 9090
+  }
+  rpc {
+    port = 60051
+  }
+  jsonrpc {
+    httpFullNodeEnable = true
+    httpFullNodePort = 8546
+  }
+}
+"#;
+        let ports = NodePorts::from_config_str(conf);
+        assert_eq!(
+            ports,
+            NodePorts {
+                p2p: 19999,
+                http: 9090,
+                grpc: 60051,
+                jsonrpc: Some(8546),
+            }
+        );
+        assert_eq!(ports.tcp_ports().len(), 4);
+    }
+
+    #[test]
+    fn test_node_ports_defaults() {
+        let ports = NodePorts::from_config_str("node {\n}\n");
+        assert_eq!(ports, NodePorts::default());
+        assert_eq!(ports.tcp_ports().len(), 3);
+    }
+
+    #[test]
+    fn test_node_ports_load_missing_file() {
+        let ports = NodePorts::load(Path::new("/nonexistent/tron.conf")).unwrap();
+        assert_eq!(ports.p2p, 18888);
+    }
+
+    #[test]
+    fn test_check_free_detects_occupied_port() {
+        let listener = TcpListener::bind(("0.0.0.0", 0)).unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let ports = NodePorts {
+            http: port,
+            ..Default::default()
+        };
+        let results = PortChecker::check_free(&ports);
+        let http = results.iter().find(|r| r.name.starts_with("HTTP")).unwrap();
+
+        assert_eq!(http.level, CheckLevel::Fail);
+        assert!(PortChecker::ensure_free(&ports).is_err());
+    }
+
+    #[test]
+    fn test_parse_proc_net() {
+        let content = "  sl  local_address rem_address   st tx_queue rx_queue\n   \
+            0: 00000000:1F9A 00000000:0000 0A 00000000:00000000\n   \
+            1: 0100007F:49D2 0100007F:1F9A 01 00000000:00000000\n";
+
+        let ports = PortChecker::parse_proc_net(content, "0A");
+        assert!(ports.contains(&8090));
+        assert!(!ports.contains(&18898));
+    }
+
+    #[test]
+    fn test_analyze_iptables_policy_drop() {
+        let rules =
+            "-P INPUT DROP\n-A INPUT -i lo -j ACCEPT\n-A INPUT -p tcp --dport 22 -j ACCEPT\n";
+        assert_eq!(
+            PortChecker::analyze_iptables(rules, 18888).as_deref(),
+            Some("-P INPUT DROP")
+        );
+    }
+
+    #[test]
+    fn test_analyze_iptables_port_accepted() {
+        let rules = "-P INPUT DROP\n-A INPUT -p tcp -m tcp --dport 18888 -j ACCEPT\n";
+        assert!(PortChecker::analyze_iptables(rules, 18888).is_none());
+
+        let range =
+            "-P INPUT DROP\n-A INPUT -p tcp -m multiport --dports 80,18000:19000 -j ACCEPT\n";
+        assert!(PortChecker::analyze_iptables(range, 18888).is_none());
+    }
+
+    #[test]
+    fn test_analyze_iptables_explicit_reject() {
+        let rules = "-P INPUT ACCEPT\n-A INPUT -p udp --dport 18888 -j REJECT\n";
+        assert!(PortChecker::analyze_iptables(rules, 18888).is_some());
+
+        let catch_all = "-P INPUT ACCEPT\n-A INPUT -j DROP\n";
+        assert!(PortChecker::analyze_iptables(catch_all, 18888).is_some());
+    }
+
+    #[test]
+    fn test_analyze_iptables_open() {
+        let rules = "-P INPUT ACCEPT\n";
+        assert!(PortChecker::analyze_iptables(rules, 18888).is_none());
+    }
+
+    #[test]
+    fn test_analyze_nftables() {
+        let blocked = "table inet filter {\n\tchain input {\n\t\ttype filter hook input priority filter; policy drop;\n\t\ttcp dport 22 accept\n\t}\n}\n";
+        assert!(PortChecker::analyze_nftables(blocked, 18888).is_some());
+
+        let allowed = "table inet filter {\n\tchain input {\n\t\ttype filter hook input priority filter; policy drop;\n\t\ttcp dport { 22, 18888 } accept\n\t}\n}\n";
+        assert!(PortChecker::analyze_nftables(allowed, 18888).is_none());
+
+        let open = "table inet filter {\n\tchain input {\n\t\ttype filter hook input priority filter; policy accept;\n\t}\n}\n";
+        assert!(PortChecker::analyze_nftables(open, 18888).is_none());
+    }
+
+    #[test]
+    fn test_analyze_ufw() {
+        let blocked = "Status: active\nDefault: deny (incoming), allow (outgoing)\n\nTo Action From\n22/tcp ALLOW IN Anywhere\n";
+        assert!(PortChecker::analyze_ufw(blocked, 18888).is_some());
+
+        let allowed = "Status: active\nDefault: deny (incoming), allow (outgoing)\n\nTo Action From\n18888 ALLOW IN Anywhere\n";
+        assert!(PortChecker::analyze_ufw(allowed, 18888).is_none());
+
+        assert!(PortChecker::analyze_ufw("Status: inactive\n", 18888).is_none());
+    }
+}
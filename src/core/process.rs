@@ -1,4 +1,5 @@
 use crate::constants::PID_FILE;
+use crate::core::{NodePorts, PortChecker};
 use crate::error::{Result, TronCtlError};
 use crate::models::TronCtlConfig;
 use fs2::FileExt;
@@ -49,6 +50,9 @@ impl ProcessManager {
             return Err(TronCtlError::NodeAlreadyRunning(existing_pid));
         }
 
+        // 端口被占用时 java-tron 会启动失败，提前报告
+        PortChecker::ensure_free(&NodePorts::load(&config.node_config)?)?;
+
         info!("启动 Tron FullNode...");
 
         let log_file = fs::OpenOptions::new()
@@ -0,0 +1,664 @@
+use crate::constants::{
+    GITHUB_API_BASE, GITHUB_API_RELEASES, GITHUB_DOWNLOAD_BASE, GITHUB_REPO,
+    RELEASE_CHECK_INTERVAL_SECS, RELEASES_MAX_PAGES, RELEASES_PER_PAGE,
+};
+use crate::error::{Result, TronCtlError};
+use crate::models::checksum::{Checksum, ChecksumAlgorithm};
+use crate::models::node_config::GithubConfig;
+use crate::models::release::{Release, ReleaseState, compare_versions};
+use crate::utils::hash;
+use reqwest::header::{ACCEPT, HeaderMap, LINK, RETRY_AFTER};
+use reqwest::{Client, StatusCode};
+use std::cmp::Ordering;
+use std::path::Path;
+use std::time::Duration;
+use tracing::debug;
+
+/// 查询 java-tron 在 GitHub（或镜像）上发布的版本
+pub struct ReleaseChecker {
+    client: Client,
+    api_url: String,
+    download_base: String,
+    token: Option<String>,
+    prerelease: bool,
+}
+
+impl ReleaseChecker {
+    pub fn new() -> Self {
+        Self::with_endpoints(GITHUB_API_RELEASES, GITHUB_DOWNLOAD_BASE)
+    }
+
+    /// 按 `[github]` 配置创建，并读取 GITHUB_TOKEN 环境变量
+    pub fn from_config(github: &GithubConfig) -> Self {
+        let mut checker = Self::with_endpoints(&github.releases_api(), &github.download_base)
+            .with_prerelease(github.prerelease);
+        checker.token = std::env::var("GITHUB_TOKEN")
+            .ok()
+            .filter(|token| !token.is_empty());
+        checker
+    }
+
+    /// 指定 Releases API 与附件下载地址
+    pub fn with_endpoints(api_url: &str, download_base: &str) -> Self {
+        Self {
+            client: Client::builder()
+                .timeout(Duration::from_secs(30))
+                .user_agent("tronctl/0.1.0")
+                .build()
+                .expect("Failed to build HTTP client"),
+            api_url: api_url.to_string(),
+            download_base: download_base.trim_end_matches('/').to_string(),
+            token: None,
+            prerelease: false,
+        }
+    }
+
+    /// 是否将预发布版本视为最新版本
+    pub fn with_prerelease(mut self, prerelease: bool) -> Self {
+        self.prerelease = prerelease;
+        self
+    }
+
+    pub fn prerelease(&self) -> bool {
+        self.prerelease
+    }
+
+    #[cfg(test)]
+    fn with_token(mut self, token: &str) -> Self {
+        self.token = Some(token.to_string());
+        self
+    }
+
+    /// Release 附件的下载地址
+    pub fn asset_url(&self, tag: &str, asset: &str) -> String {
+        format!(
+            "{}/{}/releases/download/{}/{}",
+            self.download_base, GITHUB_REPO, tag, asset
+        )
+    }
+
+    /// 列出最近发布的版本（不含草稿），按发布时间从新到旧；只读取第一页
+    pub async fn list(&self) -> Result<Vec<Release>> {
+        self.list_pages(|_| true).await
+    }
+
+    /// 列出直到 `current` 为止发布的所有版本（不含草稿），按发布时间从新到旧；
+    /// 沿 `Link: rel="next"` 翻页，直到出现 `current` 或没有下一页
+    pub async fn list_since(&self, current: &str) -> Result<Vec<Release>> {
+        self.list_pages(|page| {
+            page.iter()
+                .any(|r| compare_versions(&r.tag_name, current) == Some(Ordering::Equal))
+        })
+        .await
+    }
+
+    /// 逐页读取版本列表，`done` 对某一页返回 true 时停止
+    async fn list_pages(&self, done: impl Fn(&[Release]) -> bool) -> Result<Vec<Release>> {
+        let mut url = Some(format!("{}?per_page={}", self.api_url, RELEASES_PER_PAGE));
+        let mut releases = Vec::new();
+
+        for _ in 0..RELEASES_MAX_PAGES {
+            let Some(page_url) = url.take() else {
+                break;
+            };
+            let resp = self.get_api(&page_url).await?;
+            url = next_page(resp.headers());
+
+            let page: Vec<Release> = resp.json().await?;
+            let finished = done(&page);
+            releases.extend(page);
+            if finished {
+                break;
+            }
+        }
+
+        releases.retain(|r| !r.draft);
+        Ok(releases)
+    }
+
+    /// 查询指定标签的版本
+    pub async fn release(&self, tag: &str) -> Result<Release> {
+        Ok(self
+            .get_api(&format!("{}/tags/{}", self.api_url, tag))
+            .await?
+            .json()
+            .await?)
+    }
+
+    /// 查找附件发布的 SHA-256：依次读取附件摘要、校验文件附件与发布说明，返回校验值与来源
+    pub async fn published_sha256(
+        &self,
+        tag: &str,
+        asset: &str,
+    ) -> Result<Option<(Checksum, String)>> {
+        let release = self.release(tag).await?;
+        let sha256 = |sum: String| Checksum::new(ChecksumAlgorithm::Sha256, &sum).ok();
+
+        if let Some(sum) = release.asset_sha256(asset).and_then(sha256) {
+            return Ok(Some((sum, "附件摘要".to_string())));
+        }
+
+        for checksum_asset in release.checksum_assets(asset) {
+            let url = self.asset_url(tag, &checksum_asset.name);
+            let resp = self.client.get(&url).send().await;
+            if let Ok(resp) = resp
+                && resp.status().is_success()
+            {
+                let content = resp.text().await?;
+                let sum = hash::find_sha256(&content, asset)
+                    .or_else(|| hash::parse_checksum_file(&content))
+                    .and_then(sha256);
+                if let Some(sum) = sum {
+                    return Ok(Some((sum, checksum_asset.name.clone())));
+                }
+            }
+        }
+
+        Ok(release
+            .body
+            .as_deref()
+            .and_then(|body| hash::find_sha256(body, asset))
+            .and_then(sha256)
+            .map(|sum| (sum, "发布说明".to_string())))
+    }
+
+    async fn get_api(&self, url: &str) -> Result<reqwest::Response> {
+        let mut request = self
+            .client
+            .get(url)
+            .header(ACCEPT, "application/vnd.github+json");
+
+        // 令牌只发给 GitHub 官方 API，不泄露给镜像
+        if let Some(token) = &self.token {
+            if is_github_api(url) {
+                request = request.bearer_auth(token);
+            } else {
+                debug!("API 地址不是 GitHub，不发送 GITHUB_TOKEN");
+            }
+        }
+
+        let resp = request.send().await?;
+        if let Some(err) = rate_limit_error(resp.status(), resp.headers()) {
+            return Err(err);
+        }
+        if !resp.status().is_success() {
+            return Err(TronCtlError::DownloadFailed(format!(
+                "查询版本信息失败: {} HTTP {}",
+                url,
+                resp.status()
+            )));
+        }
+
+        Ok(resp)
+    }
+
+    /// 最新版本（未启用预发布时只看正式版本）
+    pub async fn latest(&self) -> Result<Release> {
+        self.list()
+            .await?
+            .into_iter()
+            .find(|r| self.prerelease || !r.prerelease)
+            .ok_or_else(|| TronCtlError::DownloadFailed("未找到可用版本".to_string()))
+    }
+
+    /// 检查是否有比 `current` 新的强制升级版本，结果缓存在 `state_file` 中，
+    /// 距上次检查不足 RELEASE_CHECK_INTERVAL_SECS 且版本未变时不请求 GitHub
+    pub async fn check_mandatory(
+        &self,
+        state_file: &Path,
+        current: &str,
+        now: i64,
+    ) -> Result<ReleaseState> {
+        let mut state: ReleaseState = std::fs::read_to_string(state_file)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+
+        if state.current == current && now - state.checked_at < RELEASE_CHECK_INTERVAL_SECS {
+            debug!("使用缓存的版本检查结果");
+            return Ok(state);
+        }
+
+        let releases = self.list_since(current).await?;
+        state.mandatory = newer_releases(&releases, current, self.prerelease)
+            .into_iter()
+            .filter(|r| r.is_mandatory())
+            .map(|r| r.tag_name)
+            .collect();
+        state.current = current.to_string();
+        state.checked_at = now;
+
+        save_state(state_file, &state)?;
+        Ok(state)
+    }
+}
+
+impl Default for ReleaseChecker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 比 `current` 新的版本，按版本从旧到新排序
+pub fn newer_releases(releases: &[Release], current: &str, prerelease: bool) -> Vec<Release> {
+    let mut newer: Vec<Release> = releases
+        .iter()
+        .filter(|r| {
+            (prerelease || !r.prerelease)
+                && compare_versions(&r.tag_name, current) == Some(Ordering::Greater)
+        })
+        .cloned()
+        .collect();
+
+    newer.sort_by(|a, b| compare_versions(&a.tag_name, &b.tag_name).unwrap_or(Ordering::Equal));
+    newer
+}
+
+/// 403/429 且剩余次数为 0（或带 Retry-After）时，解析限流响应头生成错误
+fn rate_limit_error(status: StatusCode, headers: &HeaderMap) -> Option<TronCtlError> {
+    if status != StatusCode::FORBIDDEN && status != StatusCode::TOO_MANY_REQUESTS {
+        return None;
+    }
+
+    let header = |name: &str| -> Option<i64> { headers.get(name)?.to_str().ok()?.parse().ok() };
+    let now = chrono::Utc::now().timestamp();
+
+    let reset = if header("x-ratelimit-remaining") == Some(0) {
+        header("x-ratelimit-reset").unwrap_or(now)
+    } else {
+        now + header(RETRY_AFTER.as_str())?
+    };
+
+    let reset_at = chrono::DateTime::from_timestamp(reset, 0)
+        .map(|at| {
+            at.with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string()
+        })
+        .unwrap_or_else(|| reset.to_string());
+
+    Some(TronCtlError::GithubRateLimited {
+        limit: header("x-ratelimit-limit").unwrap_or(0).max(0) as u64,
+        reset_at,
+    })
+}
+
+/// 解析 `Link` 响应头中 `rel="next"` 的地址
+fn next_page(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(LINK)?
+        .to_str()
+        .ok()?
+        .split(',')
+        .find_map(|link| {
+            let (url, params) = link.split_once(';')?;
+            params
+                .split(';')
+                .any(|param| param.trim() == r#"rel="next""#)
+                .then(|| {
+                    url.trim()
+                        .trim_start_matches('<')
+                        .trim_end_matches('>')
+                        .to_string()
+                })
+        })
+}
+
+/// 写入版本检查结果
+pub fn save_state(state_file: &Path, state: &ReleaseState) -> Result<()> {
+    if let Some(parent) = state_file.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(state_file, serde_json::to_string(state)?)?;
+    Ok(())
+}
+
+/// 是否为 GitHub 官方 API：必须是 https 且主机名完全一致，前缀相同的镜像域名不算
+fn is_github_api(url: &str) -> bool {
+    let official = reqwest::Url::parse(GITHUB_API_BASE).ok();
+    reqwest::Url::parse(url).is_ok_and(|url| {
+        url.scheme() == "https"
+            && url.host_str().is_some()
+            && url.host_str() == official.as_ref().and_then(|o| o.host_str())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const RELEASES_JSON: &str = r#"[
+        {"tag_name": "GreatVoyage-v4.8.1", "name": "4.8.1", "body": null, "draft": false,
+         "prerelease": true, "published_at": "2025-06-01T00:00:00Z", "html_url": "https://example/4.8.1"},
+        {"tag_name": "GreatVoyage-v4.8.0", "name": "Kant", "body": "This is a mandatory upgrade version.",
+         "draft": false, "prerelease": false, "published_at": "2025-03-20T00:00:00Z", "html_url": "https://example/4.8.0"},
+        {"tag_name": "GreatVoyage-v4.7.7", "name": "Epicurus", "body": "This is a non-mandatory upgrade version.",
+         "draft": false, "prerelease": false, "published_at": "2024-12-01T00:00:00Z", "html_url": "https://example/4.7.7"},
+        {"tag_name": "GreatVoyage-v4.7.6", "name": null, "body": "draft", "draft": true,
+         "prerelease": false, "published_at": null, "html_url": "https://example/4.7.6"}
+    ]"#;
+
+    async fn mock_releases(server: &mut mockito::ServerGuard, hits: usize) -> mockito::Mock {
+        server
+            .mock("GET", "/releases")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_body(RELEASES_JSON)
+            .expect(hits)
+            .create_async()
+            .await
+    }
+
+    fn checker(server: &mockito::ServerGuard) -> ReleaseChecker {
+        ReleaseChecker::with_endpoints(&format!("{}/releases", server.url()), &server.url())
+    }
+
+    #[tokio::test]
+    async fn test_list_and_latest() {
+        let mut server = mockito::Server::new_async().await;
+        let _mock = mock_releases(&mut server, 2).await;
+        let checker = checker(&server);
+
+        let releases = checker.list().await.unwrap();
+        assert_eq!(releases.len(), 3);
+
+        // 跳过预发布版本
+        let latest = checker.latest().await.unwrap();
+        assert_eq!(latest.tag_name, "GreatVoyage-v4.8.0");
+    }
+
+    #[tokio::test]
+    async fn test_latest_prerelease() {
+        let mut server = mockito::Server::new_async().await;
+        let _mock = mock_releases(&mut server, 1).await;
+
+        let latest = checker(&server)
+            .with_prerelease(true)
+            .latest()
+            .await
+            .unwrap();
+        assert_eq!(latest.tag_name, "GreatVoyage-v4.8.1");
+    }
+
+    #[test]
+    fn test_is_github_api() {
+        assert!(is_github_api("https://api.github.com/repos/a/b/releases"));
+        assert!(is_github_api("https://API.github.com:443/repos"));
+        assert!(!is_github_api("https://api.github.com.example.org/repos"));
+        assert!(!is_github_api("http://api.github.com/repos"));
+        assert!(!is_github_api("https://example.org/api.github.com"));
+        assert!(!is_github_api("not a url"));
+    }
+
+    #[tokio::test]
+    async fn test_token_not_sent_to_mirror() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/releases")
+            .match_query(mockito::Matcher::Any)
+            .match_header("authorization", mockito::Matcher::Missing)
+            .with_status(200)
+            .with_body("[]")
+            .create_async()
+            .await;
+
+        checker(&server).with_token("secret").list().await.unwrap();
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_rate_limited() {
+        let mut server = mockito::Server::new_async().await;
+        let _mock = server
+            .mock("GET", "/releases")
+            .match_query(mockito::Matcher::Any)
+            .with_status(403)
+            .with_header("x-ratelimit-limit", "60")
+            .with_header("x-ratelimit-remaining", "0")
+            .with_header("x-ratelimit-reset", "1735689600")
+            .with_body(r#"{"message": "API rate limit exceeded"}"#)
+            .create_async()
+            .await;
+
+        let result = checker(&server).list().await;
+        match result {
+            Err(TronCtlError::GithubRateLimited { limit, reset_at }) => {
+                assert_eq!(limit, 60);
+                assert!(reset_at.starts_with("2025-01-01") || reset_at.starts_with("2024-12-31"));
+            }
+            other => panic!("unexpected: {:?}", other.map(|r| r.len())),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_published_sha256_sources() {
+        let sum = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+        let mut server = mockito::Server::new_async().await;
+        let _digest = server
+            .mock("GET", "/releases/tags/v1")
+            .with_body(format!(
+                r#"{{"tag_name": "v1", "assets": [{{"name": "FullNode.jar", "digest": "sha256:{}"}}]}}"#,
+                sum
+            ))
+            .create_async()
+            .await;
+        let _file = server
+            .mock("GET", "/releases/tags/v2")
+            .with_body(r#"{"tag_name": "v2", "assets": [{"name": "FullNode.jar"}, {"name": "FullNode.jar.sha256"}]}"#)
+            .create_async()
+            .await;
+        let _sidecar = server
+            .mock(
+                "GET",
+                "/tronprotocol/java-tron/releases/download/v2/FullNode.jar.sha256",
+            )
+            .with_body(format!("{}  FullNode.jar\n", sum))
+            .create_async()
+            .await;
+        let _notes = server
+            .mock("GET", "/releases/tags/v3")
+            .with_body(format!(
+                r#"{{"tag_name": "v3", "body": "| FullNode.jar | {} |", "assets": []}}"#,
+                sum
+            ))
+            .create_async()
+            .await;
+        let _none = server
+            .mock("GET", "/releases/tags/v4")
+            .with_body(r#"{"tag_name": "v4", "body": "no checksums"}"#)
+            .create_async()
+            .await;
+
+        let checker = checker(&server);
+        for (tag, source) in [
+            ("v1", "附件摘要"),
+            ("v2", "FullNode.jar.sha256"),
+            ("v3", "发布说明"),
+        ] {
+            let found = checker.published_sha256(tag, "FullNode.jar").await.unwrap();
+            assert_eq!(
+                found,
+                Some((
+                    Checksum::new(ChecksumAlgorithm::Sha256, sum).unwrap(),
+                    source.to_string()
+                )),
+                "{}",
+                tag
+            );
+        }
+        assert_eq!(
+            checker
+                .published_sha256("v4", "FullNode.jar")
+                .await
+                .unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_rate_limit_error_ignores_other_403() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-ratelimit-remaining", "42".parse().unwrap());
+        assert!(rate_limit_error(StatusCode::FORBIDDEN, &headers).is_none());
+        assert!(rate_limit_error(StatusCode::NOT_FOUND, &HeaderMap::new()).is_none());
+
+        headers.insert(RETRY_AFTER, "60".parse().unwrap());
+        assert!(rate_limit_error(StatusCode::TOO_MANY_REQUESTS, &headers).is_some());
+    }
+
+    #[test]
+    fn test_asset_url() {
+        let checker = ReleaseChecker::new();
+        assert_eq!(
+            checker.asset_url("GreatVoyage-v4.8.0", "FullNode.jar"),
+            "https://github.com/tronprotocol/java-tron/releases/download/GreatVoyage-v4.8.0/FullNode.jar"
+        );
+
+        let mirror =
+            ReleaseChecker::with_endpoints(GITHUB_API_RELEASES, "https://mirror.example/gh/");
+        assert!(
+            mirror
+                .asset_url("v1", "FullNode.jar")
+                .starts_with("https://mirror.example/gh/tronprotocol/")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_check_mandatory_uses_cache() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = mock_releases(&mut server, 2).await;
+        let checker = checker(&server);
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("release.state");
+
+        let state = checker
+            .check_mandatory(&state_file, "4.7.6", 1000)
+            .await
+            .unwrap();
+        assert_eq!(state.mandatory, vec!["GreatVoyage-v4.8.0"]);
+
+        // 间隔内且版本未变：使用缓存
+        checker
+            .check_mandatory(&state_file, "4.7.6", 2000)
+            .await
+            .unwrap();
+
+        // 版本变化后重新检查
+        let state = checker
+            .check_mandatory(&state_file, "4.8.0", 3000)
+            .await
+            .unwrap();
+        assert!(state.mandatory.is_empty());
+        mock.assert_async().await;
+    }
+
+    fn release_json(tag: &str, body: &str) -> String {
+        format!(
+            r#"{{"tag_name": "{}", "name": null, "body": "{}", "draft": false,
+                "prerelease": false, "published_at": null, "html_url": "https://example/{}"}}"#,
+            tag, body, tag
+        )
+    }
+
+    #[tokio::test]
+    async fn test_list_since_follows_next_link() {
+        let mut server = mockito::Server::new_async().await;
+        let next = format!("{}/releases?per_page=100&page=2", server.url());
+        let first = server
+            .mock("GET", "/releases")
+            .match_query(mockito::Matcher::Exact("per_page=100".to_string()))
+            .with_header(
+                "link",
+                &format!(
+                    r#"<{}>; rel="next", <{}/releases?per_page=100&page=3>; rel="last""#,
+                    next,
+                    server.url()
+                ),
+            )
+            .with_body(format!(
+                "[{}]",
+                release_json("GreatVoyage-v4.8.0", "This is a mandatory upgrade version.")
+            ))
+            .expect(2)
+            .create_async()
+            .await;
+        let second = server
+            .mock("GET", "/releases")
+            .match_query(mockito::Matcher::Exact("per_page=100&page=2".to_string()))
+            .with_header(
+                "link",
+                &format!(
+                    r#"<{}/releases?per_page=100&page=3>; rel="next""#,
+                    server.url()
+                ),
+            )
+            .with_body(format!(
+                "[{}, {}]",
+                release_json("GreatVoyage-v4.7.7", "This is a mandatory upgrade version."),
+                release_json("GreatVoyage-v4.7.6", "")
+            ))
+            .expect(1)
+            .create_async()
+            .await;
+        let checker = checker(&server);
+
+        // 当前版本在第二页：翻页后停止，不再读取第三页
+        let tags: Vec<String> = checker
+            .list_since("4.7.6")
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|r| r.tag_name)
+            .collect();
+        assert_eq!(
+            tags,
+            vec![
+                "GreatVoyage-v4.8.0",
+                "GreatVoyage-v4.7.7",
+                "GreatVoyage-v4.7.6"
+            ]
+        );
+
+        // 当前版本在第一页时不翻页
+        assert_eq!(checker.list_since("4.8.0").await.unwrap().len(), 1);
+        first.assert_async().await;
+        second.assert_async().await;
+    }
+
+    #[test]
+    fn test_next_page() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(next_page(&headers), None);
+
+        headers.insert(
+            LINK,
+            r#"<https://api.github.com/r?page=1>; rel="prev", <https://api.github.com/r?page=3>; rel="next""#
+                .parse()
+                .unwrap(),
+        );
+        assert_eq!(
+            next_page(&headers).as_deref(),
+            Some("https://api.github.com/r?page=3")
+        );
+
+        headers.insert(
+            LINK,
+            r#"<https://api.github.com/r?page=1>; rel="first""#.parse().unwrap(),
+        );
+        assert_eq!(next_page(&headers), None);
+    }
+
+    #[test]
+    fn test_newer_releases() {
+        let releases: Vec<Release> = serde_json::from_str(RELEASES_JSON).unwrap();
+        let tags: Vec<String> = newer_releases(&releases, "GreatVoyage-v4.7.6", false)
+            .into_iter()
+            .map(|r| r.tag_name)
+            .collect();
+        // 按版本从旧到新，跳过预发布版本
+        assert_eq!(tags, vec!["GreatVoyage-v4.7.7", "GreatVoyage-v4.8.0"]);
+        assert!(newer_releases(&releases, "4.8.0", false).is_empty());
+        assert_eq!(newer_releases(&releases, "4.8.0", true).len(), 1);
+    }
+}
@@ -1,103 +1,244 @@
+use crate::constants::{
+    SNAPSHOT_LIST_CONCURRENCY, SNAPSHOT_LOOKBACK_DAYS, SNAPSHOT_PROBE_BYTES,
+    SNAPSHOT_PROBE_TIMEOUT_SECS,
+};
+use crate::core::downloader::Mirror;
 use crate::error::{Result, TronCtlError};
-use crate::models::snapshot_info::{SnapshotMetadata, SnapshotServer};
+use crate::models::checksum::{Checksum, ChecksumAlgorithm};
+use crate::models::node_config::SnapshotConfig;
+use crate::models::snapshot_info::{SnapshotMetadata, SnapshotServer, SnapshotSource};
 use crate::utils::network;
+use futures::future::join_all;
+use futures::stream::{self, StreamExt};
 use reqwest::Client;
 use std::time::Duration;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
 pub struct SnapshotManager {
     client: Client,
+    sources: Vec<SnapshotSource>,
 }
 
 impl SnapshotManager {
+    /// 使用内置快照服务器
     pub fn new() -> Self {
+        Self::from_config(&SnapshotConfig::default())
+    }
+
+    /// 使用 `[snapshot]` 中配置的快照服务器，未配置时使用内置服务器
+    pub fn from_config(config: &SnapshotConfig) -> Self {
         Self {
             client: Client::builder()
                 .timeout(Duration::from_secs(10))
                 .user_agent("tronctl/0.1.0")
                 .build()
                 .expect("Failed to build HTTP client"),
+            sources: config.sources(),
         }
     }
 
-    /// 自动选择最快的快照服务器
-    pub async fn select_fastest_server(&self) -> Result<SnapshotServer> {
-        info!("测试快照服务器延迟...");
+    /// 附加了服务器所需 HTTP 头的客户端
+    fn client_for(&self, source: &SnapshotSource) -> Result<Client> {
+        if source.headers.is_empty() {
+            return Ok(self.client.clone());
+        }
+
+        Ok(Client::builder()
+            .timeout(Duration::from_secs(10))
+            .user_agent("tronctl/0.1.0")
+            .default_headers(network::header_map(&source.headers)?)
+            .build()?)
+    }
 
-        let mut servers = Vec::new();
+    /// 快照类型对应的标称大小（GB）与文件名前缀
+    fn snapshot_kind(snapshot_type: &str) -> Result<(u64, &'static str)> {
+        match snapshot_type {
+            "lite" => Ok((53, "LiteFullNode_output-directory")),
+            "full" => Ok((2937, "FullNode_output-directory")),
+            _ => Err(TronCtlError::ConfigError(format!(
+                "无效的快照类型: {}",
+                snapshot_type
+            ))),
+        }
+    }
+
+    /// 服务器未返回 Content-Length 时用于磁盘规划的标称大小（字节）
+    pub fn nominal_bytes(snapshot_type: &str) -> Result<u64> {
+        Ok(Self::snapshot_kind(snapshot_type)?.0 * 1024 * 1024 * 1024)
+    }
 
-        for &url in crate::constants::SNAPSHOT_SERVERS {
-            let latency = network::measure_latency(&self.client, url, Duration::from_secs(5)).await;
+    /// 并发测试所有快照服务器的延迟
+    pub async fn probe_servers(&self) -> Vec<SnapshotServer> {
+        info!("测试快照服务器延迟...");
 
-            let server = SnapshotServer {
-                url: url.to_string(),
+        let probes = self.sources.iter().map(|source| async move {
+            let latency = match self.client_for(source) {
+                Ok(client) => {
+                    network::measure_latency(&client, &source.url, Duration::from_secs(5)).await
+                }
+                Err(e) => {
+                    warn!("跳过快照服务器 {}: {}", source.url, e);
+                    None
+                }
+            };
+
+            SnapshotServer {
+                source: source.clone(),
                 latency: latency.unwrap_or(Duration::from_secs(999)),
                 available: latency.is_some(),
-            };
+                throughput: None,
+                snapshot: None,
+            }
+        });
 
-            info!(
-                "  {} - {}ms {}",
-                server.url,
-                server.latency.as_millis(),
-                if server.available {
-                    "可用"
-                } else {
-                    "不可用"
-                }
-            );
+        let mut servers = join_all(probes).await;
+        servers.sort_by_key(|s| (!s.available, s.source.priority, s.latency));
+        servers
+    }
 
-            servers.push(server);
+    /// 并发探测所有服务器：延迟、是否提供目标快照，以及短时分段下载的实测吞吐
+    ///
+    /// 未指定日期时以各服务器中最新的快照为目标，没有该快照的服务器被排除；
+    /// 可用服务器按优先级、吞吐、延迟排序
+    pub async fn rank_servers(
+        &self,
+        snapshot_type: &str,
+        date: Option<&str>,
+    ) -> Result<Vec<SnapshotServer>> {
+        Self::snapshot_kind(snapshot_type)?;
+        if let Some(date) = date {
+            parse_snapshot_date(date)?;
         }
+        info!("探测快照服务器...");
+
+        let probes = self
+            .sources
+            .iter()
+            .map(|source| self.probe(source, snapshot_type, date));
+        let mut servers = join_all(probes).await;
+
+        let newest = newest_date(&servers);
+        servers.sort_by_key(|s| {
+            (
+                !s.has_snapshot(newest.as_deref()),
+                s.source.priority,
+                std::cmp::Reverse(s.throughput.unwrap_or(0)),
+                s.latency,
+            )
+        });
+        Ok(servers)
+    }
 
-        servers.sort_by_key(|s| s.latency);
+    /// 从 [`rank_servers`](Self::rank_servers) 的结果中选择提供目标快照且吞吐最高的服务器
+    ///
+    /// 其他服务器上大小与校验值都相同的快照作为镜像一并返回
+    pub fn select_server(
+        servers: Vec<SnapshotServer>,
+        snapshot_type: &str,
+        date: Option<&str>,
+    ) -> Result<SnapshotSelection> {
+        let newest = newest_date(&servers);
+        let server = servers
+            .iter()
+            .find(|s| s.has_snapshot(newest.as_deref()))
+            .ok_or_else(|| match date {
+                Some(date) => TronCtlError::DownloadFailed(format!(
+                    "所有服务器上都没有 {} 的 {} 快照",
+                    date, snapshot_type
+                )),
+                None => {
+                    TronCtlError::DownloadFailed(format!("未找到可用的 {} 快照", snapshot_type))
+                }
+            })?;
 
-        servers
+        let metadata = server.snapshot.clone().expect("已确认服务器提供快照");
+        let chosen = server.source.clone();
+        info!("使用快照服务器: {} ({} 快照)", server.url(), metadata.date);
+
+        let servers: Vec<SnapshotServer> = servers
             .into_iter()
-            .find(|s| s.available)
-            .ok_or_else(|| TronCtlError::DownloadFailed("无可用快照服务器".to_string()))
+            .filter(|s| s.has_snapshot(newest.as_deref()))
+            .filter(|s| {
+                s.source == chosen || s.snapshot.as_ref().is_some_and(|m| m.same_file(&metadata))
+            })
+            .collect();
+        if servers.len() > 1 {
+            info!("{} 个服务器提供相同的快照，可并行下载", servers.len());
+        }
+
+        Ok(SnapshotSelection { metadata, servers })
     }
 
-    /// 获取最新的快照元数据
-    pub async fn get_latest_snapshot(
+    /// 探测单个服务器
+    async fn probe(
         &self,
-        server: &SnapshotServer,
+        source: &SnapshotSource,
         snapshot_type: &str,
-    ) -> Result<SnapshotMetadata> {
-        debug!("查找最新快照: 类型={}", snapshot_type);
+        date: Option<&str>,
+    ) -> SnapshotServer {
+        let mut server = SnapshotServer {
+            source: source.clone(),
+            latency: Duration::from_secs(999),
+            available: false,
+            throughput: None,
+            snapshot: None,
+        };
 
-        let (size_gb, filename_prefix) = match snapshot_type {
-            "lite" => (53, "LiteFullNode_output-directory"),
-            "full" => (2937, "FullNode_output-directory"),
-            _ => {
-                return Err(TronCtlError::ConfigError(format!(
-                    "无效的快照类型: {}",
-                    snapshot_type
-                )));
+        let client = match self.client_for(source) {
+            Ok(client) => client,
+            Err(e) => {
+                warn!("跳过快照服务器 {}: {}", source.url, e);
+                return server;
             }
         };
 
-        // 从今天开始向前尝试7天
-        for days_ago in 0..7 {
-            let date = chrono::Utc::now() - chrono::Duration::days(days_ago);
-            let date_str = date.format("%Y%m%d").to_string();
-
-            // 构造快照 URL，格式如：backup20260109/FullNode_output-directory.tgz
-            let snapshot_url = format!("{}/backup{}/{}.tgz", server.url, date_str, filename_prefix);
-            let md5_url = format!("{}.md5sum", snapshot_url);
+        let Some(latency) =
+            network::measure_latency(&client, &source.url, Duration::from_secs(5)).await
+        else {
+            return server;
+        };
+        server.latency = latency;
+        server.available = true;
+
+        let snapshot = match date {
+            Some(date) => self.snapshot_at(source, snapshot_type, date).await,
+            None => self
+                .get_latest_snapshot(source, snapshot_type)
+                .await
+                .map(Some),
+        };
+        server.snapshot = snapshot.ok().flatten();
+
+        if let Some(snapshot) = &server.snapshot {
+            server.throughput = network::measure_throughput(
+                &client,
+                &snapshot.download_url,
+                SNAPSHOT_PROBE_BYTES,
+                Duration::from_secs(SNAPSHOT_PROBE_TIMEOUT_SECS),
+            )
+            .await;
+        }
 
-            debug!("尝试快照: {}", snapshot_url);
+        server
+    }
 
-            if network::check_url_exists(&self.client, &snapshot_url).await {
-                let md5 = self.fetch_md5(&md5_url).await.unwrap_or_default();
+    /// 获取服务器上最新的快照元数据
+    pub async fn get_latest_snapshot(
+        &self,
+        source: &SnapshotSource,
+        snapshot_type: &str,
+    ) -> Result<SnapshotMetadata> {
+        debug!("查找最新快照: 类型={}", snapshot_type);
+        Self::snapshot_kind(snapshot_type)?;
 
-                info!("找到快照: {} ({} GB)", date_str, size_gb);
+        // 从今天开始向前尝试
+        for days_ago in 0..SNAPSHOT_LOOKBACK_DAYS {
+            let date = chrono::Utc::now() - chrono::Duration::days(days_ago);
+            let date_str = date.format("%Y%m%d").to_string();
 
-                return Ok(SnapshotMetadata {
-                    date: date_str,
-                    size_gb,
-                    md5,
-                    download_url: snapshot_url,
-                });
+            if let Some(metadata) = self.snapshot_at(source, snapshot_type, &date_str).await? {
+                debug!("{} 的最新快照: {}", source.url, date_str);
+                return Ok(metadata);
             }
         }
 
@@ -107,12 +248,219 @@ impl SnapshotManager {
         )))
     }
 
-    async fn fetch_md5(&self, url: &str) -> Result<String> {
-        let resp = self.client.get(url).send().await?;
-        let text = resp.text().await?;
+    /// 查询服务器上指定日期的快照，不存在时返回 None
+    pub async fn snapshot_at(
+        &self,
+        source: &SnapshotSource,
+        snapshot_type: &str,
+        date: &str,
+    ) -> Result<Option<SnapshotMetadata>> {
+        let (_, filename_prefix) = Self::snapshot_kind(snapshot_type)?;
+        let client = self.client_for(source)?;
+
+        // 按服务器的路径模板构造快照 URL，默认如：backup20260109/FullNode_output-directory.tgz
+        let snapshot_url = source.snapshot_url(date, filename_prefix);
+        debug!("尝试快照: {}", snapshot_url);
+
+        if !network::check_url_exists(&client, &snapshot_url).await {
+            return Ok(None);
+        }
+
+        // 大小以服务器返回的 Content-Length 为准，缺失时记为 0
+        let size_bytes = network::content_length(&client, &snapshot_url)
+            .await
+            .unwrap_or(0);
+        let checksum = Self::fetch_checksum(&client, &snapshot_url).await;
+
+        Ok(Some(SnapshotMetadata {
+            date: date.to_string(),
+            size_gb: size_bytes.div_ceil(1024 * 1024 * 1024),
+            size_bytes,
+            checksum,
+            download_url: snapshot_url,
+        }))
+    }
+
+    /// 列出服务器上指定类型的所有快照，按日期从新到旧排列
+    pub async fn list_snapshots(
+        &self,
+        source: &SnapshotSource,
+        snapshot_type: &str,
+    ) -> Result<Vec<SnapshotMetadata>> {
+        Self::snapshot_kind(snapshot_type)?;
+
+        let mut dates = self.list_dates(source).await?;
+        if dates.is_empty() {
+            // 服务器未开启目录索引时，逐日探测最近的快照
+            dates = (0..SNAPSHOT_LOOKBACK_DAYS)
+                .map(|days_ago| {
+                    (chrono::Utc::now() - chrono::Duration::days(days_ago))
+                        .format("%Y%m%d")
+                        .to_string()
+                })
+                .collect();
+        }
+
+        // 每个日期需要多次请求（HEAD、大小、校验文件），并发探测并保持日期顺序
+        let probed: Vec<Result<Option<SnapshotMetadata>>> = stream::iter(dates)
+            .map(|date| async move { self.snapshot_at(source, snapshot_type, &date).await })
+            .buffered(SNAPSHOT_LIST_CONCURRENCY)
+            .collect()
+            .await;
+
+        let mut snapshots = Vec::new();
+        for metadata in probed {
+            snapshots.extend(metadata?);
+        }
+        Ok(snapshots)
+    }
+
+    /// 从路径模板中 `{date}` 所在目录的索引页读取日期，默认布局为根目录下的 `backupYYYYMMDD`
+    async fn list_dates(&self, source: &SnapshotSource) -> Result<Vec<String>> {
+        let Some((index_url, marker)) = source.date_index() else {
+            return Ok(Vec::new());
+        };
+
+        let resp = match self.client_for(source)?.get(&index_url).send().await {
+            Ok(resp) if resp.status().is_success() => resp,
+            _ => return Ok(Vec::new()),
+        };
+
+        Ok(parse_backup_dates(
+            &resp.text().await.unwrap_or_default(),
+            &marker,
+        ))
+    }
+
+    /// 依次尝试 `.sha512sum`、`.sha256sum`、`.sha1sum`、`.md5sum` 校验文件，返回最强的可用校验值
+    async fn fetch_checksum(client: &Client, snapshot_url: &str) -> Option<Checksum> {
+        for algorithm in ChecksumAlgorithm::ALL {
+            let url = format!("{}{}", snapshot_url, algorithm.sidecar_suffix());
+            let Ok(resp) = client.get(&url).send().await else {
+                continue;
+            };
+            if !resp.status().is_success() {
+                continue;
+            }
+
+            match Checksum::from_sidecar(algorithm, &resp.text().await.unwrap_or_default()) {
+                Some(checksum) => return Some(checksum),
+                None => debug!("无法解析校验文件: {}", url),
+            }
+        }
+
+        None
+    }
+}
+
+/// 选定的快照，以及提供完全相同文件的服务器（最优者在前）
+#[derive(Debug, Clone)]
+pub struct SnapshotSelection {
+    pub metadata: SnapshotMetadata,
+    pub servers: Vec<SnapshotServer>,
+}
+
+impl SnapshotSelection {
+    /// 吞吐最高的服务器
+    pub fn primary(&self) -> &SnapshotServer {
+        &self.servers[0]
+    }
+
+    /// 所有服务器作为下载镜像，按实测吞吐分配下载连接
+    pub fn mirrors(&self) -> Result<Vec<Mirror>> {
+        self.servers
+            .iter()
+            .filter_map(|server| Some((server, server.snapshot.as_ref()?)))
+            .map(|(server, snapshot)| {
+                Ok(Mirror {
+                    url: snapshot.download_url.clone(),
+                    headers: network::header_map(&server.source.headers)?,
+                    weight: server.throughput.unwrap_or(0),
+                })
+            })
+            .collect()
+    }
+}
+
+/// 探测结果中最新的快照日期
+fn newest_date(servers: &[SnapshotServer]) -> Option<String> {
+    servers
+        .iter()
+        .filter_map(|s| s.snapshot.as_ref())
+        .map(|snapshot| snapshot.date.clone())
+        .max()
+}
+
+/// 各服务器探测结果表格
+pub fn server_table(servers: &[SnapshotServer]) -> String {
+    let newest = newest_date(servers);
+    let mut table = format!(
+        "  {:<40} {:>8} {:>12} {:>10}  状态",
+        "服务器", "延迟", "吞吐", "快照"
+    );
+
+    for server in servers {
+        let latency = if server.available {
+            format!("{}ms", server.latency.as_millis())
+        } else {
+            "-".to_string()
+        };
+        let throughput = server
+            .throughput
+            .map(|bps| format!("{:.1}MB/s", bps as f64 / (1024.0 * 1024.0)))
+            .unwrap_or_else(|| "-".to_string());
+        let date = server
+            .snapshot
+            .as_ref()
+            .map(|snapshot| snapshot.date.as_str())
+            .unwrap_or("-");
+        let status = if !server.available {
+            "不可达"
+        } else if server.snapshot.is_none() {
+            "无快照"
+        } else if !server.has_snapshot(newest.as_deref()) {
+            "快照过期"
+        } else {
+            "可用"
+        };
 
-        Ok(text.split_whitespace().next().unwrap_or("").to_string())
+        table.push_str(&format!(
+            "\n  {:<40} {:>8} {:>12} {:>10}  {}",
+            server.url(),
+            latency,
+            throughput,
+            date,
+            status
+        ));
     }
+
+    table
+}
+
+/// 校验 `YYYYMMDD` 格式的快照日期
+pub fn parse_snapshot_date(date: &str) -> Result<chrono::NaiveDate> {
+    chrono::NaiveDate::parse_from_str(date, "%Y%m%d")
+        .ok()
+        .filter(|_| date.len() == 8)
+        .ok_or_else(|| {
+            TronCtlError::ConfigError(format!("无效的快照日期: {}，格式应为 YYYYMMDD", date))
+        })
+}
+
+/// 从目录索引页面中提取 `{marker}YYYYMMDD` 形式的日期，去重后从新到旧排列
+fn parse_backup_dates(index: &str, marker: &str) -> Vec<String> {
+    let start = marker.len();
+    let mut dates: Vec<String> = index
+        .match_indices(marker)
+        .filter_map(|(pos, _)| {
+            let date = index.get(pos + start..pos + start + 8)?;
+            (date.bytes().all(|b| b.is_ascii_digit()) && parse_snapshot_date(date).is_ok())
+                .then(|| date.to_string())
+        })
+        .collect();
+    dates.sort_unstable_by(|a, b| b.cmp(a));
+    dates.dedup();
+    dates
 }
 
 impl Default for SnapshotManager {
@@ -140,61 +488,331 @@ mod tests {
     #[tokio::test]
     async fn test_get_latest_snapshot_invalid_type() {
         let manager = SnapshotManager::new();
-        let server = SnapshotServer {
-            url: "http://test.com".to_string(),
-            latency: Duration::from_millis(100),
-            available: true,
-        };
+        let source = SnapshotSource::builtin("http://test.com");
 
-        let result = manager.get_latest_snapshot(&server, "invalid").await;
+        let result = manager.get_latest_snapshot(&source, "invalid").await;
         assert!(result.is_err());
         assert!(matches!(result.unwrap_err(), TronCtlError::ConfigError(_)));
     }
 
     #[tokio::test]
-    async fn test_fetch_md5_success() {
+    async fn test_fetch_checksum_md5() {
         let mut server = mockito::Server::new_async().await;
-        let md5_content = "abc123def456  filename.tgz\n";
 
         let _mock = server
-            .mock("GET", "/test.md5sum")
+            .mock("GET", "/test.tgz.md5sum")
             .with_status(200)
-            .with_body(md5_content)
+            .with_body("900150983CD24FB0D6963F7D28E17F72  test.tgz\n")
+            .create_async()
+            .await;
+        let _missing = server
+            .mock("GET", mockito::Matcher::Regex(r"\.sha\d+sum$".to_string()))
+            .with_status(404)
             .create_async()
             .await;
 
         let manager = SnapshotManager::new();
-        let url = format!("{}/test.md5sum", server.url());
-        let md5 = manager.fetch_md5(&url).await.unwrap();
+        let url = format!("{}/test.tgz", server.url());
+        let checksum = SnapshotManager::fetch_checksum(&manager.client, &url)
+            .await
+            .unwrap();
 
-        assert_eq!(md5, "abc123def456");
+        assert_eq!(checksum.algorithm, ChecksumAlgorithm::Md5);
+        assert_eq!(checksum.value, "900150983cd24fb0d6963f7d28e17f72");
     }
 
     #[tokio::test]
-    async fn test_fetch_md5_empty() {
+    async fn test_fetch_checksum_prefers_sha256() {
+        let mut server = mockito::Server::new_async().await;
+        let sha256 = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+
+        let _sha256 = server
+            .mock("GET", "/test.tgz.sha256sum")
+            .with_status(200)
+            .with_body(format!("{}  test.tgz\n", sha256))
+            .create_async()
+            .await;
+        let _md5 = server
+            .mock("GET", "/test.tgz.md5sum")
+            .with_status(200)
+            .with_body("900150983cd24fb0d6963f7d28e17f72  test.tgz\n")
+            .create_async()
+            .await;
+        let _missing = server
+            .mock(
+                "GET",
+                mockito::Matcher::Regex(r"\.sha(1|512)sum$".to_string()),
+            )
+            .with_status(404)
+            .create_async()
+            .await;
+
+        let manager = SnapshotManager::new();
+        let url = format!("{}/test.tgz", server.url());
+        let checksum = SnapshotManager::fetch_checksum(&manager.client, &url)
+            .await
+            .unwrap();
+
+        assert_eq!(checksum.algorithm, ChecksumAlgorithm::Sha256);
+        assert_eq!(checksum.value, sha256);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_checksum_empty() {
         let mut server = mockito::Server::new_async().await;
 
         let _mock = server
-            .mock("GET", "/test.md5sum")
+            .mock("GET", mockito::Matcher::Any)
             .with_status(200)
             .with_body("")
             .create_async()
             .await;
 
         let manager = SnapshotManager::new();
-        let url = format!("{}/test.md5sum", server.url());
-        let md5 = manager.fetch_md5(&url).await.unwrap();
+        let url = format!("{}/test.tgz", server.url());
+        assert!(
+            SnapshotManager::fetch_checksum(&manager.client, &url)
+                .await
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fetch_checksum_network_error() {
+        let manager = SnapshotManager::new();
+        let result = SnapshotManager::fetch_checksum(
+            &manager.client,
+            "http://invalid.test.nonexistent/test.tgz",
+        )
+        .await;
+
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn test_parse_snapshot_date() {
+        assert!(parse_snapshot_date("20260109").is_ok());
+        assert!(parse_snapshot_date("20261309").is_err());
+        assert!(parse_snapshot_date("2026-01-09").is_err());
+        assert!(parse_snapshot_date("2026019").is_err());
+    }
 
-        assert_eq!(md5, "");
+    #[test]
+    fn test_parse_backup_dates() {
+        let index = r#"<html><body><pre>
+<a href="../">../</a>
+<a href="backup20260108/">backup20260108/</a>   08-Jan-2026 02:00    -
+<a href="backup20260109/">backup20260109/</a>   09-Jan-2026 02:00    -
+<a href="backup2026/">backup2026/</a>
+<a href="backup20261399/">backup20261399/</a>
+</pre></body></html>"#;
+
+        assert_eq!(
+            parse_backup_dates(index, "backup"),
+            vec!["20260109", "20260108"]
+        );
+        assert!(parse_backup_dates("", "backup").is_empty());
+        assert_eq!(
+            parse_backup_dates(r#"<a href="20260109/">20260109/</a>"#, ""),
+            vec!["20260109"]
+        );
     }
 
     #[tokio::test]
-    async fn test_fetch_md5_network_error() {
+    async fn test_snapshot_at() {
+        let mut server = mockito::Server::new_async().await;
+        let path = "/backup20260109/LiteFullNode_output-directory.tgz";
+
+        let _head = server
+            .mock("HEAD", path)
+            .with_status(200)
+            .with_header("content-length", "56908316672")
+            .create_async()
+            .await;
+        let _md5 = server
+            .mock("GET", format!("{}.md5sum", path).as_str())
+            .with_status(200)
+            .with_body("900150983cd24fb0d6963f7d28e17f72  snapshot.tgz\n")
+            .create_async()
+            .await;
+        let _missing = server
+            .mock("GET", mockito::Matcher::Regex(r"\.sha\d+sum$".to_string()))
+            .with_status(404)
+            .create_async()
+            .await;
+
         let manager = SnapshotManager::new();
-        let result = manager
-            .fetch_md5("http://invalid.test.nonexistent/test.md5sum")
+        let metadata = manager
+            .snapshot_at(&SnapshotSource::builtin(&server.url()), "lite", "20260109")
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(metadata.size_bytes, 56908316672);
+        assert_eq!(metadata.size_gb, 53);
+        assert_eq!(metadata.checksum.unwrap().algorithm, ChecksumAlgorithm::Md5);
+        assert!(metadata.download_url.ends_with(path));
+    }
+
+    #[tokio::test]
+    async fn test_list_snapshots_from_index() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _index = server
+            .mock("GET", "/")
+            .with_status(200)
+            .with_body(r#"<a href="backup20260108/">x</a><a href="backup20260109/">y</a>"#)
+            .create_async()
+            .await;
+        let _present = server
+            .mock("HEAD", "/backup20260109/FullNode_output-directory.tgz")
+            .with_status(200)
+            .with_header("content-length", "1024")
+            .create_async()
+            .await;
+        let _absent = server
+            .mock("HEAD", "/backup20260108/FullNode_output-directory.tgz")
+            .with_status(404)
+            .create_async()
+            .await;
+        let _sidecars = server
+            .mock("GET", mockito::Matcher::Regex(r"sum$".to_string()))
+            .with_status(404)
+            .create_async()
             .await;
 
-        assert!(result.is_err());
+        let manager = SnapshotManager::new();
+        let snapshots = manager
+            .list_snapshots(&SnapshotSource::builtin(&server.url()), "full")
+            .await
+            .unwrap();
+
+        assert_eq!(snapshots.len(), 1);
+        assert_eq!(snapshots[0].date, "20260109");
+        assert_eq!(snapshots[0].size_bytes, 1024);
+        assert!(snapshots[0].checksum.is_none());
+    }
+
+    /// 在服务器上模拟一份可探测的快照
+    async fn mock_snapshot(server: &mut mockito::ServerGuard, date: &str) -> Vec<mockito::Mock> {
+        let path = format!("/backup{}/LiteFullNode_output-directory.tgz", date);
+        vec![
+            server
+                .mock("HEAD", "/")
+                .with_status(200)
+                .create_async()
+                .await,
+            server
+                .mock("HEAD", path.as_str())
+                .with_status(200)
+                .with_header("content-length", "2048")
+                .create_async()
+                .await,
+            server
+                .mock("GET", path.as_str())
+                .match_header("range", mockito::Matcher::Any)
+                .with_status(206)
+                .with_body(vec![0u8; 1024])
+                .create_async()
+                .await,
+        ]
+    }
+
+    fn manager_for(servers: &[&mockito::ServerGuard]) -> SnapshotManager {
+        SnapshotManager::from_config(&SnapshotConfig {
+            sources: servers
+                .iter()
+                .map(|server| SnapshotSource::builtin(&server.url()))
+                .collect(),
+        })
+    }
+
+    #[tokio::test]
+    async fn test_select_server_excludes_stale_servers() {
+        let today = chrono::Utc::now().format("%Y%m%d").to_string();
+        let yesterday = (chrono::Utc::now() - chrono::Duration::days(1))
+            .format("%Y%m%d")
+            .to_string();
+
+        let mut fresh = mockito::Server::new_async().await;
+        let mut stale = mockito::Server::new_async().await;
+        let _fresh = mock_snapshot(&mut fresh, &today).await;
+        let _stale = mock_snapshot(&mut stale, &yesterday).await;
+
+        let manager = manager_for(&[&stale, &fresh]);
+        let servers = manager.rank_servers("lite", None).await.unwrap();
+        assert_eq!(servers[0].url(), fresh.url());
+        assert!(servers[0].throughput.is_some());
+        assert!(!servers[1].has_snapshot(Some(&today)));
+
+        let table = server_table(&servers);
+        assert!(table.contains("快照过期"));
+
+        let selection = SnapshotManager::select_server(servers, "lite", None).unwrap();
+        assert_eq!(selection.primary().url(), fresh.url());
+        assert_eq!(selection.servers.len(), 1);
+        assert_eq!(selection.metadata.date, today);
+        assert_eq!(selection.metadata.size_bytes, 2048);
+    }
+
+    #[tokio::test]
+    async fn test_select_server_with_date() {
+        let mut missing = mockito::Server::new_async().await;
+        let mut present = mockito::Server::new_async().await;
+        let _missing = missing
+            .mock("HEAD", "/")
+            .with_status(200)
+            .create_async()
+            .await;
+        let _present = mock_snapshot(&mut present, "20260109").await;
+
+        let manager = manager_for(&[&missing, &present]);
+        let servers = manager
+            .rank_servers("lite", Some("20260109"))
+            .await
+            .unwrap();
+        let selection = SnapshotManager::select_server(servers, "lite", Some("20260109")).unwrap();
+        assert_eq!(selection.primary().url(), present.url());
+        assert_eq!(selection.metadata.date, "20260109");
+
+        let servers = manager
+            .rank_servers("lite", Some("20260108"))
+            .await
+            .unwrap();
+        assert!(SnapshotManager::select_server(servers, "lite", Some("20260108")).is_err());
+        assert!(manager.rank_servers("lite", Some("bad")).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_at_custom_source() {
+        let mut server = mockito::Server::new_async().await;
+        let path = "/tron/20260109/LiteFullNode_output-directory.tgz";
+
+        let head = server
+            .mock("HEAD", path)
+            .match_header("authorization", "Bearer secret")
+            .with_status(200)
+            .create_async()
+            .await;
+        let _sidecars = server
+            .mock("GET", mockito::Matcher::Regex(r"sum$".to_string()))
+            .with_status(404)
+            .create_async()
+            .await;
+
+        let source = SnapshotSource {
+            path: "tron/{date}/{prefix}.tgz".to_string(),
+            headers: [("Authorization".to_string(), "Bearer secret".to_string())].into(),
+            ..SnapshotSource::builtin(&server.url())
+        };
+        let manager = SnapshotManager::new();
+        let metadata = manager
+            .snapshot_at(&source, "lite", "20260109")
+            .await
+            .unwrap()
+            .unwrap();
+
+        head.expect_at_least(1).assert_async().await;
+        assert!(metadata.download_url.ends_with(path));
     }
 }
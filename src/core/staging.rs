@@ -0,0 +1,248 @@
+use crate::error::{Result, TronCtlError};
+use crate::models::snapshot_info::SnapshotMetadata;
+use crate::utils::fs;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+/// 暂存目录名前缀，位于数据目录下，保证与最终位置在同一文件系统
+const STAGING_PREFIX: &str = ".staging-";
+
+/// 解压并校验完成后写入的标记文件
+const COMPLETE_MARKER: &str = ".complete";
+
+/// 移动暂存内容时，被替换的旧数据先改名为该前缀，新数据就位后再删除
+const REPLACED_PREFIX: &str = ".replaced-";
+
+/// 快照解压的暂存目录：解压与校验完成后写入完成标记，再整体移动到数据目录
+pub struct SnapshotStaging {
+    data_dir: PathBuf,
+    dir: PathBuf,
+}
+
+impl SnapshotStaging {
+    /// 按快照日期与类型（lite/full）命名，不同类型的快照不会共用暂存目录
+    pub fn new(data_dir: &Path, date: &str, kind: &str) -> Self {
+        Self::named(data_dir, &format!("{}-{}", date, kind))
+    }
+
+    fn named(data_dir: &Path, name: &str) -> Self {
+        Self {
+            data_dir: data_dir.to_path_buf(),
+            dir: data_dir.join(format!("{}{}", STAGING_PREFIX, name)),
+        }
+    }
+
+    /// 数据目录中上次运行遗留的暂存目录
+    pub fn leftovers(data_dir: &Path) -> Vec<Self> {
+        let Ok(entries) = std::fs::read_dir(data_dir) else {
+            return Vec::new();
+        };
+
+        let mut leftovers: Vec<Self> = entries
+            .flatten()
+            .filter(|entry| entry.path().is_dir())
+            .filter_map(|entry| {
+                let name = entry.file_name().to_string_lossy().to_string();
+                let name = name.strip_prefix(STAGING_PREFIX)?;
+                Some(Self::named(data_dir, name))
+            })
+            .collect();
+        leftovers.sort_by(|a, b| a.dir.cmp(&b.dir));
+        leftovers
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// 暂存目录是否属于指定类型的快照；指定日期时日期也须一致
+    pub fn is_for(&self, kind: &str, date: Option<&str>) -> bool {
+        let name = self.dir.file_name().unwrap_or_default().to_string_lossy();
+        let Some((staged_date, staged_kind)) = name
+            .strip_prefix(STAGING_PREFIX)
+            .and_then(|name| name.rsplit_once('-'))
+        else {
+            return false;
+        };
+        staged_kind == kind && date.is_none_or(|date| date == staged_date)
+    }
+
+    /// 是否已完成解压与校验
+    pub fn is_complete(&self) -> bool {
+        self.dir.join(COMPLETE_MARKER).is_file()
+    }
+
+    /// 创建暂存目录并清理其他快照遗留的暂存目录；同一快照的暂存目录保留以便续传
+    pub async fn prepare(&self) -> Result<()> {
+        for leftover in Self::leftovers(&self.data_dir) {
+            if leftover.dir != self.dir {
+                info!("清理过期的暂存目录: {:?}", leftover.dir);
+                tokio::fs::remove_dir_all(&leftover.dir).await?;
+            }
+        }
+
+        if self.dir.exists() {
+            info!("继续上次未完成的解压: {:?}", self.dir);
+        }
+        fs::ensure_dir_exists(&self.dir).await
+    }
+
+    /// 写入完成标记，记录快照元数据
+    pub async fn mark_complete(&self, metadata: &SnapshotMetadata) -> Result<()> {
+        let content = serde_json::to_string_pretty(metadata)
+            .map_err(|e| TronCtlError::Other(anyhow::anyhow!("序列化快照元数据失败: {}", e)))?;
+        tokio::fs::write(self.dir.join(COMPLETE_MARKER), content).await?;
+        Ok(())
+    }
+
+    /// 将已完成的暂存内容移动到数据目录，替换同名的旧数据，最后删除暂存目录
+    ///
+    /// 旧数据先改名到一旁，新数据就位后才删除，任何时刻中断都不会同时丢失新旧数据；
+    /// 上次中断遗留的旧数据在下次移动时清理
+    pub async fn commit(&self) -> Result<()> {
+        if !self.is_complete() {
+            return Err(TronCtlError::ConfigError(format!(
+                "暂存目录未完成解压，拒绝移动: {:?}",
+                self.dir
+            )));
+        }
+
+        let mut entries = tokio::fs::read_dir(&self.dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let name = entry.file_name();
+            if name.to_string_lossy().starts_with('.') {
+                continue;
+            }
+
+            let target = self.data_dir.join(&name);
+            let mut aside_name = std::ffi::OsString::from(REPLACED_PREFIX);
+            aside_name.push(&name);
+            let aside = self.data_dir.join(aside_name);
+
+            if aside.exists() {
+                warn!("清理上次中断时保留的旧数据: {:?}", aside);
+                remove_path(&aside).await?;
+            }
+            if target.exists() {
+                warn!("替换已存在的数据: {:?}", target);
+                tokio::fs::rename(&target, &aside).await?;
+            }
+            tokio::fs::rename(entry.path(), &target).await?;
+            if aside.exists() {
+                remove_path(&aside).await?;
+            }
+        }
+
+        tokio::fs::remove_dir_all(&self.dir).await?;
+        info!("快照已移动到数据目录: {:?}", self.data_dir);
+        Ok(())
+    }
+}
+
+async fn remove_path(path: &Path) -> Result<()> {
+    if path.is_dir() {
+        tokio::fs::remove_dir_all(path).await?;
+    } else {
+        tokio::fs::remove_file(path).await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn metadata() -> SnapshotMetadata {
+        SnapshotMetadata {
+            date: "20260109".to_string(),
+            size_gb: 53,
+            size_bytes: 0,
+            checksum: None,
+            download_url: "http://example.com/snapshot.tgz".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_commit_moves_completed_staging() {
+        let temp_dir = TempDir::new().unwrap();
+        let data_dir = temp_dir.path();
+        let old = data_dir.join("output-directory/database");
+        std::fs::create_dir_all(&old).unwrap();
+        std::fs::write(old.join("old.sst"), b"old").unwrap();
+
+        let staging = SnapshotStaging::new(data_dir, "20260109", "lite");
+        staging.prepare().await.unwrap();
+        let new = staging.dir().join("output-directory/database");
+        std::fs::create_dir_all(&new).unwrap();
+        std::fs::write(new.join("new.sst"), b"new").unwrap();
+
+        // 未写入完成标记时不允许移动
+        assert!(staging.commit().await.is_err());
+
+        staging.mark_complete(&metadata()).await.unwrap();
+        assert!(staging.is_complete());
+        staging.commit().await.unwrap();
+
+        assert!(!staging.dir().exists());
+        assert!(!old.join("old.sst").exists());
+        assert_eq!(std::fs::read(old.join("new.sst")).unwrap(), b"new");
+        assert!(!data_dir.join(".replaced-output-directory").exists());
+    }
+
+    #[tokio::test]
+    async fn test_commit_after_interrupted_swap() {
+        let temp_dir = TempDir::new().unwrap();
+        let data_dir = temp_dir.path();
+        // 上次移动在旧数据改名后中断：output-directory 不存在，旧数据在一旁
+        let aside = data_dir.join(".replaced-output-directory/database");
+        std::fs::create_dir_all(&aside).unwrap();
+        std::fs::write(aside.join("old.sst"), b"old").unwrap();
+
+        let staging = SnapshotStaging::new(data_dir, "20260109", "lite");
+        staging.prepare().await.unwrap();
+        let new = staging.dir().join("output-directory/database");
+        std::fs::create_dir_all(&new).unwrap();
+        std::fs::write(new.join("new.sst"), b"new").unwrap();
+        staging.mark_complete(&metadata()).await.unwrap();
+        staging.commit().await.unwrap();
+
+        let database = data_dir.join("output-directory/database");
+        assert_eq!(std::fs::read(database.join("new.sst")).unwrap(), b"new");
+        assert!(!data_dir.join(".replaced-output-directory").exists());
+    }
+
+    #[test]
+    fn test_is_for() {
+        let data_dir = Path::new("/var/lib/tronctl/data");
+        let staging = SnapshotStaging::new(data_dir, "20260109", "lite");
+
+        assert!(staging.is_for("lite", None));
+        assert!(staging.is_for("lite", Some("20260109")));
+        assert!(!staging.is_for("lite", Some("20260101")));
+        assert!(!staging.is_for("full", None));
+    }
+
+    #[tokio::test]
+    async fn test_prepare_cleans_other_leftovers() {
+        let temp_dir = TempDir::new().unwrap();
+        let data_dir = temp_dir.path();
+        std::fs::create_dir_all(data_dir.join(".staging-20260101-lite/output-directory")).unwrap();
+        std::fs::create_dir_all(data_dir.join(".staging-20260109-lite/output-directory")).unwrap();
+        std::fs::create_dir_all(data_dir.join(".staging-20260109-full/output-directory")).unwrap();
+        std::fs::create_dir_all(data_dir.join("output-directory")).unwrap();
+
+        let leftovers = SnapshotStaging::leftovers(data_dir);
+        assert_eq!(leftovers.len(), 3);
+        assert!(leftovers.iter().all(|staging| !staging.is_complete()));
+
+        let staging = SnapshotStaging::new(data_dir, "20260109", "lite");
+        staging.prepare().await.unwrap();
+
+        assert!(!data_dir.join(".staging-20260101-lite").exists());
+        // 同一日期的另一类型快照不会混入
+        assert!(!data_dir.join(".staging-20260109-full").exists());
+        // 同一快照的暂存内容保留以便续传
+        assert!(staging.dir().join("output-directory").exists());
+    }
+}
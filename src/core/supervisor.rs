@@ -0,0 +1,108 @@
+use crate::constants::SYSTEMD_SERVICE;
+use crate::core::ProcessManager;
+use crate::error::{Result, TronCtlError};
+use crate::models::TronCtlConfig;
+use std::process::Command;
+use tracing::info;
+
+/// 节点的托管方式：systemd 服务或 `tronctl start` 启动的进程
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Supervisor {
+    Systemd,
+    Tronctl,
+}
+
+impl Supervisor {
+    /// systemd 服务处于 active 时视为由 systemd 托管
+    pub fn detect() -> Self {
+        let active = Command::new("systemctl")
+            .args(["is-active", "--quiet", SYSTEMD_SERVICE])
+            .status()
+            .map(|s| s.success())
+            .unwrap_or(false);
+
+        if active { Self::Systemd } else { Self::Tronctl }
+    }
+
+    /// 当前运行中的节点 PID
+    pub fn running_pid(&self) -> Result<Option<i32>> {
+        match self {
+            Self::Systemd => Ok(systemd_main_pid()),
+            Self::Tronctl => Ok(
+                ProcessManager::read_pid()?.filter(|&pid| ProcessManager::is_process_alive(pid))
+            ),
+        }
+    }
+
+    /// 优雅停止节点
+    pub fn stop(&self) -> Result<()> {
+        match self {
+            Self::Systemd => systemctl("stop"),
+            Self::Tronctl => ProcessManager::stop(false),
+        }
+    }
+
+    /// 启动节点并返回 PID
+    pub async fn start(&self, config: &TronCtlConfig) -> Result<i32> {
+        match self {
+            Self::Systemd => {
+                systemctl("start")?;
+                systemd_main_pid().ok_or_else(|| {
+                    TronCtlError::ProcessStartFailed(format!(
+                        "无法获取 {} 服务的 PID",
+                        SYSTEMD_SERVICE
+                    ))
+                })
+            }
+            Self::Tronctl => ProcessManager::start(config).await,
+        }
+    }
+}
+
+fn systemctl(action: &str) -> Result<()> {
+    info!("systemctl {} {}", action, SYSTEMD_SERVICE);
+
+    let status = Command::new("systemctl")
+        .args([action, SYSTEMD_SERVICE])
+        .status()?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(TronCtlError::ProcessStartFailed(format!(
+            "systemctl {} {} 失败: {}",
+            action, SYSTEMD_SERVICE, status
+        )))
+    }
+}
+
+fn systemd_main_pid() -> Option<i32> {
+    let output = Command::new("systemctl")
+        .args(["show", "-p", "MainPID", "--value", SYSTEMD_SERVICE])
+        .output()
+        .ok()?;
+
+    parse_main_pid(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// systemd 在服务未运行时返回 MainPID=0
+fn parse_main_pid(output: &str) -> Option<i32> {
+    output.trim().parse().ok().filter(|&pid| pid > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_main_pid() {
+        assert_eq!(parse_main_pid("12345\n"), Some(12345));
+        assert_eq!(parse_main_pid("0\n"), None);
+        assert_eq!(parse_main_pid(""), None);
+    }
+
+    #[test]
+    fn test_detect_is_consistent() {
+        assert_eq!(Supervisor::detect(), Supervisor::detect());
+    }
+}
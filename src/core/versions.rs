@@ -0,0 +1,487 @@
+use crate::constants::{FULLNODE_ASSET, FULLNODE_MAIN_CLASS, VERSIONS_DIR};
+use crate::core::{ConfigTemplate, Downloader, ReleaseChecker};
+use crate::error::{Result, TronCtlError};
+use crate::models::checksum::Checksum;
+use crate::models::jar_version::JarVersion;
+use crate::utils::{archive, fs, hash};
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+/// 现有 FullNode.jar 为普通文件（来源未知）时纳入版本目录使用的标签
+pub const LEGACY_TAG: &str = "previous";
+
+/// 并列存放多个 `FullNode-<tag>.jar` 及其元数据的版本目录
+pub struct VersionStore {
+    dir: PathBuf,
+}
+
+impl VersionStore {
+    pub fn new() -> Self {
+        Self::with_dir(Path::new(VERSIONS_DIR))
+    }
+
+    pub fn with_dir(dir: &Path) -> Self {
+        Self {
+            dir: dir.to_path_buf(),
+        }
+    }
+
+    pub fn jar_path(&self, tag: &str) -> Result<PathBuf> {
+        self.version_file(tag, "jar")
+    }
+
+    fn metadata_path(&self, tag: &str) -> Result<PathBuf> {
+        self.version_file(tag, "json")
+    }
+
+    /// 指定版本默认节点配置的缓存路径
+    pub fn template_path(&self, tag: &str) -> Result<PathBuf> {
+        self.version_file(tag, "conf")
+    }
+
+    /// 版本目录中 `FullNode-<tag>.<extension>` 的路径；标签来自命令行或 GitHub，先校验再拼接
+    fn version_file(&self, tag: &str, extension: &str) -> Result<PathBuf> {
+        validate_tag(tag)?;
+        Ok(self.dir.join(format!("FullNode-{}.{}", tag, extension)))
+    }
+
+    /// 指定版本的默认节点配置，首次读取后缓存在版本目录中
+    pub async fn template(&self, tag: &str, source: &ConfigTemplate) -> Result<String> {
+        let cached = self.template_path(tag)?;
+        if cached.exists() {
+            return Ok(tokio::fs::read_to_string(&cached).await?);
+        }
+
+        let content = source.load(&self.jar_path(tag)?, tag).await?;
+        fs::ensure_dir_exists(&self.dir).await?;
+        tokio::fs::write(&cached, &content).await?;
+        Ok(content)
+    }
+
+    /// 读取指定版本的元数据
+    pub fn get(&self, tag: &str) -> Result<Option<JarVersion>> {
+        let path = self.metadata_path(tag)?;
+        if !path.exists() || !self.jar_path(tag)?.exists() {
+            return Ok(None);
+        }
+
+        Ok(Some(serde_json::from_str(&std::fs::read_to_string(path)?)?))
+    }
+
+    /// 列出所有版本，按下载时间从旧到新排序
+    pub fn list(&self) -> Result<Vec<JarVersion>> {
+        if !self.dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut versions = Vec::new();
+        for entry in std::fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if let Some(tag) = tag_from_jar(&path)
+                && let Some(version) = self.get(&tag)?
+            {
+                versions.push(version);
+            }
+        }
+
+        versions.sort_by(|a, b| {
+            a.downloaded_at
+                .cmp(&b.downloaded_at)
+                .then_with(|| a.tag.cmp(&b.tag))
+        });
+        Ok(versions)
+    }
+
+    /// 下载指定版本到版本目录（已存在时直接返回），校验后记录下载时间与 SHA-256；
+    /// `checksum` 为固定的校验值，未提供时使用 Release 发布的 SHA-256
+    pub async fn install(
+        &self,
+        downloader: &Downloader,
+        releases: &ReleaseChecker,
+        tag: &str,
+        checksum: Option<&Checksum>,
+    ) -> Result<JarVersion> {
+        if let Some(version) = self.get(tag)? {
+            if let Some(expected) = checksum {
+                hash::verify_file(&self.jar_path(tag)?, expected)?;
+            }
+            info!("版本 {} 已存在，跳过下载", tag);
+            return Ok(version);
+        }
+
+        fs::ensure_dir_exists(&self.dir).await?;
+        let expected = expected_checksum(releases, tag, checksum).await?;
+
+        // 下载完成后再改名，避免残留不完整的 jar；网络中断时保留以便续传
+        let jar = self.jar_path(tag)?;
+        let partial = jar.with_extension("jar.part");
+        let verified = downloader
+            .download_fullnode(releases, Some(tag.to_string()), &partial, expected.as_ref())
+            .await
+            .and_then(|()| archive::verify_jar(&partial, FULLNODE_MAIN_CLASS));
+        if matches!(
+            verified,
+            Err(TronCtlError::ChecksumMismatch { .. } | TronCtlError::InvalidJar(_))
+        ) {
+            tokio::fs::remove_file(&partial).await.ok();
+        }
+        verified?;
+        tokio::fs::rename(&partial, &jar).await?;
+
+        self.record(tag)
+    }
+
+    /// 为版本目录中已存在的 jar 写入元数据
+    pub fn record(&self, tag: &str) -> Result<JarVersion> {
+        let jar = self.jar_path(tag)?;
+        let version = JarVersion {
+            tag: tag.to_string(),
+            downloaded_at: chrono::Utc::now().timestamp(),
+            sha256: hash::sha256_file(&jar)?,
+            size: std::fs::metadata(&jar)?.len(),
+        };
+
+        std::fs::write(
+            self.metadata_path(tag)?,
+            serde_json::to_string_pretty(&version)?,
+        )?;
+        Ok(version)
+    }
+
+    /// 当前 FullNode.jar 链接指向的版本标签
+    pub fn active(&self, jar_link: &Path) -> Option<String> {
+        let target = std::fs::read_link(jar_link).ok()?;
+        tag_from_jar(&target)
+    }
+
+    /// 将 FullNode.jar 原子地指向指定版本
+    pub fn activate(&self, tag: &str, jar_link: &Path) -> Result<()> {
+        let jar = self.jar_path(tag)?;
+        if !jar.exists() {
+            return Err(TronCtlError::ConfigError(format!(
+                "版本 {} 不存在，请先运行 'tronctl upgrade --version {}'",
+                tag, tag
+            )));
+        }
+
+        fs::atomic_symlink(&jar, jar_link)?;
+        info!("{:?} -> {:?}", jar_link, jar);
+        Ok(())
+    }
+
+    /// 返回当前生效的版本；FullNode.jar 为普通文件时先纳入版本目录并替换为符号链接
+    pub fn adopt(&self, jar_link: &Path) -> Result<Option<String>> {
+        let Ok(metadata) = jar_link.symlink_metadata() else {
+            return Ok(None);
+        };
+
+        if metadata.file_type().is_symlink() {
+            return Ok(self.active(jar_link));
+        }
+
+        std::fs::create_dir_all(&self.dir)?;
+        let legacy = self.jar_path(LEGACY_TAG)?;
+        info!("将现有 {:?} 保存为 {:?}", jar_link, legacy);
+        std::fs::rename(jar_link, &legacy)?;
+        self.record(LEGACY_TAG)?;
+        self.activate(LEGACY_TAG, jar_link)?;
+
+        Ok(Some(LEGACY_TAG.to_string()))
+    }
+
+    /// 保留最近下载的 `keep` 个版本（始终保留当前版本），返回删除的标签
+    pub fn prune(&self, keep: usize, active: Option<&str>) -> Result<Vec<String>> {
+        let mut versions = self.list()?;
+        versions.reverse();
+
+        let mut removed = Vec::new();
+        for version in versions.into_iter().skip(keep) {
+            if Some(version.tag.as_str()) == active {
+                continue;
+            }
+
+            std::fs::remove_file(self.jar_path(&version.tag)?)?;
+            std::fs::remove_file(self.metadata_path(&version.tag)?)?;
+            let template = self.template_path(&version.tag)?;
+            if template.exists() {
+                std::fs::remove_file(template)?;
+            }
+            removed.push(version.tag);
+        }
+
+        Ok(removed)
+    }
+}
+
+impl Default for VersionStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 下载 jar 时使用的校验值：固定值优先，其次为 Release 发布的 SHA-256
+async fn expected_checksum(
+    releases: &ReleaseChecker,
+    tag: &str,
+    checksum: Option<&Checksum>,
+) -> Result<Option<Checksum>> {
+    let expected = match checksum {
+        Some(sum) => Some((sum.clone(), "--checksum".to_string())),
+        None => releases.published_sha256(tag, FULLNODE_ASSET).await?,
+    };
+
+    match expected {
+        Some((sum, source)) => {
+            info!("使用 {} 校验 (来源: {})", sum.algorithm.name(), source);
+            Ok(Some(sum))
+        }
+        None => {
+            warn!("{} 未发布 SHA-256，跳过校验（可通过 --checksum 指定）", tag);
+            Ok(None)
+        }
+    }
+}
+
+/// 拒绝可能逃出版本目录的标签：空值、路径分隔符、`..` 与控制字符
+fn validate_tag(tag: &str) -> Result<()> {
+    if tag.is_empty()
+        || tag.contains(['/', '\\'])
+        || tag.contains("..")
+        || tag.chars().any(char::is_control)
+    {
+        return Err(TronCtlError::ConfigError(format!(
+            "无效的版本标签: {:?}",
+            tag
+        )));
+    }
+    Ok(())
+}
+
+/// 从 `FullNode-<tag>.jar` 文件名解析标签
+fn tag_from_jar(path: &Path) -> Option<String> {
+    path.file_name()?
+        .to_str()?
+        .strip_prefix("FullNode-")?
+        .strip_suffix(".jar")
+        .filter(|tag| validate_tag(tag).is_ok())
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn store_with(tags: &[(&str, i64)]) -> (TempDir, VersionStore) {
+        let temp_dir = TempDir::new().unwrap();
+        let store = VersionStore::with_dir(&temp_dir.path().join("versions"));
+        std::fs::create_dir_all(&store.dir).unwrap();
+
+        for (tag, at) in tags {
+            std::fs::write(store.jar_path(tag).unwrap(), tag.as_bytes()).unwrap();
+            let mut version = store.record(tag).unwrap();
+            version.downloaded_at = *at;
+            std::fs::write(
+                store.metadata_path(tag).unwrap(),
+                serde_json::to_string(&version).unwrap(),
+            )
+            .unwrap();
+        }
+
+        (temp_dir, store)
+    }
+
+    fn jar_bytes(entry: &str) -> Vec<u8> {
+        let mut jar = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        jar.start_file(entry, zip::write::SimpleFileOptions::default())
+            .unwrap();
+        jar.finish().unwrap().into_inner()
+    }
+
+    async fn mock_release(server: &mut mockito::ServerGuard, tag: &str, jar: &[u8], sha256: &str) {
+        let path = format!(
+            "/tronprotocol/java-tron/releases/download/{}/FullNode.jar",
+            tag
+        );
+        for method in ["HEAD", "GET"] {
+            server
+                .mock(method, path.as_str())
+                .with_body(jar)
+                .create_async()
+                .await;
+        }
+        server
+            .mock("GET", format!("/releases/tags/{}", tag).as_str())
+            .with_body(format!(
+                r#"{{"tag_name": "{}", "assets": [{{"name": "FullNode.jar", "digest": "sha256:{}"}}]}}"#,
+                tag, sha256
+            ))
+            .create_async()
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_install_verifies_jar() {
+        let mut server = mockito::Server::new_async().await;
+        let valid = jar_bytes(FULLNODE_MAIN_CLASS);
+        let not_fullnode = jar_bytes("com/example/Main.class");
+
+        let temp_dir = TempDir::new().unwrap();
+        let file = temp_dir.path().join("jar");
+        std::fs::write(&file, &valid).unwrap();
+        let valid_sum = hash::sha256_file(&file).unwrap();
+        std::fs::write(&file, &not_fullnode).unwrap();
+        let other_sum = hash::sha256_file(&file).unwrap();
+
+        let pinned = Checksum::parse(&valid_sum).unwrap();
+        let other = Checksum::parse(&other_sum).unwrap();
+        mock_release(&mut server, "v1", &valid, &valid_sum).await;
+        mock_release(&mut server, "v2", &valid, &"0".repeat(64)).await;
+        mock_release(&mut server, "v3", &not_fullnode, &other_sum).await;
+
+        let store = VersionStore::with_dir(&temp_dir.path().join("versions"));
+        let releases =
+            ReleaseChecker::with_endpoints(&format!("{}/releases", server.url()), &server.url());
+        let downloader = Downloader::new();
+
+        let version = store
+            .install(&downloader, &releases, "v1", None)
+            .await
+            .unwrap();
+        assert_eq!(version.sha256, valid_sum);
+
+        // 已存在的版本与固定校验值不一致
+        assert!(matches!(
+            store
+                .install(&downloader, &releases, "v1", Some(&other))
+                .await,
+            Err(TronCtlError::ChecksumMismatch { .. })
+        ));
+
+        // 发布的校验值不一致
+        assert!(matches!(
+            store.install(&downloader, &releases, "v2", None).await,
+            Err(TronCtlError::ChecksumMismatch { .. })
+        ));
+        // 固定校验值优先于发布的校验值
+        assert!(
+            store
+                .install(&downloader, &releases, "v2", Some(&pinned))
+                .await
+                .is_ok()
+        );
+
+        // 校验值一致但不是 FullNode
+        assert!(matches!(
+            store.install(&downloader, &releases, "v3", None).await,
+            Err(TronCtlError::InvalidJar(_))
+        ));
+        assert!(!store.jar_path("v3").unwrap().exists());
+        assert!(
+            !store
+                .jar_path("v3")
+                .unwrap()
+                .with_extension("jar.part")
+                .exists()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_template_cached_from_jar() {
+        let (_temp, store) = store_with(&[]);
+        let mut jar =
+            zip::ZipWriter::new(std::fs::File::create(store.jar_path("v1").unwrap()).unwrap());
+        jar.start_file("config.conf", zip::write::SimpleFileOptions::default())
+            .unwrap();
+        std::io::Write::write_all(&mut jar, b"net { type = mainnet }").unwrap();
+        jar.finish().unwrap();
+
+        // 不可达的地址：必须从 jar 读取
+        let source = ConfigTemplate::with_raw_base("http://127.0.0.1:1");
+        let content = store.template("v1", &source).await.unwrap();
+        assert!(content.contains("mainnet"));
+        assert!(store.template_path("v1").unwrap().exists());
+
+        std::fs::remove_file(store.jar_path("v1").unwrap()).unwrap();
+        assert_eq!(store.template("v1", &source).await.unwrap(), content);
+    }
+
+    #[test]
+    fn test_tag_from_jar() {
+        assert_eq!(
+            tag_from_jar(Path::new("/v/FullNode-GreatVoyage-v4.8.0.jar")).as_deref(),
+            Some("GreatVoyage-v4.8.0")
+        );
+        assert_eq!(tag_from_jar(Path::new("/v/FullNode.jar")), None);
+        assert_eq!(tag_from_jar(Path::new("/v/FullNode-x.json")), None);
+        assert_eq!(tag_from_jar(Path::new("/v/FullNode-...jar")), None);
+    }
+
+    #[test]
+    fn test_rejects_unsafe_tags() {
+        let (temp, store) = store_with(&[("v1", 100)]);
+        let jar_link = temp.path().join("FullNode.jar");
+
+        for tag in ["", "../etc/passwd", "a/b", "a\\b", "..", "v1\n", "v\u{7f}"] {
+            assert!(
+                matches!(store.jar_path(tag), Err(TronCtlError::ConfigError(_))),
+                "{:?}",
+                tag
+            );
+            assert!(store.get(tag).is_err());
+            assert!(store.activate(tag, &jar_link).is_err());
+        }
+        assert!(store.jar_path("GreatVoyage-v4.8.0").is_ok());
+        assert!(!jar_link.exists());
+    }
+
+    #[test]
+    fn test_list_sorted_by_download_time() {
+        let (_temp, store) = store_with(&[("v3", 300), ("v1", 100), ("v2", 200)]);
+        let tags: Vec<String> = store.list().unwrap().into_iter().map(|v| v.tag).collect();
+        assert_eq!(tags, vec!["v1", "v2", "v3"]);
+
+        let v1 = store.get("v1").unwrap().unwrap();
+        assert_eq!(v1.size, 2);
+        assert_eq!(v1.sha256.len(), 64);
+    }
+
+    #[test]
+    fn test_activate_and_active() {
+        let (temp, store) = store_with(&[("v1", 100), ("v2", 200)]);
+        let link = temp.path().join("FullNode.jar");
+
+        store.activate("v1", &link).unwrap();
+        assert_eq!(store.active(&link).as_deref(), Some("v1"));
+
+        store.activate("v2", &link).unwrap();
+        assert_eq!(store.active(&link).as_deref(), Some("v2"));
+        assert_eq!(std::fs::read(&link).unwrap(), b"v2");
+
+        assert!(store.activate("missing", &link).is_err());
+    }
+
+    #[test]
+    fn test_adopt_regular_jar() {
+        let (temp, store) = store_with(&[]);
+        let link = temp.path().join("FullNode.jar");
+        std::fs::write(&link, b"old").unwrap();
+
+        assert_eq!(store.adopt(&link).unwrap().as_deref(), Some(LEGACY_TAG));
+        assert!(link.symlink_metadata().unwrap().file_type().is_symlink());
+        assert_eq!(std::fs::read(&link).unwrap(), b"old");
+        assert!(store.get(LEGACY_TAG).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_prune_keeps_recent_and_active() {
+        let (_temp, store) = store_with(&[("v1", 100), ("v2", 200), ("v3", 300), ("v4", 400)]);
+
+        let removed = store.prune(2, Some("v1")).unwrap();
+        assert_eq!(removed, vec!["v2"]);
+
+        let tags: Vec<String> = store.list().unwrap().into_iter().map(|v| v.tag).collect();
+        assert_eq!(tags, vec!["v1", "v3", "v4"]);
+        assert!(!store.jar_path("v2").unwrap().exists());
+    }
+}
@@ -12,9 +12,12 @@ pub enum TronCtlError {
     #[error("内存不足: 推荐 {recommended}GB, 当前 {current}GB")]
     InsufficientMemory { recommended: u64, current: u64 },
 
-    #[allow(dead_code)]
-    #[error("磁盘空间不足: 推荐 {recommended}GB, 当前 {current}GB")]
-    InsufficientDisk { recommended: u64, current: u64 },
+    #[error("磁盘空间不足: {mount_point} 需要 {required}GB, 可用 {available}GB")]
+    InsufficientDisk {
+        mount_point: String,
+        required: u64,
+        available: u64,
+    },
 
     #[allow(dead_code)]
     #[error("节点未初始化: 请先运行 'tronctl init'")]
@@ -26,18 +29,48 @@ pub enum TronCtlError {
     #[error("节点未运行")]
     NodeNotRunning,
 
+    #[error("端口被占用: {0}")]
+    PortInUse(String),
+
     #[error("下载失败: {0}")]
     DownloadFailed(String),
 
-    #[error("MD5 校验失败: 期望 {expected}, 实际 {actual}")]
-    Md5Mismatch { expected: String, actual: String },
+    #[error("{algorithm} 校验失败: 期望 {expected}, 实际 {actual}")]
+    ChecksumMismatch {
+        algorithm: String,
+        expected: String,
+        actual: String,
+    },
+
+    #[error("远程文件已变化: {0}")]
+    RemoteFileChanged(String),
+
+    #[error("无效的 jar: {0}")]
+    InvalidJar(String),
 
     #[error("进程启动失败: {0}")]
     ProcessStartFailed(String),
 
+    #[error("健康检查失败: {0}")]
+    HealthCheckFailed(String),
+
+    #[error("升级到 {version} 失败，已回滚到之前的版本: {reason}")]
+    UpgradeRolledBack { version: String, reason: String },
+
     #[error("RPC 调用失败: {0}")]
     RpcCallFailed(String),
 
+    #[error(
+        "GitHub API 请求次数已达上限 ({limit} 次/小时)，将于 {reset_at} 重置；可设置 GITHUB_TOKEN 环境变量或在 tronctl.toml 的 [github] 中配置镜像"
+    )]
+    GithubRateLimited { limit: u64, reset_at: String },
+
+    #[error("通知发送失败: {0}")]
+    NotificationFailed(String),
+
+    #[error("链分叉: 参考节点 {endpoint} 在高度 {height} 处与本地区块哈希不一致")]
+    ChainForked { endpoint: String, height: u64 },
+
     #[error("配置文件错误: {0}")]
     ConfigError(String),
 
@@ -98,6 +131,85 @@ mod tests {
         assert!(err.to_string().contains("16"));
     }
 
+    #[test]
+    fn test_insufficient_disk_error() {
+        let err = TronCtlError::InsufficientDisk {
+            mount_point: "/data".to_string(),
+            required: 120,
+            available: 80,
+        };
+        let msg = err.to_string();
+        assert!(msg.contains("/data"));
+        assert!(msg.contains("120"));
+        assert!(msg.contains("80"));
+    }
+
+    #[test]
+    fn test_checksum_mismatch_error() {
+        let err = TronCtlError::ChecksumMismatch {
+            algorithm: "SHA-256".to_string(),
+            expected: "aa".to_string(),
+            actual: "bb".to_string(),
+        };
+        let msg = err.to_string();
+        assert!(msg.contains("SHA-256"));
+        assert!(msg.contains("aa"));
+        assert!(msg.contains("bb"));
+    }
+
+    #[test]
+    fn test_remote_file_changed_error() {
+        let err = TronCtlError::RemoteFileChanged("http://example.com/snapshot.tgz".to_string());
+        assert_eq!(
+            err.to_string(),
+            "远程文件已变化: http://example.com/snapshot.tgz"
+        );
+    }
+
+    #[test]
+    fn test_invalid_jar_error() {
+        let err = TronCtlError::InvalidJar("缺少 org/tron/program/FullNode.class".to_string());
+        assert!(err.to_string().contains("jar"));
+        assert!(err.to_string().contains("FullNode.class"));
+    }
+
+    #[test]
+    fn test_upgrade_rolled_back_error() {
+        let err = TronCtlError::UpgradeRolledBack {
+            version: "GreatVoyage-v4.8.0".to_string(),
+            reason: "进程已退出".to_string(),
+        };
+        let msg = err.to_string();
+        assert!(msg.contains("GreatVoyage-v4.8.0"));
+        assert!(msg.contains("回滚"));
+        assert!(msg.contains("进程已退出"));
+    }
+
+    #[test]
+    fn test_github_rate_limited_error() {
+        let err = TronCtlError::GithubRateLimited {
+            limit: 60,
+            reset_at: "2025-01-01 08:00:00".to_string(),
+        };
+        let msg = err.to_string();
+        assert!(msg.contains("60"));
+        assert!(msg.contains("2025-01-01 08:00:00"));
+        assert!(msg.contains("GITHUB_TOKEN"));
+    }
+
+    #[test]
+    fn test_notification_failed_error() {
+        let err = TronCtlError::NotificationFailed("HTTP 500".to_string());
+        assert!(err.to_string().contains("通知"));
+        assert!(err.to_string().contains("HTTP 500"));
+    }
+
+    #[test]
+    fn test_health_check_failed_error() {
+        let err = TronCtlError::HealthCheckFailed("RPC 未响应".to_string());
+        assert!(err.to_string().contains("RPC 未响应"));
+    }
+
     #[test]
     fn test_node_already_running_error() {
         let err = TronCtlError::NodeAlreadyRunning(12345);
@@ -106,20 +218,38 @@ mod tests {
 
     #[test]
     fn test_md5_mismatch_error() {
-        let err = TronCtlError::Md5Mismatch {
+        let err = TronCtlError::ChecksumMismatch {
+            algorithm: "MD5".to_string(),
             expected: "abc123".to_string(),
             actual: "def456".to_string(),
         };
+        assert!(err.to_string().starts_with("MD5 校验失败"));
         assert!(err.to_string().contains("abc123"));
         assert!(err.to_string().contains("def456"));
     }
 
+    #[test]
+    fn test_port_in_use_error() {
+        let err = TronCtlError::PortInUse("P2P 端口 18888/tcp".to_string());
+        assert!(err.to_string().contains("18888"));
+    }
+
     #[test]
     fn test_download_failed_error() {
         let err = TronCtlError::DownloadFailed("网络超时".to_string());
         assert!(err.to_string().contains("网络超时"));
     }
 
+    #[test]
+    fn test_chain_forked_error() {
+        let err = TronCtlError::ChainForked {
+            endpoint: "https://api.trongrid.io".to_string(),
+            height: 12345,
+        };
+        assert!(err.to_string().contains("api.trongrid.io"));
+        assert!(err.to_string().contains("12345"));
+    }
+
     #[test]
     fn test_config_error() {
         let err = TronCtlError::ConfigError("配置无效".to_string());
@@ -136,7 +266,7 @@ mod tests {
     #[test]
     fn test_result_type() {
         let ok: Result<i32> = Ok(42);
-        assert_eq!(ok.unwrap(), 42);
+        assert!(matches!(ok, Ok(42)));
 
         let err: Result<i32> = Err(TronCtlError::NodeNotRunning);
         assert!(err.is_err());
@@ -24,9 +24,22 @@ async fn main() -> anyhow::Result<()> {
     let result = match cli.command {
         cli::Commands::Init {
             snapshot,
+            snapshot_date,
             version,
+            checksum,
             skip_checks,
-        } => commands::init::execute(snapshot, version, skip_checks).await,
+            download,
+        } => {
+            commands::init::execute(
+                snapshot,
+                snapshot_date,
+                version,
+                checksum,
+                skip_checks,
+                download.into(),
+            )
+            .await
+        }
 
         cli::Commands::Start { daemon } => commands::start::execute(daemon).await,
 
@@ -37,13 +50,54 @@ async fn main() -> anyhow::Result<()> {
 
         cli::Commands::Restart { daemon } => commands::restart::execute(daemon).await,
 
+        cli::Commands::Upgrade {
+            version,
+            checksum,
+            timeout,
+            prerelease,
+            download,
+        } => {
+            commands::upgrade::execute(version, checksum, timeout, prerelease, download.into())
+                .await
+        }
+
         cli::Commands::Status { verbose } => commands::status::execute(verbose).await,
 
+        cli::Commands::Doctor { json } => commands::doctor::execute(json).await,
+
+        cli::Commands::Peers { verbose, record } => commands::peers::execute(verbose, record).await,
+
         cli::Commands::Logs { follow, lines } => commands::logs::execute(follow, lines).await,
 
         cli::Commands::Clean { yes } => commands::clean::execute(yes).await,
 
         cli::Commands::Systemd { force } => commands::systemd::execute(force).await,
+
+        cli::Commands::Java { action } => match action {
+            cli::JavaAction::List => commands::java::list().await,
+            cli::JavaAction::Use { path } => commands::java::use_java(path).await,
+            cli::JavaAction::Install { from, checksum } => {
+                commands::java::install(from, checksum).await
+            }
+        },
+
+        cli::Commands::Version { check } => commands::version::execute(check).await,
+
+        cli::Commands::Versions { action } => match action {
+            cli::VersionsAction::List => commands::versions::list().await,
+            cli::VersionsAction::Use { tag } => commands::versions::use_version(&tag).await,
+            cli::VersionsAction::Prune { keep } => commands::versions::prune(keep).await,
+        },
+
+        cli::Commands::Snapshot { action } => match action {
+            cli::SnapshotAction::List { snapshot_type } => {
+                commands::snapshot::list(snapshot_type).await
+            }
+        },
+
+        cli::Commands::VerifyChain { reference, samples } => {
+            commands::verify_chain::execute(reference, samples).await
+        }
     };
 
     if let Err(e) = result {
@@ -1,6 +1,13 @@
+pub mod chain_report;
+pub mod check_result;
+pub mod checksum;
 pub mod health_status;
+pub mod jar_version;
+pub mod java_info;
 pub mod node_config;
 pub mod node_state;
+pub mod peer_info;
+pub mod release;
 pub mod snapshot_info;
 
 pub use node_config::TronCtlConfig;
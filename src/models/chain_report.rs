@@ -0,0 +1,127 @@
+use serde::{Deserialize, Serialize};
+
+/// `/wallet/getblockbynum` 的响应（区块不存在时返回 `{}`）
+#[derive(Debug, Clone, Deserialize)]
+pub struct BlockIdResponse {
+    #[serde(rename = "blockID")]
+    pub block_id: Option<String>,
+}
+
+/// 单个高度上的区块哈希比对结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HeightComparison {
+    pub height: u64,
+    pub local_hash: String,
+    pub reference_hash: String,
+    pub matches: bool,
+}
+
+/// 本地链与参考节点的一致性结论
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ChainConsistency {
+    Consistent,
+    Behind { blocks: u64 },
+    Forked { first_mismatch: u64 },
+    Unreachable { reason: String },
+}
+
+/// 与单个参考节点的比对结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReferenceResult {
+    pub endpoint: String,
+    pub reference_head: Option<u64>,
+    pub comparisons: Vec<HeightComparison>,
+    pub status: ChainConsistency,
+}
+
+/// 链一致性检查报告
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChainReport {
+    pub local_head: u64,
+    /// 本地节点保存的最早区块，轻节点大于 1
+    pub local_tail: u64,
+    pub references: Vec<ReferenceResult>,
+}
+
+impl ChainReport {
+    /// 第一个判定本地链已分叉的参考节点及其首个不一致高度
+    pub fn first_fork(&self) -> Option<(&str, u64)> {
+        self.references.iter().find_map(|r| match r.status {
+            ChainConsistency::Forked { first_mismatch } => {
+                Some((r.endpoint.as_str(), first_mismatch))
+            }
+            _ => None,
+        })
+    }
+
+    /// 是否所有参考节点均不可达
+    pub fn all_unreachable(&self) -> bool {
+        self.references
+            .iter()
+            .all(|r| matches!(r.status, ChainConsistency::Unreachable { .. }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reference(status: ChainConsistency) -> ReferenceResult {
+        ReferenceResult {
+            endpoint: "http://ref.test".to_string(),
+            reference_head: Some(100),
+            comparisons: Vec::new(),
+            status,
+        }
+    }
+
+    #[test]
+    fn test_block_id_response_deserialization() {
+        let json = r#"{"blockID": "00000000000000641a2b", "block_header": {}}"#;
+        let resp: BlockIdResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(resp.block_id.as_deref(), Some("00000000000000641a2b"));
+    }
+
+    #[test]
+    fn test_block_id_response_missing_block() {
+        let resp: BlockIdResponse = serde_json::from_str("{}").unwrap();
+        assert!(resp.block_id.is_none());
+    }
+
+    #[test]
+    fn test_chain_report_forked() {
+        let report = ChainReport {
+            local_head: 100,
+            local_tail: 1,
+            references: vec![
+                reference(ChainConsistency::Consistent),
+                reference(ChainConsistency::Forked { first_mismatch: 42 }),
+            ],
+        };
+
+        assert_eq!(report.first_fork(), Some(("http://ref.test", 42)));
+        assert!(!report.all_unreachable());
+    }
+
+    #[test]
+    fn test_chain_report_all_unreachable() {
+        let report = ChainReport {
+            local_head: 100,
+            local_tail: 1,
+            references: vec![reference(ChainConsistency::Unreachable {
+                reason: "timeout".to_string(),
+            })],
+        };
+
+        assert!(report.first_fork().is_none());
+        assert!(report.all_unreachable());
+    }
+
+    #[test]
+    fn test_chain_consistency_serialization() {
+        let status = ChainConsistency::Behind { blocks: 12 };
+        let json = serde_json::to_string(&status).unwrap();
+        assert!(json.contains("Behind"));
+        assert!(json.contains("12"));
+    }
+}
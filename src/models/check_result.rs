@@ -0,0 +1,91 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckLevel {
+    Pass,
+    Warn,
+    Fail,
+}
+
+/// 单项主机/环境检查结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckResult {
+    pub name: String,
+    pub level: CheckLevel,
+    pub message: String,
+    /// 修复建议
+    pub hint: Option<String>,
+}
+
+impl CheckResult {
+    pub fn pass(name: &str, message: impl Into<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            level: CheckLevel::Pass,
+            message: message.into(),
+            hint: None,
+        }
+    }
+
+    pub fn warn(name: &str, message: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            level: CheckLevel::Warn,
+            message: message.into(),
+            hint: Some(hint.into()),
+        }
+    }
+
+    pub fn fail(name: &str, message: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            level: CheckLevel::Fail,
+            message: message.into(),
+            hint: Some(hint.into()),
+        }
+    }
+
+    /// 终端输出的状态标记
+    pub fn symbol(&self) -> &'static str {
+        match self.level {
+            CheckLevel::Pass => "✓",
+            CheckLevel::Warn => "!",
+            CheckLevel::Fail => "✗",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_result_constructors() {
+        let pass = CheckResult::pass("内存", "32GB");
+        assert_eq!(pass.level, CheckLevel::Pass);
+        assert!(pass.hint.is_none());
+
+        let warn = CheckResult::warn("swap", "已启用", "swapoff -a");
+        assert_eq!(warn.level, CheckLevel::Warn);
+        assert_eq!(warn.hint.as_deref(), Some("swapoff -a"));
+
+        let fail = CheckResult::fail("端口", "被占用", "停止占用进程");
+        assert_eq!(fail.level, CheckLevel::Fail);
+        assert_eq!(fail.symbol(), "✗");
+    }
+
+    #[test]
+    fn test_check_level_serialization() {
+        let json = serde_json::to_string(&CheckLevel::Warn).unwrap();
+        assert_eq!(json, "\"warn\"");
+    }
+
+    #[test]
+    fn test_check_result_serialization() {
+        let result = CheckResult::fail("P2P 端口", "18888 被占用", "ss -ltnp");
+        let json = serde_json::to_string(&result).unwrap();
+        assert!(json.contains("\"level\":\"fail\""));
+        assert!(json.contains("ss -ltnp"));
+    }
+}
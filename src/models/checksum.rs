@@ -0,0 +1,234 @@
+use crate::error::{Result, TronCtlError};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// 支持的校验算法
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChecksumAlgorithm {
+    Md5,
+    Sha1,
+    Sha256,
+    Sha512,
+}
+
+impl ChecksumAlgorithm {
+    /// 按强度从高到低排列，用于选择校验文件
+    pub const ALL: [ChecksumAlgorithm; 4] = [Self::Sha512, Self::Sha256, Self::Sha1, Self::Md5];
+
+    /// 用于显示的名称，如 `SHA-256`
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Md5 => "MD5",
+            Self::Sha1 => "SHA-1",
+            Self::Sha256 => "SHA-256",
+            Self::Sha512 => "SHA-512",
+        }
+    }
+
+    /// 前缀与校验文件使用的标识，如 `sha256`
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::Md5 => "md5",
+            Self::Sha1 => "sha1",
+            Self::Sha256 => "sha256",
+            Self::Sha512 => "sha512",
+        }
+    }
+
+    /// 十六进制摘要长度
+    pub fn hex_len(&self) -> usize {
+        match self {
+            Self::Md5 => 32,
+            Self::Sha1 => 40,
+            Self::Sha256 => 64,
+            Self::Sha512 => 128,
+        }
+    }
+
+    /// 校验文件后缀，如 `.sha256sum`
+    pub fn sidecar_suffix(&self) -> String {
+        format!(".{}sum", self.id())
+    }
+
+    /// 按标识解析，兼容 `SHA-256`、`sha256` 等写法
+    pub fn parse(id: &str) -> Option<Self> {
+        let id = id.to_lowercase().replace('-', "");
+        Self::ALL.into_iter().find(|alg| alg.id() == id)
+    }
+
+    /// 按摘要长度推断算法
+    pub fn from_hex_len(len: usize) -> Option<Self> {
+        Self::ALL.into_iter().find(|alg| alg.hex_len() == len)
+    }
+}
+
+/// 期望的校验值，字符串形式为 `<算法>:<十六进制>`，省略算法时按长度推断
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Checksum {
+    pub algorithm: ChecksumAlgorithm,
+    /// 小写十六进制摘要
+    pub value: String,
+}
+
+impl Checksum {
+    pub fn new(algorithm: ChecksumAlgorithm, value: &str) -> Result<Self> {
+        let value = value.trim().to_lowercase();
+        if value.len() != algorithm.hex_len() || !value.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(TronCtlError::ConfigError(format!(
+                "无效的 {} 校验值: {}",
+                algorithm.name(),
+                value
+            )));
+        }
+
+        Ok(Self { algorithm, value })
+    }
+
+    /// 解析 `sha256:<hex>` 或不带前缀的十六进制摘要
+    pub fn parse(s: &str) -> Result<Self> {
+        let s = s.trim();
+        let algorithm = match s.split_once(':') {
+            Some((id, value)) => {
+                let algorithm = ChecksumAlgorithm::parse(id).ok_or_else(|| {
+                    TronCtlError::ConfigError(format!("不支持的校验算法: {}", id))
+                })?;
+                return Self::new(algorithm, value);
+            }
+            None => ChecksumAlgorithm::from_hex_len(s.len()),
+        };
+
+        match algorithm {
+            Some(algorithm) => Self::new(algorithm, s),
+            None => Err(TronCtlError::ConfigError(format!(
+                "无法识别校验值的算法: {}",
+                s
+            ))),
+        }
+    }
+
+    /// 从 `<hash>  <filename>` 格式的校验文件中读取指定算法的摘要
+    pub fn from_sidecar(algorithm: ChecksumAlgorithm, content: &str) -> Option<Self> {
+        Self::new(algorithm, content.split_whitespace().next()?).ok()
+    }
+
+    /// 与实际摘要比较，不一致时返回带算法名称的错误
+    pub fn verify(&self, actual: &str) -> Result<()> {
+        if self.value.eq_ignore_ascii_case(actual) {
+            Ok(())
+        } else {
+            Err(TronCtlError::ChecksumMismatch {
+                algorithm: self.algorithm.name().to_string(),
+                expected: self.value.clone(),
+                actual: actual.to_string(),
+            })
+        }
+    }
+}
+
+impl fmt::Display for Checksum {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.algorithm.id(), self.value)
+    }
+}
+
+impl FromStr for Checksum {
+    type Err = TronCtlError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+impl TryFrom<String> for Checksum {
+    type Error = TronCtlError;
+
+    fn try_from(s: String) -> Result<Self> {
+        Self::parse(&s)
+    }
+}
+
+impl From<Checksum> for String {
+    fn from(checksum: Checksum) -> Self {
+        checksum.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MD5: &str = "900150983cd24fb0d6963f7d28e17f72";
+    const SHA256: &str = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+
+    #[test]
+    fn test_parse_with_prefix() {
+        let checksum = Checksum::parse(&format!("SHA-256:{}", SHA256.to_uppercase())).unwrap();
+        assert_eq!(checksum.algorithm, ChecksumAlgorithm::Sha256);
+        assert_eq!(checksum.value, SHA256);
+        assert_eq!(checksum.to_string(), format!("sha256:{}", SHA256));
+
+        assert!(Checksum::parse("crc32:abcd").is_err());
+        assert!(Checksum::parse("sha256:abcd").is_err());
+    }
+
+    #[test]
+    fn test_parse_infers_algorithm() {
+        assert_eq!(
+            Checksum::parse(MD5).unwrap().algorithm,
+            ChecksumAlgorithm::Md5
+        );
+        assert_eq!(
+            Checksum::parse(SHA256).unwrap().algorithm,
+            ChecksumAlgorithm::Sha256
+        );
+        assert_eq!(
+            Checksum::parse(&"a".repeat(128)).unwrap().algorithm,
+            ChecksumAlgorithm::Sha512
+        );
+        assert!(Checksum::parse("abc123").is_err());
+    }
+
+    #[test]
+    fn test_from_sidecar() {
+        let content = format!("{}  FullNode_output-directory.tgz\n", SHA256);
+        assert_eq!(
+            Checksum::from_sidecar(ChecksumAlgorithm::Sha256, &content)
+                .unwrap()
+                .value,
+            SHA256
+        );
+        // 长度与算法不符
+        assert!(Checksum::from_sidecar(ChecksumAlgorithm::Md5, &content).is_none());
+        assert!(Checksum::from_sidecar(ChecksumAlgorithm::Sha256, "<html>404</html>").is_none());
+    }
+
+    #[test]
+    fn test_verify_names_algorithm() {
+        let checksum = Checksum::parse(MD5).unwrap();
+        assert!(checksum.verify(&MD5.to_uppercase()).is_ok());
+
+        let err = checksum.verify("00").unwrap_err();
+        assert!(err.to_string().contains("MD5"));
+    }
+
+    #[test]
+    fn test_serde_string_form() {
+        let checksum = Checksum::parse(SHA256).unwrap();
+        let json = serde_json::to_string(&checksum).unwrap();
+        assert_eq!(json, format!("\"sha256:{}\"", SHA256));
+        assert_eq!(serde_json::from_str::<Checksum>(&json).unwrap(), checksum);
+        assert!(serde_json::from_str::<Checksum>("\"md5:xyz\"").is_err());
+    }
+
+    #[test]
+    fn test_algorithm_parse() {
+        assert_eq!(
+            ChecksumAlgorithm::parse("SHA-512"),
+            Some(ChecksumAlgorithm::Sha512)
+        );
+        assert_eq!(ChecksumAlgorithm::parse("sha3"), None);
+        assert_eq!(ChecksumAlgorithm::Sha256.sidecar_suffix(), ".sha256sum");
+    }
+}
@@ -118,7 +118,7 @@ mod tests {
         let status2 = status1.clone();
         assert_eq!(status2.current_block, 777);
         assert_eq!(status2.previous_block, 776);
-        assert_eq!(status2.process_alive, true);
+        assert!(status2.process_alive);
     }
 
     #[test]
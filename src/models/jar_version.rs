@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+
+/// 版本目录中一个 FullNode.jar 的元数据
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JarVersion {
+    pub tag: String,
+    /// 下载时间（Unix 时间戳，秒）
+    pub downloaded_at: i64,
+    pub sha256: String,
+    pub size: u64,
+}
+
+impl JarVersion {
+    pub fn downloaded_at_display(&self) -> String {
+        chrono::DateTime::from_timestamp(self.downloaded_at, 0)
+            .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_else(|| "-".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_jar_version_roundtrip() {
+        let version = JarVersion {
+            tag: "GreatVoyage-v4.8.0".to_string(),
+            downloaded_at: 1_767_225_600,
+            sha256: "abc".to_string(),
+            size: 1024,
+        };
+
+        let json = serde_json::to_string(&version).unwrap();
+        let parsed: JarVersion = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, version);
+        assert_eq!(parsed.downloaded_at_display(), "2026-01-01 00:00");
+    }
+}
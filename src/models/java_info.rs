@@ -0,0 +1,79 @@
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// 探测到的 Java 运行时
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct JavaInstallation {
+    pub path: PathBuf,
+    pub vendor: String,
+    /// 完整版本号，如 `1.8.0_382`、`17.0.9`
+    pub version: String,
+    pub major: u32,
+    /// 规范化后的 CPU 架构，如 `x86_64`、`aarch64`
+    pub arch: String,
+}
+
+impl JavaInstallation {
+    pub fn summary(&self) -> String {
+        format!(
+            "{} {} ({}) - {}",
+            self.vendor,
+            self.version,
+            self.arch,
+            self.path.display()
+        )
+    }
+}
+
+/// 解析主版本号：`1.8.0_382` 为 8，`17.0.9` 为 17
+pub fn parse_major(version: &str) -> Option<u32> {
+    let mut parts = version.split(['.', '_', '-', '+']);
+    match parts.next()?.parse().ok()? {
+        1 => parts.next()?.parse().ok(),
+        major => Some(major),
+    }
+}
+
+/// 统一架构名称（JVM 的 os.arch 使用 amd64、arm64 等别名）
+pub fn normalize_arch(arch: &str) -> String {
+    match arch {
+        "amd64" | "x86-64" | "x64" => "x86_64".to_string(),
+        "arm64" | "aarch_64" => "aarch64".to_string(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_major() {
+        assert_eq!(parse_major("1.8.0_382"), Some(8));
+        assert_eq!(parse_major("17.0.9"), Some(17));
+        assert_eq!(parse_major("21"), Some(21));
+        assert_eq!(parse_major("11.0.2+9"), Some(11));
+        assert_eq!(parse_major("unknown"), None);
+    }
+
+    #[test]
+    fn test_normalize_arch() {
+        assert_eq!(normalize_arch("amd64"), "x86_64");
+        assert_eq!(normalize_arch("arm64"), "aarch64");
+        assert_eq!(normalize_arch("aarch64"), "aarch64");
+    }
+
+    #[test]
+    fn test_summary() {
+        let java = JavaInstallation {
+            path: PathBuf::from("/usr/lib/jvm/java-8-openjdk-amd64/bin/java"),
+            vendor: "Private Build".to_string(),
+            version: "1.8.0_382".to_string(),
+            major: 8,
+            arch: "x86_64".to_string(),
+        };
+        let summary = java.summary();
+        assert!(summary.contains("1.8.0_382"));
+        assert!(summary.contains("java-8-openjdk-amd64"));
+    }
+}
@@ -1,5 +1,8 @@
+use super::snapshot_info::SnapshotSource;
 use serde::{Deserialize, Serialize};
+use std::fmt;
 use std::path::PathBuf;
+use std::str::FromStr;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TronCtlConfig {
@@ -7,10 +10,262 @@ pub struct TronCtlConfig {
     pub jvm_min_heap: String,
     pub jvm_max_heap: String,
     pub fullnode_jar: PathBuf,
+    /// fullnode_jar 当前指向的版本标签
+    #[serde(default)]
+    pub fullnode_version: Option<String>,
     pub node_config: PathBuf,
+    /// node_config 所基于的默认配置模板的版本标签
+    #[serde(default)]
+    pub node_config_tag: Option<String>,
     pub data_dir: PathBuf,
     pub log_file: PathBuf,
     pub snapshot_type: String,
+    /// 用于链一致性检查的可信参考节点 HTTP 地址
+    #[serde(default = "default_reference_endpoints")]
+    pub reference_endpoints: Vec<String>,
+    /// 无对等节点连接持续超过该时长（秒）则判定节点异常
+    #[serde(default = "default_zero_peer_timeout_secs")]
+    pub zero_peer_timeout_secs: u64,
+    /// 发现强制升级版本等事件时 POST 通知的地址
+    #[serde(default)]
+    pub webhook_url: Option<String>,
+    #[serde(default)]
+    pub github: GithubConfig,
+    #[serde(default)]
+    pub snapshot: SnapshotConfig,
+    #[serde(default)]
+    pub download: DownloadConfig,
+}
+
+/// `[snapshot]`：快照服务器列表
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SnapshotConfig {
+    /// `[[snapshot.sources]]`，为空时使用内置服务器
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub sources: Vec<SnapshotSource>,
+}
+
+impl SnapshotConfig {
+    /// 实际使用的快照服务器，按优先级排列
+    pub fn sources(&self) -> Vec<SnapshotSource> {
+        let mut sources = if self.sources.is_empty() {
+            crate::constants::SNAPSHOT_SERVERS
+                .iter()
+                .map(|url| SnapshotSource::builtin(url))
+                .collect()
+        } else {
+            self.sources.clone()
+        };
+        sources.sort_by_key(|source| source.priority);
+        sources
+    }
+}
+
+/// `[download]`：分块下载的并发连接数、分块大小与限速，命令行参数优先
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DownloadConfig {
+    /// 并发连接数，未设置时使用 CPU 核数
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub connections: Option<usize>,
+    /// 分块大小，如 `"16M"`，不小于 1M
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_chunk_size"
+    )]
+    pub chunk_size: Option<ByteSize>,
+    /// 所有连接合计的速率上限（每秒），如 `"10M"`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit_rate: Option<ByteSize>,
+}
+
+impl DownloadConfig {
+    /// 用 `overrides` 中设置的项覆盖当前配置
+    pub fn merge(self, overrides: DownloadConfig) -> Self {
+        Self {
+            connections: overrides.connections.or(self.connections),
+            chunk_size: overrides.chunk_size.or(self.chunk_size),
+            limit_rate: overrides.limit_rate.or(self.limit_rate),
+        }
+    }
+
+    pub fn connections(&self) -> usize {
+        self.connections.unwrap_or_else(num_cpus::get).max(1)
+    }
+
+    pub fn chunk_size(&self) -> u64 {
+        self.chunk_size
+            .map_or(crate::constants::DEFAULT_DOWNLOAD_CHUNK_SIZE, |size| size.0)
+            .max(crate::constants::MIN_DOWNLOAD_CHUNK_SIZE)
+    }
+
+    pub fn limit_rate(&self) -> Option<u64> {
+        self.limit_rate.map(|rate| rate.0).filter(|rate| *rate > 0)
+    }
+}
+
+/// 解析分块大小，拒绝小于 [`MIN_DOWNLOAD_CHUNK_SIZE`](crate::constants::MIN_DOWNLOAD_CHUNK_SIZE) 的值
+pub fn parse_chunk_size(s: &str) -> Result<ByteSize, String> {
+    check_chunk_size(s.parse()?)
+}
+
+fn check_chunk_size(size: ByteSize) -> Result<ByteSize, String> {
+    let min = ByteSize(crate::constants::MIN_DOWNLOAD_CHUNK_SIZE);
+    if size.0 < min.0 {
+        return Err(format!("分块大小 {} 过小，不能小于 {}", size, min));
+    }
+    Ok(size)
+}
+
+fn deserialize_chunk_size<'de, D>(deserializer: D) -> Result<Option<ByteSize>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Option::<ByteSize>::deserialize(deserializer)?
+        .map(check_chunk_size)
+        .transpose()
+        .map_err(serde::de::Error::custom)
+}
+
+/// 字节数，可写为整数或带 K/M/G/T 单位（按 1024 进位）的字符串，如 `"512K"`、`"16MiB"`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "ByteSizeRepr", into = "String")]
+pub struct ByteSize(pub u64);
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ByteSizeRepr {
+    Bytes(u64),
+    Text(String),
+}
+
+impl TryFrom<ByteSizeRepr> for ByteSize {
+    type Error = String;
+
+    fn try_from(repr: ByteSizeRepr) -> Result<Self, Self::Error> {
+        match repr {
+            ByteSizeRepr::Bytes(bytes) => Ok(Self(bytes)),
+            ByteSizeRepr::Text(text) => text.parse(),
+        }
+    }
+}
+
+impl FromStr for ByteSize {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+        let (number, unit) = s.split_at(split);
+        let number: u64 = number.parse().map_err(|_| format!("无效的大小: {:?}", s))?;
+
+        let unit = unit.trim().to_ascii_uppercase();
+        let unit = unit
+            .strip_suffix("IB")
+            .or_else(|| unit.strip_suffix('B'))
+            .unwrap_or(&unit);
+        let shift = match unit {
+            "" => 0,
+            "K" => 10,
+            "M" => 20,
+            "G" => 30,
+            "T" => 40,
+            _ => return Err(format!("无效的大小单位: {:?}", s)),
+        };
+
+        number
+            .checked_mul(1 << shift)
+            .map(Self)
+            .ok_or_else(|| format!("大小超出范围: {:?}", s))
+    }
+}
+
+impl fmt::Display for ByteSize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (unit, shift) in [("T", 40), ("G", 30), ("M", 20), ("K", 10)] {
+            if self.0 >= 1 << shift && self.0.is_multiple_of(1 << shift) {
+                return write!(f, "{}{}", self.0 >> shift, unit);
+            }
+        }
+        write!(f, "{}", self.0)
+    }
+}
+
+impl From<ByteSize> for String {
+    fn from(size: ByteSize) -> Self {
+        size.to_string()
+    }
+}
+
+/// `[github]`：Releases API 与 jar 下载地址，GitHub 受限时可改为镜像
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GithubConfig {
+    /// API 地址，镜像需兼容 `/repos/{owner}/{repo}/releases`
+    pub api_base: String,
+    /// Release 附件下载地址，拼接为 `{download_base}/{owner}/{repo}/releases/download/{tag}/{asset}`
+    pub download_base: String,
+    /// 仓库文件下载地址，拼接为 `{raw_base}/{owner}/{repo}/{tag}/{path}`
+    pub raw_base: String,
+    /// 是否将预发布版本视为最新版本
+    pub prerelease: bool,
+}
+
+impl GithubConfig {
+    /// Releases 列表地址
+    pub fn releases_api(&self) -> String {
+        format!(
+            "{}/repos/{}/releases",
+            self.api_base.trim_end_matches('/'),
+            crate::constants::GITHUB_REPO
+        )
+    }
+}
+
+impl Default for GithubConfig {
+    fn default() -> Self {
+        Self {
+            api_base: crate::constants::GITHUB_API_BASE.to_string(),
+            download_base: crate::constants::GITHUB_DOWNLOAD_BASE.to_string(),
+            raw_base: crate::constants::GITHUB_RAW_BASE.to_string(),
+            prerelease: false,
+        }
+    }
+}
+
+fn default_zero_peer_timeout_secs() -> u64 {
+    crate::constants::ZERO_PEER_TIMEOUT_SECS
+}
+
+fn default_reference_endpoints() -> Vec<String> {
+    crate::constants::DEFAULT_REFERENCE_ENDPOINTS
+        .iter()
+        .map(|s| s.to_string())
+        .collect()
+}
+
+impl TronCtlConfig {
+    /// 读取 tronctl.toml，不存在时使用默认配置
+    pub fn load_or_default() -> crate::error::Result<Self> {
+        let config_path =
+            PathBuf::from(crate::constants::CONFIG_DIR).join(crate::constants::APP_CONFIG);
+
+        if !config_path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = std::fs::read_to_string(config_path)?;
+        Ok(toml::from_str(&content)?)
+    }
+
+    /// 写入 tronctl.toml
+    pub fn save(&self) -> crate::error::Result<()> {
+        let config_path =
+            PathBuf::from(crate::constants::CONFIG_DIR).join(crate::constants::APP_CONFIG);
+        std::fs::write(config_path, toml::to_string(self)?)?;
+        Ok(())
+    }
 }
 
 impl Default for TronCtlConfig {
@@ -20,11 +275,19 @@ impl Default for TronCtlConfig {
             jvm_min_heap: crate::constants::DEFAULT_JVM_MIN_HEAP.to_string(),
             jvm_max_heap: crate::constants::DEFAULT_JVM_MAX_HEAP.to_string(),
             fullnode_jar: PathBuf::from(crate::constants::DATA_DIR).join("FullNode.jar"),
+            fullnode_version: None,
             node_config: PathBuf::from(crate::constants::CONFIG_DIR)
                 .join(crate::constants::NODE_CONFIG),
+            node_config_tag: None,
             data_dir: PathBuf::from(crate::constants::DATA_DIR).join("data/output-directory"),
             log_file: PathBuf::from(crate::constants::LOG_DIR).join("fullnode.log"),
             snapshot_type: "none".to_string(),
+            reference_endpoints: default_reference_endpoints(),
+            zero_peer_timeout_secs: default_zero_peer_timeout_secs(),
+            webhook_url: None,
+            github: GithubConfig::default(),
+            snapshot: SnapshotConfig::default(),
+            download: DownloadConfig::default(),
         }
     }
 }
@@ -76,6 +339,174 @@ mod tests {
         let config: TronCtlConfig = toml::from_str(toml_str).unwrap();
         assert_eq!(config.snapshot_type, "lite");
         assert_eq!(config.jvm_min_heap, "8g");
+        assert_eq!(
+            config.reference_endpoints,
+            vec!["https://api.trongrid.io".to_string()]
+        );
+        assert_eq!(config.fullnode_version, None);
+        assert_eq!(config.webhook_url, None);
+        assert_eq!(config.node_config_tag, None);
+        assert_eq!(
+            config.github.releases_api(),
+            crate::constants::GITHUB_API_RELEASES
+        );
+    }
+
+    #[test]
+    fn test_config_github_mirror() {
+        let toml_str = r#"
+            java_path = "/usr/bin/java"
+            jvm_min_heap = "8g"
+            jvm_max_heap = "12g"
+            fullnode_jar = "/var/lib/tronctl/FullNode.jar"
+            node_config = "/etc/tronctl/tron.conf"
+            data_dir = "/var/lib/tronctl/data/output-directory"
+            log_file = "/var/log/tronctl/fullnode.log"
+            snapshot_type = "none"
+
+            [github]
+            api_base = "https://gh-api.example.com/"
+            prerelease = true
+        "#;
+
+        let config: TronCtlConfig = toml::from_str(toml_str).unwrap();
+        assert_eq!(
+            config.github.releases_api(),
+            "https://gh-api.example.com/repos/tronprotocol/java-tron/releases"
+        );
+        assert_eq!(config.github.download_base, "https://github.com");
+        assert!(config.github.prerelease);
+
+        // 嵌套表可以正确写回
+        let serialized = toml::to_string(&config).unwrap();
+        assert!(serialized.contains("[github]"));
+    }
+
+    #[test]
+    fn test_config_snapshot_sources() {
+        let toml_str = r#"
+            java_path = "/usr/bin/java"
+            jvm_min_heap = "8g"
+            jvm_max_heap = "12g"
+            fullnode_jar = "/var/lib/tronctl/FullNode.jar"
+            node_config = "/etc/tronctl/tron.conf"
+            data_dir = "/var/lib/tronctl/data/output-directory"
+            log_file = "/var/log/tronctl/fullnode.log"
+            snapshot_type = "lite"
+
+            [[snapshot.sources]]
+            url = "https://backup.example.com"
+            priority = 10
+
+            [[snapshot.sources]]
+            url = "https://mirror.example.com"
+            path = "tron/{date}/{prefix}.tgz"
+            priority = 1
+            headers = { Authorization = "Bearer secret" }
+        "#;
+
+        let config: TronCtlConfig = toml::from_str(toml_str).unwrap();
+        let sources = config.snapshot.sources();
+        assert_eq!(sources.len(), 2);
+        assert_eq!(sources[0].url, "https://mirror.example.com");
+        assert_eq!(sources[0].headers["Authorization"], "Bearer secret");
+        assert_eq!(sources[1].path, "backup{date}/{prefix}.tgz");
+
+        let serialized = toml::to_string(&config).unwrap();
+        assert!(serialized.contains("[[snapshot.sources]]"));
+    }
+
+    #[test]
+    fn test_config_download() {
+        let toml_str = r#"
+            java_path = "/usr/bin/java"
+            jvm_min_heap = "8g"
+            jvm_max_heap = "12g"
+            fullnode_jar = "/var/lib/tronctl/FullNode.jar"
+            node_config = "/etc/tronctl/tron.conf"
+            data_dir = "/var/lib/tronctl/data/output-directory"
+            log_file = "/var/log/tronctl/fullnode.log"
+            snapshot_type = "none"
+
+            [download]
+            connections = 4
+            chunk_size = "8M"
+            limit_rate = 1048576
+        "#;
+
+        let config: TronCtlConfig = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.download.connections(), 4);
+        assert_eq!(config.download.chunk_size(), 8 * 1024 * 1024);
+        assert_eq!(config.download.limit_rate(), Some(1024 * 1024));
+
+        // 命令行参数覆盖配置文件
+        let merged = config.download.clone().merge(DownloadConfig {
+            connections: Some(16),
+            ..Default::default()
+        });
+        assert_eq!(merged.connections(), 16);
+        assert_eq!(merged.chunk_size(), 8 * 1024 * 1024);
+
+        let serialized = toml::to_string(&config).unwrap();
+        assert!(serialized.contains("chunk_size = \"8M\""));
+        assert!(serialized.contains("limit_rate = \"1M\""));
+
+        let defaults = DownloadConfig::default();
+        assert_eq!(
+            defaults.chunk_size(),
+            crate::constants::DEFAULT_DOWNLOAD_CHUNK_SIZE
+        );
+        assert_eq!(defaults.limit_rate(), None);
+    }
+
+    #[test]
+    fn test_byte_size_parse() {
+        assert_eq!("1048576".parse(), Ok(ByteSize(1024 * 1024)));
+        assert_eq!("512k".parse(), Ok(ByteSize(512 * 1024)));
+        assert_eq!("16MiB".parse(), Ok(ByteSize(16 * 1024 * 1024)));
+        assert_eq!("2 GB".parse(), Ok(ByteSize(2 * 1024 * 1024 * 1024)));
+        assert!("".parse::<ByteSize>().is_err());
+        assert!("10X".parse::<ByteSize>().is_err());
+        assert!("99999999999T".parse::<ByteSize>().is_err());
+
+        assert_eq!(ByteSize(1536).to_string(), "1536");
+        assert_eq!(ByteSize(3 * 1024 * 1024).to_string(), "3M");
+    }
+
+    #[test]
+    fn test_chunk_size_minimum() {
+        assert_eq!(parse_chunk_size("1M"), Ok(ByteSize(1024 * 1024)));
+        assert!(parse_chunk_size("1K").is_err());
+        assert!(parse_chunk_size("0").is_err());
+
+        let result: Result<TronCtlConfig, _> = toml::from_str("[download]\nchunk_size = \"4K\"\n");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_config_snapshot_sources_default() {
+        let sources = TronCtlConfig::default().snapshot.sources();
+        assert_eq!(sources.len(), crate::constants::SNAPSHOT_SERVERS.len());
+        assert_eq!(sources[0].url, crate::constants::SNAPSHOT_SERVERS[0]);
+    }
+
+    #[test]
+    fn test_config_reference_endpoints_override() {
+        let toml_str = r#"
+            java_path = "/usr/bin/java"
+            jvm_min_heap = "8g"
+            jvm_max_heap = "12g"
+            fullnode_jar = "/var/lib/tronctl/FullNode.jar"
+            node_config = "/etc/tronctl/tron.conf"
+            data_dir = "/var/lib/tronctl/data/output-directory"
+            log_file = "/var/log/tronctl/fullnode.log"
+            snapshot_type = "none"
+            reference_endpoints = ["http://10.0.0.1:8090", "http://10.0.0.2:8090"]
+        "#;
+
+        let config: TronCtlConfig = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.reference_endpoints.len(), 2);
+        assert_eq!(config.zero_peer_timeout_secs, 600);
     }
 
     #[test]
@@ -0,0 +1,230 @@
+use serde::{Deserialize, Serialize};
+
+/// `/wallet/getnodeinfo` 的响应
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NodeInfo {
+    pub current_connect_count: u32,
+    pub active_connect_count: u32,
+    pub passive_connect_count: u32,
+    /// 格式如 `Num:123,ID:0000...`
+    pub block: String,
+    pub solidity_block: String,
+    pub peer_list: Vec<PeerInfo>,
+    pub config_node_info: Option<ConfigNodeInfo>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PeerInfo {
+    /// true 表示由本节点主动发起的连接（出站）
+    pub active: bool,
+    pub host: String,
+    pub port: u16,
+    pub avg_latency: f64,
+    pub head_block_we_both_have: String,
+    pub last_sync_block: String,
+    pub remain_num: u64,
+    pub sync_flag: bool,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ConfigNodeInfo {
+    pub code_version: String,
+    pub p2p_version: String,
+    pub listen_port: u16,
+    pub discover_enable: bool,
+    pub max_connect_count: u32,
+    pub same_ip_max_connect_count: u32,
+    pub db_version: u32,
+    pub support_constant: bool,
+}
+
+/// `/wallet/listnodes` 的响应
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ListNodesResponse {
+    pub nodes: Vec<DiscoveredNode>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DiscoveredNode {
+    pub address: NodeAddress,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct NodeAddress {
+    /// java-tron 返回十六进制编码的主机字符串
+    pub host: String,
+    pub port: u16,
+}
+
+/// 持久化的对等节点状态，用于判断节点无连接持续了多久
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PeerState {
+    /// 记录状态时的节点进程，节点重启后重新计时
+    #[serde(default)]
+    pub pid: Option<i32>,
+    pub zero_peers_since: Option<i64>,
+}
+
+/// 解析 `Num:123,ID:...` 格式的区块描述
+pub fn parse_block_num(block: &str) -> Option<u64> {
+    block
+        .split(',')
+        .find_map(|part| part.trim().strip_prefix("Num:"))
+        .and_then(|n| n.trim().parse().ok())
+}
+
+impl NodeInfo {
+    pub fn head_block(&self) -> Option<u64> {
+        parse_block_num(&self.block)
+    }
+
+    /// 出站连接数，优先以 peerList 为准
+    pub fn outbound(&self) -> u32 {
+        if self.peer_list.is_empty() {
+            self.active_connect_count
+        } else {
+            self.peer_list.iter().filter(|p| p.active).count() as u32
+        }
+    }
+
+    /// 入站连接数，优先以 peerList 为准
+    pub fn inbound(&self) -> u32 {
+        if self.peer_list.is_empty() {
+            self.passive_connect_count
+        } else {
+            self.peer_list.iter().filter(|p| !p.active).count() as u32
+        }
+    }
+
+    pub fn peer_count(&self) -> u32 {
+        self.current_connect_count.max(self.peer_list.len() as u32)
+    }
+}
+
+impl PeerInfo {
+    /// 估算对端区块高度: 双方共同拥有的最高区块 + 尚需从对端同步的数量
+    pub fn head_estimate(&self) -> Option<u64> {
+        let both = parse_block_num(&self.head_block_we_both_have);
+        let synced = parse_block_num(&self.last_sync_block);
+
+        both.max(synced).map(|n| n + self.remain_num)
+    }
+}
+
+impl NodeAddress {
+    pub fn decoded_host(&self) -> String {
+        let bytes: Option<Vec<u8>> = (0..self.host.len())
+            .step_by(2)
+            .map(|i| {
+                self.host
+                    .get(i..i + 2)
+                    .and_then(|b| u8::from_str_radix(b, 16).ok())
+            })
+            .collect();
+
+        bytes
+            .and_then(|b| String::from_utf8(b).ok())
+            .unwrap_or_else(|| self.host.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NODE_INFO_JSON: &str = r#"{
+        "activeConnectCount": 2,
+        "passiveConnectCount": 1,
+        "currentConnectCount": 3,
+        "block": "Num:1000,ID:00000000000003e8abc",
+        "solidityBlock": "Num:980,ID:00000000000003d4abc",
+        "peerList": [
+            {"active": true, "host": "1.2.3.4", "port": 18888, "avgLatency": 12.5,
+             "headBlockWeBothHave": "Num:990,ID:x", "lastSyncBlock": "", "remainNum": 15},
+            {"active": true, "host": "5.6.7.8", "port": 18888,
+             "headBlockWeBothHave": "Num:1000,ID:y", "remainNum": 0},
+            {"active": false, "host": "9.9.9.9", "port": 18888}
+        ],
+        "configNodeInfo": {
+            "codeVersion": "4.7.4",
+            "p2pVersion": "11111",
+            "listenPort": 18888,
+            "discoverEnable": true,
+            "maxConnectCount": 30,
+            "sameIpMaxConnectCount": 2,
+            "dbVersion": 2
+        }
+    }"#;
+
+    #[test]
+    fn test_parse_block_num() {
+        assert_eq!(parse_block_num("Num:12345,ID:abc"), Some(12345));
+        assert_eq!(parse_block_num(""), None);
+        assert_eq!(parse_block_num("ID:abc"), None);
+    }
+
+    #[test]
+    fn test_node_info_deserialization() {
+        let info: NodeInfo = serde_json::from_str(NODE_INFO_JSON).unwrap();
+
+        assert_eq!(info.head_block(), Some(1000));
+        assert_eq!(info.peer_count(), 3);
+        assert_eq!(info.outbound(), 2);
+        assert_eq!(info.inbound(), 1);
+
+        let config = info.config_node_info.unwrap();
+        assert_eq!(config.code_version, "4.7.4");
+        assert_eq!(config.listen_port, 18888);
+        assert_eq!(config.max_connect_count, 30);
+    }
+
+    #[test]
+    fn test_node_info_empty() {
+        let info: NodeInfo = serde_json::from_str("{}").unwrap();
+        assert_eq!(info.peer_count(), 0);
+        assert!(info.head_block().is_none());
+    }
+
+    #[test]
+    fn test_peer_head_estimate() {
+        let info: NodeInfo = serde_json::from_str(NODE_INFO_JSON).unwrap();
+
+        assert_eq!(info.peer_list[0].head_estimate(), Some(1005));
+        assert_eq!(info.peer_list[1].head_estimate(), Some(1000));
+        assert_eq!(info.peer_list[2].head_estimate(), None);
+    }
+
+    #[test]
+    fn test_list_nodes_decoded_host() {
+        let json = r#"{"nodes": [{"address": {"host": "3132372e302e302e31", "port": 18888}}]}"#;
+        let resp: ListNodesResponse = serde_json::from_str(json).unwrap();
+
+        assert_eq!(resp.nodes.len(), 1);
+        assert_eq!(resp.nodes[0].address.decoded_host(), "127.0.0.1");
+    }
+
+    #[test]
+    fn test_decoded_host_invalid_hex() {
+        let addr = NodeAddress {
+            host: "not-hex".to_string(),
+            port: 1,
+        };
+        assert_eq!(addr.decoded_host(), "not-hex");
+    }
+
+    #[test]
+    fn test_peer_state_roundtrip() {
+        let state = PeerState {
+            pid: Some(1234),
+            zero_peers_since: Some(1_700_000_000),
+        };
+        let json = serde_json::to_string(&state).unwrap();
+        let parsed: PeerState = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.pid, Some(1234));
+        assert_eq!(parsed.zero_peers_since, Some(1_700_000_000));
+    }
+}
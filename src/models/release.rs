@@ -0,0 +1,205 @@
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+/// GitHub Releases API 返回的版本信息
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Release {
+    pub tag_name: String,
+    pub name: Option<String>,
+    /// Markdown 格式的发布说明
+    pub body: Option<String>,
+    pub draft: bool,
+    pub prerelease: bool,
+    pub published_at: Option<String>,
+    pub html_url: String,
+    pub assets: Vec<ReleaseAsset>,
+}
+
+/// Release 附件
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ReleaseAsset {
+    pub name: String,
+    pub size: u64,
+    /// GitHub 计算的摘要，如 `sha256:...`
+    pub digest: Option<String>,
+}
+
+impl Release {
+    /// 发布说明中标注为强制升级或硬分叉的版本
+    pub fn is_mandatory(&self) -> bool {
+        let text = format!(
+            "{}\n{}",
+            self.name.as_deref().unwrap_or_default(),
+            self.body.as_deref().unwrap_or_default()
+        )
+        .to_lowercase()
+        .replace("non-mandatory", "")
+        .replace("非强制", "");
+
+        [
+            "mandatory",
+            "hard fork",
+            "hardfork",
+            "hard-fork",
+            "强制",
+            "硬分叉",
+        ]
+        .iter()
+        .any(|keyword| text.contains(keyword))
+    }
+
+    /// 附件摘要中的 SHA-256
+    pub fn asset_sha256(&self, name: &str) -> Option<String> {
+        self.assets
+            .iter()
+            .find(|asset| asset.name == name)?
+            .digest
+            .as_deref()?
+            .strip_prefix("sha256:")
+            .map(str::to_lowercase)
+    }
+
+    /// 可能包含 `name` 校验值的附件：`<name>.sha256` 或 `SHA256SUMS` 等汇总文件
+    pub fn checksum_assets(&self, name: &str) -> Vec<&ReleaseAsset> {
+        self.assets
+            .iter()
+            .filter(|asset| {
+                let lower = asset.name.to_lowercase();
+                lower.starts_with(&format!("{}.sha256", name.to_lowercase()))
+                    || ((lower.contains("sha256") || lower.contains("checksum"))
+                        && !lower.ends_with(".jar"))
+            })
+            .collect()
+    }
+
+    /// 发布日期，如 `2024-05-20`
+    pub fn published_date(&self) -> &str {
+        self.published_at
+            .as_deref()
+            .map(|at| at.get(..10).unwrap_or(at))
+            .unwrap_or("-")
+    }
+}
+
+/// 解析版本号，兼容 `GreatVoyage-v4.7.4`、`v4.7.3.1` 与 `4.7.4` 等格式
+pub fn parse_version(version: &str) -> Option<Vec<u32>> {
+    let start = version.find(|c: char| c.is_ascii_digit())?;
+    let mut parts: Vec<u32> = version[start..]
+        .split('.')
+        .map_while(|part| {
+            let digits: String = part.chars().take_while(char::is_ascii_digit).collect();
+            digits.parse().ok()
+        })
+        .collect();
+
+    // 4.7 与 4.7.0 视为同一版本
+    while parts.len() > 1 && parts.last() == Some(&0) {
+        parts.pop();
+    }
+
+    (!parts.is_empty()).then_some(parts)
+}
+
+/// 比较两个版本号，无法解析时返回 None
+pub fn compare_versions(a: &str, b: &str) -> Option<Ordering> {
+    Some(parse_version(a)?.cmp(&parse_version(b)?))
+}
+
+/// 持久化的版本检查结果，避免每次 `status` 都请求 GitHub
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReleaseState {
+    pub checked_at: i64,
+    /// 检查时节点运行的版本
+    pub current: String,
+    /// 比当前版本新的强制升级版本
+    pub mandatory: Vec<String>,
+    /// 已发送过 webhook 通知的版本
+    pub notified: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn release(tag: &str, body: &str) -> Release {
+        Release {
+            tag_name: tag.to_string(),
+            body: Some(body.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(parse_version("GreatVoyage-v4.7.4"), Some(vec![4, 7, 4]));
+        assert_eq!(
+            parse_version("GreatVoyage-v4.7.3.1"),
+            Some(vec![4, 7, 3, 1])
+        );
+        assert_eq!(parse_version("4.8.0"), Some(vec![4, 8]));
+        assert_eq!(parse_version("v4.8.0-rc1"), Some(vec![4, 8]));
+        assert_eq!(parse_version("previous"), None);
+    }
+
+    #[test]
+    fn test_compare_versions() {
+        assert_eq!(
+            compare_versions("GreatVoyage-v4.7.4", "4.7.4"),
+            Some(Ordering::Equal)
+        );
+        assert_eq!(
+            compare_versions("GreatVoyage-v4.7.3.1", "GreatVoyage-v4.7.3"),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(
+            compare_versions("GreatVoyage-v4.7.10", "GreatVoyage-v4.8.0"),
+            Some(Ordering::Less)
+        );
+        assert_eq!(compare_versions("previous", "4.7.4"), None);
+    }
+
+    #[test]
+    fn test_is_mandatory() {
+        assert!(release("v4.8.0", "This is a **mandatory** upgrade version.").is_mandatory());
+        assert!(release("v4.7.0", "Includes a hard fork of TVM.").is_mandatory());
+        assert!(!release("v4.7.5", "This is a non-mandatory upgrade version.").is_mandatory());
+        assert!(!release("v4.7.6", "Bug fixes.").is_mandatory());
+    }
+
+    #[test]
+    fn test_asset_checksums() {
+        let r: Release = serde_json::from_str(
+            r#"{
+                "tag_name": "GreatVoyage-v4.8.0",
+                "assets": [
+                    {"name": "FullNode.jar", "size": 10, "digest": "sha256:ABCD"},
+                    {"name": "FullNode.jar.sha256", "size": 80, "digest": null},
+                    {"name": "SHA256SUMS.txt", "size": 160},
+                    {"name": "Toolkit.jar", "size": 10}
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(r.asset_sha256("FullNode.jar").as_deref(), Some("abcd"));
+        assert_eq!(r.asset_sha256("Toolkit.jar"), None);
+
+        let names: Vec<&str> = r
+            .checksum_assets("FullNode.jar")
+            .iter()
+            .map(|a| a.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["FullNode.jar.sha256", "SHA256SUMS.txt"]);
+    }
+
+    #[test]
+    fn test_published_date() {
+        let mut r = release("v4.8.0", "");
+        assert_eq!(r.published_date(), "-");
+        r.published_at = Some("2025-03-20T08:00:00Z".to_string());
+        assert_eq!(r.published_date(), "2025-03-20");
+    }
+}
@@ -1,34 +1,126 @@
+use super::checksum::Checksum;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::time::Duration;
 
+/// `[[snapshot.sources]]`：快照服务器及其目录布局
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotSource {
+    pub url: String,
+    /// 相对 url 的快照路径，`{date}` 替换为 YYYYMMDD，`{prefix}` 替换为快照文件名前缀
+    #[serde(default = "default_path_template")]
+    pub path: String,
+    /// 数值越小越优先，相同优先级按延迟排序
+    #[serde(default)]
+    pub priority: i32,
+    /// 请求该服务器时附加的 HTTP 头，如 Authorization
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+}
+
+impl SnapshotSource {
+    /// 使用默认目录布局的内置服务器
+    pub fn builtin(url: &str) -> Self {
+        Self {
+            url: url.to_string(),
+            path: default_path_template(),
+            priority: 0,
+            headers: BTreeMap::new(),
+        }
+    }
+
+    /// 指定日期与文件名前缀的快照地址
+    pub fn snapshot_url(&self, date: &str, prefix: &str) -> String {
+        format!(
+            "{}/{}",
+            self.url.trim_end_matches('/'),
+            self.path
+                .trim_start_matches('/')
+                .replace("{date}", date)
+                .replace("{prefix}", prefix)
+        )
+    }
+
+    /// 列出快照日期的目录索引地址，以及索引中日期前的固定前缀（默认布局为 `/` 与 `backup`）
+    pub fn date_index(&self) -> Option<(String, String)> {
+        let path = self.path.trim_start_matches('/');
+        let before_date = &path[..path.find("{date}")?];
+        let (dir, marker) = match before_date.rfind('/') {
+            Some(pos) => (&before_date[..=pos], &before_date[pos + 1..]),
+            None => ("", before_date),
+        };
+        Some((
+            format!("{}/{}", self.url.trim_end_matches('/'), dir),
+            marker.to_string(),
+        ))
+    }
+}
+
+fn default_path_template() -> String {
+    crate::constants::SNAPSHOT_PATH_TEMPLATE.to_string()
+}
+
 #[derive(Debug, Clone)]
 pub struct SnapshotServer {
-    pub url: String,
+    pub source: SnapshotSource,
     pub latency: Duration,
     pub available: bool,
+    /// 分段下载实测的吞吐（字节/秒）
+    pub throughput: Option<u64>,
+    /// 服务器上找到的目标快照
+    pub snapshot: Option<SnapshotMetadata>,
+}
+
+impl SnapshotServer {
+    pub fn url(&self) -> &str {
+        &self.source.url
+    }
+
+    /// 服务器可达且提供指定日期的快照
+    pub fn has_snapshot(&self, date: Option<&str>) -> bool {
+        self.available && date.is_some() && self.snapshot.as_ref().map(|s| s.date.as_str()) == date
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SnapshotMetadata {
     pub date: String,
     pub size_gb: u64,
-    pub md5: String,
+    /// 压缩包实际大小（字节），来自服务器 Content-Length
+    #[serde(default)]
+    pub size_bytes: u64,
+    /// 快照旁发布的校验值，旧格式中的 `md5` 字段同样适用
+    #[serde(default, alias = "md5")]
+    pub checksum: Option<Checksum>,
     pub download_url: String,
 }
 
+impl SnapshotMetadata {
+    /// 大小与校验值都一致，视为同一份快照文件；没有校验值时无法确认
+    pub fn same_file(&self, other: &SnapshotMetadata) -> bool {
+        self.size_bytes > 0
+            && self.size_bytes == other.size_bytes
+            && self.checksum.is_some()
+            && self.checksum == other.checksum
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::checksum::ChecksumAlgorithm;
 
     #[test]
     fn test_snapshot_server_creation() {
         let server = SnapshotServer {
-            url: "http://example.com".to_string(),
+            source: SnapshotSource::builtin("http://example.com"),
             latency: Duration::from_millis(100),
             available: true,
+            throughput: None,
+            snapshot: None,
         };
 
-        assert_eq!(server.url, "http://example.com");
+        assert_eq!(server.url(), "http://example.com");
         assert_eq!(server.latency, Duration::from_millis(100));
         assert!(server.available);
     }
@@ -36,28 +128,66 @@ mod tests {
     #[test]
     fn test_snapshot_server_unavailable() {
         let server = SnapshotServer {
-            url: "http://slow.com".to_string(),
+            source: SnapshotSource::builtin("http://slow.com"),
             latency: Duration::from_secs(999),
             available: false,
+            throughput: None,
+            snapshot: None,
         };
 
         assert!(!server.available);
         assert_eq!(server.latency.as_secs(), 999);
     }
 
+    #[test]
+    fn test_snapshot_source_urls() {
+        let source = SnapshotSource::builtin("http://34.143.247.77/");
+        assert_eq!(
+            source.snapshot_url("20260109", "FullNode_output-directory"),
+            "http://34.143.247.77/backup20260109/FullNode_output-directory.tgz"
+        );
+        assert_eq!(
+            source.date_index(),
+            Some(("http://34.143.247.77/".to_string(), "backup".to_string()))
+        );
+
+        let mirror = SnapshotSource {
+            path: "/tron/{date}/{prefix}.tgz".to_string(),
+            ..SnapshotSource::builtin("https://mirror.example.com")
+        };
+        assert_eq!(
+            mirror.snapshot_url("20260109", "LiteFullNode_output-directory"),
+            "https://mirror.example.com/tron/20260109/LiteFullNode_output-directory.tgz"
+        );
+        assert_eq!(
+            mirror.date_index(),
+            Some((
+                "https://mirror.example.com/tron/".to_string(),
+                String::new()
+            ))
+        );
+
+        let fixed = SnapshotSource {
+            path: "latest/{prefix}.tgz".to_string(),
+            ..SnapshotSource::builtin("https://mirror.example.com")
+        };
+        assert_eq!(fixed.date_index(), None);
+    }
+
     #[test]
     fn test_snapshot_metadata_serialization() {
         let metadata = SnapshotMetadata {
             date: "20260109".to_string(),
             size_gb: 53,
-            md5: "abc123".to_string(),
+            size_bytes: 56908316672,
+            checksum: Checksum::parse("900150983cd24fb0d6963f7d28e17f72").ok(),
             download_url: "http://example.com/snapshot.tgz".to_string(),
         };
 
         let json = serde_json::to_string(&metadata).unwrap();
         assert!(json.contains("20260109"));
         assert!(json.contains("53"));
-        assert!(json.contains("abc123"));
+        assert!(json.contains("md5:900150983cd24fb0d6963f7d28e17f72"));
     }
 
     #[test]
@@ -65,14 +195,45 @@ mod tests {
         let json = r#"{
             "date": "20260109",
             "size_gb": 2937,
-            "md5": "def456",
+            "md5": "900150983cd24fb0d6963f7d28e17f72",
             "download_url": "http://example.com/full.tgz"
         }"#;
 
         let metadata: SnapshotMetadata = serde_json::from_str(json).unwrap();
         assert_eq!(metadata.date, "20260109");
         assert_eq!(metadata.size_gb, 2937);
-        assert_eq!(metadata.md5, "def456");
+        let checksum = metadata.checksum.unwrap();
+        assert_eq!(checksum.algorithm, ChecksumAlgorithm::Md5);
+        assert_eq!(checksum.value, "900150983cd24fb0d6963f7d28e17f72");
+        assert_eq!(metadata.size_bytes, 0);
+    }
+
+    #[test]
+    fn test_snapshot_metadata_same_file() {
+        let meta = SnapshotMetadata {
+            date: "20260109".to_string(),
+            size_gb: 53,
+            size_bytes: 56908316672,
+            checksum: Checksum::parse("900150983cd24fb0d6963f7d28e17f72").ok(),
+            download_url: "http://a/snapshot.tgz".to_string(),
+        };
+        let mirror = SnapshotMetadata {
+            download_url: "http://b/snapshot.tgz".to_string(),
+            ..meta.clone()
+        };
+        assert!(meta.same_file(&mirror));
+
+        let resized = SnapshotMetadata {
+            size_bytes: 1,
+            ..mirror.clone()
+        };
+        assert!(!meta.same_file(&resized));
+
+        let unchecked = SnapshotMetadata {
+            checksum: None,
+            ..mirror
+        };
+        assert!(!unchecked.same_file(&unchecked.clone()));
     }
 
     #[test]
@@ -80,7 +241,8 @@ mod tests {
         let meta1 = SnapshotMetadata {
             date: "20260109".to_string(),
             size_gb: 53,
-            md5: "test".to_string(),
+            size_bytes: 56908316672,
+            checksum: None,
             download_url: "http://test.com".to_string(),
         };
 
@@ -1,4 +1,7 @@
+pub mod archive;
 pub mod fs;
+pub mod hash;
 pub mod network;
+pub mod node_conf;
 pub mod permissions;
 pub mod ui;
@@ -0,0 +1,255 @@
+use crate::error::{Result, TronCtlError};
+use flate2::read::GzDecoder;
+use std::fs::File;
+use std::path::{Component, Path};
+use tar::Archive;
+use tracing::info;
+
+/// 安全解压本地 .tar.gz 文件，拒绝路径遍历与目标目录外的条目
+pub async fn extract_tgz(archive_path: &Path, dest_dir: &Path) -> Result<()> {
+    info!("开始解压: {:?}", archive_path);
+
+    // 规范化目标路径
+    let dest_dir_canonical = dest_dir
+        .canonicalize()
+        .map_err(|e| TronCtlError::Other(anyhow::anyhow!("无效的目标路径: {}", e)))?;
+
+    // 在独立线程中进行解压（阻塞操作）
+    let archive_path = archive_path.to_path_buf();
+    let extract_task = tokio::task::spawn_blocking(move || {
+        let file = File::open(&archive_path)?;
+        let decoder = GzDecoder::new(file);
+        let mut archive = Archive::new(decoder);
+
+        // 安全解压：验证每个文件的路径
+        for entry in archive.entries()? {
+            let mut entry = entry?;
+            let path = entry.path()?;
+
+            // 1. 检查路径是否包含 .. 组件
+            for component in path.components() {
+                if matches!(component, Component::ParentDir) {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::PermissionDenied,
+                        format!("检测到路径遍历攻击（包含 ..）: {:?}", path),
+                    ));
+                }
+            }
+
+            // 2. 检查是否为绝对路径
+            if path.is_absolute() {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::PermissionDenied,
+                    format!("拒绝解压绝对路径: {:?}", path),
+                ));
+            }
+
+            // 3. 构造完整路径并验证
+            let full_path = dest_dir_canonical.join(&path);
+
+            // 4. 验证解压路径确实在目标目录内
+            let path_to_check = if full_path.exists() {
+                full_path.canonicalize()?
+            } else {
+                // 确保父目录存在
+                if let Some(parent) = full_path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                // 对于不存在的文件，验证其父目录在目标目录内
+                if let Some(parent) = full_path.parent() {
+                    parent.canonicalize()?
+                } else {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::InvalidInput,
+                        "无效的文件路径",
+                    ));
+                }
+            };
+
+            // 确保路径在目标目录内
+            if !path_to_check.starts_with(&dest_dir_canonical) {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::PermissionDenied,
+                    format!("路径在目标目录外: {:?} -> {:?}", path, path_to_check),
+                ));
+            }
+
+            // 安全解压
+            entry.unpack(&full_path)?;
+        }
+
+        Ok::<_, std::io::Error>(())
+    });
+
+    extract_task
+        .await
+        .map_err(|e| TronCtlError::Other(anyhow::anyhow!("解压任务失败: {}", e)))??;
+
+    info!("解压完成: {:?}", dest_dir);
+    Ok(())
+}
+
+/// 检查文件是有效的 jar（zip）并包含指定的类文件
+pub fn verify_jar(path: &Path, class_entry: &str) -> Result<()> {
+    let mut jar = zip::ZipArchive::new(File::open(path)?)
+        .map_err(|e| TronCtlError::InvalidJar(format!("{:?} 不是有效的 zip 文件: {}", path, e)))?;
+
+    if jar.by_name(class_entry).is_err() {
+        return Err(TronCtlError::InvalidJar(format!(
+            "{:?} 中缺少 {}",
+            path, class_entry
+        )));
+    }
+
+    Ok(())
+}
+
+/// 读取 jar 中类文件的字节码版本，换算为编译目标的 Java 主版本（class 主版本号 - 44）
+pub fn class_java_major(path: &Path, class_entry: &str) -> Result<u32> {
+    let mut jar = zip::ZipArchive::new(File::open(path)?)
+        .map_err(|e| TronCtlError::InvalidJar(format!("{:?} 不是有效的 zip 文件: {}", path, e)))?;
+
+    let mut file = jar
+        .by_name(class_entry)
+        .map_err(|_| TronCtlError::InvalidJar(format!("{:?} 中缺少 {}", path, class_entry)))?;
+
+    // 类文件头：4 字节魔数 0xCAFEBABE、2 字节次版本号、2 字节主版本号
+    let mut header = [0u8; 8];
+    std::io::Read::read_exact(&mut file, &mut header)?;
+    let major = u16::from_be_bytes([header[6], header[7]]) as u32;
+    if header[..4] != [0xca, 0xfe, 0xba, 0xbe] || major < 45 {
+        return Err(TronCtlError::InvalidJar(format!(
+            "{:?} 中的 {} 不是有效的类文件",
+            path, class_entry
+        )));
+    }
+
+    Ok(major - 44)
+}
+
+/// 读取 jar 中的文本条目，不存在时返回 None
+pub fn read_jar_entry(path: &Path, entry: &str) -> Result<Option<String>> {
+    let mut jar = zip::ZipArchive::new(File::open(path)?)
+        .map_err(|e| TronCtlError::InvalidJar(format!("{:?} 不是有效的 zip 文件: {}", path, e)))?;
+
+    let Ok(mut file) = jar.by_name(entry) else {
+        return Ok(None);
+    };
+
+    let mut content = String::new();
+    std::io::Read::read_to_string(&mut file, &mut content)?;
+    Ok(Some(content))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::Compression;
+    use flate2::write::GzEncoder;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_extract_tgz() {
+        let temp_dir = TempDir::new().unwrap();
+        let archive_path = temp_dir.path().join("test.tar.gz");
+
+        let encoder = GzEncoder::new(File::create(&archive_path).unwrap(), Compression::fast());
+        let mut builder = tar::Builder::new(encoder);
+        let content = b"hello";
+        let mut header = tar::Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_mode(0o755);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, "jdk/bin/java", &content[..])
+            .unwrap();
+        builder.into_inner().unwrap().finish().unwrap();
+
+        let dest = temp_dir.path().join("out");
+        std::fs::create_dir(&dest).unwrap();
+        extract_tgz(&archive_path, &dest).await.unwrap();
+
+        assert_eq!(std::fs::read(dest.join("jdk/bin/java")).unwrap(), content);
+    }
+
+    fn write_jar(path: &Path, entry: &str) {
+        write_jar_with_class(path, entry, b"\xca\xfe\xba\xbe");
+    }
+
+    fn write_jar_with_class(path: &Path, entry: &str, class: &[u8]) {
+        let mut jar = zip::ZipWriter::new(File::create(path).unwrap());
+        jar.start_file(entry, zip::write::SimpleFileOptions::default())
+            .unwrap();
+        std::io::Write::write_all(&mut jar, class).unwrap();
+        jar.start_file("config.conf", zip::write::SimpleFileOptions::default())
+            .unwrap();
+        std::io::Write::write_all(&mut jar, b"net {\n  type = mainnet\n}\n").unwrap();
+        jar.finish().unwrap();
+    }
+
+    #[test]
+    fn test_read_jar_entry() {
+        let temp_dir = TempDir::new().unwrap();
+        let jar = temp_dir.path().join("FullNode.jar");
+        write_jar(&jar, "org/tron/program/FullNode.class");
+
+        let content = read_jar_entry(&jar, "config.conf").unwrap().unwrap();
+        assert!(content.contains("mainnet"));
+        assert_eq!(read_jar_entry(&jar, "missing.conf").unwrap(), None);
+    }
+
+    #[test]
+    fn test_class_java_major() {
+        let temp_dir = TempDir::new().unwrap();
+        let class = "org/tron/program/FullNode.class";
+
+        // 主版本号 52 为 Java 8，61 为 Java 17
+        let java8 = temp_dir.path().join("Java8.jar");
+        write_jar_with_class(&java8, class, b"\xca\xfe\xba\xbe\x00\x00\x00\x34");
+        assert_eq!(class_java_major(&java8, class).unwrap(), 8);
+
+        let java17 = temp_dir.path().join("Java17.jar");
+        write_jar_with_class(&java17, class, b"\xca\xfe\xba\xbe\x00\x00\x00\x3d");
+        assert_eq!(class_java_major(&java17, class).unwrap(), 17);
+
+        let not_class = temp_dir.path().join("NotClass.jar");
+        write_jar_with_class(&not_class, class, b"not a class file");
+        assert!(matches!(
+            class_java_major(&not_class, class),
+            Err(TronCtlError::InvalidJar(_))
+        ));
+
+        let missing = temp_dir.path().join("Missing.jar");
+        write_jar(&missing, "com/example/Main.class");
+        assert!(matches!(
+            class_java_major(&missing, class),
+            Err(TronCtlError::InvalidJar(_))
+        ));
+    }
+
+    #[test]
+    fn test_verify_jar() {
+        let temp_dir = TempDir::new().unwrap();
+        let class = "org/tron/program/FullNode.class";
+
+        let valid = temp_dir.path().join("FullNode.jar");
+        write_jar(&valid, class);
+        assert!(verify_jar(&valid, class).is_ok());
+
+        let other = temp_dir.path().join("Other.jar");
+        write_jar(&other, "com/example/Main.class");
+        assert!(matches!(
+            verify_jar(&other, class),
+            Err(TronCtlError::InvalidJar(_))
+        ));
+
+        // 截断的下载不是有效的 zip
+        let truncated = temp_dir.path().join("Truncated.jar");
+        let bytes = std::fs::read(&valid).unwrap();
+        std::fs::write(&truncated, &bytes[..bytes.len() / 2]).unwrap();
+        assert!(matches!(
+            verify_jar(&truncated, class),
+            Err(TronCtlError::InvalidJar(_))
+        ));
+    }
+}
@@ -1,7 +1,16 @@
 use crate::error::Result;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use tokio::fs;
 
+/// /proc/self/mounts 中的一条挂载记录
+#[derive(Debug, Clone, PartialEq)]
+pub struct MountInfo {
+    pub device: String,
+    pub mount_point: PathBuf,
+    pub fs_type: String,
+    pub options: Vec<String>,
+}
+
 pub async fn ensure_dir_exists(path: &Path) -> Result<()> {
     if !path.exists() {
         fs::create_dir_all(path).await?;
@@ -17,19 +26,83 @@ pub async fn ensure_parent_exists(file_path: &Path) -> Result<()> {
     Ok(())
 }
 
+/// 路径所在文件系统的可用空间（GB）
 pub fn get_disk_free_space(path: &Path) -> Result<u64> {
-    use sysinfo::Disks;
+    Ok(get_disk_free_bytes(path)? / (1024 * 1024 * 1024))
+}
+
+/// 路径所在文件系统的可用空间（字节），路径不存在时查询最近的已存在祖先目录
+pub fn get_disk_free_bytes(path: &Path) -> Result<u64> {
+    let stat =
+        nix::sys::statvfs::statvfs(&existing_ancestor(path)).map_err(std::io::Error::from)?;
+    Ok(stat.blocks_available() * stat.fragment_size())
+}
+
+/// 原子地将 `link` 指向 `target`：先创建临时链接再 rename 覆盖
+pub fn atomic_symlink(target: &Path, link: &Path) -> Result<()> {
+    let mut tmp = link.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+
+    if tmp.symlink_metadata().is_ok() {
+        std::fs::remove_file(&tmp)?;
+    }
+    std::os::unix::fs::symlink(target, &tmp)?;
+    std::fs::rename(&tmp, link)?;
+    Ok(())
+}
 
-    let disks = Disks::new_with_refreshed_list();
+/// 返回路径自身或其最近的已存在祖先目录（规范化后）
+pub fn existing_ancestor(path: &Path) -> PathBuf {
+    path.ancestors()
+        .find_map(|p| p.canonicalize().ok())
+        .unwrap_or_else(|| PathBuf::from("/"))
+}
 
-    for disk in &disks {
-        let mount_point = disk.mount_point();
-        if path.starts_with(mount_point) {
-            return Ok(disk.available_space() / (1024 * 1024 * 1024));
+/// 查找路径所在的挂载点（最长前缀匹配）
+pub fn find_mount(path: &Path) -> Result<Option<MountInfo>> {
+    let mounts = std::fs::read_to_string("/proc/self/mounts")?;
+    Ok(find_mount_in(&mounts, &existing_ancestor(path)))
+}
+
+fn find_mount_in(mounts: &str, path: &Path) -> Option<MountInfo> {
+    mounts
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            Some(MountInfo {
+                device: unescape_mount_field(fields.next()?),
+                mount_point: PathBuf::from(unescape_mount_field(fields.next()?)),
+                fs_type: fields.next()?.to_string(),
+                options: fields.next()?.split(',').map(str::to_string).collect(),
+            })
+        })
+        .filter(|m| path.starts_with(&m.mount_point))
+        // 同一挂载点被重复挂载时以最后一条为准
+        .max_by_key(|m| m.mount_point.components().count())
+}
+
+/// 还原 /proc/self/mounts 中的八进制转义（如空格为 `\040`）
+fn unescape_mount_field(field: &str) -> String {
+    let mut out = String::with_capacity(field.len());
+    let mut chars = field.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            let code: String = chars.by_ref().take(3).collect();
+            match u8::from_str_radix(&code, 8) {
+                Ok(b) => out.push(b as char),
+                Err(_) => {
+                    out.push(c);
+                    out.push_str(&code);
+                }
+            }
+        } else {
+            out.push(c);
         }
     }
 
-    Ok(0)
+    out
 }
 
 #[cfg(test)]
@@ -90,14 +163,69 @@ mod tests {
     #[test]
     fn test_get_disk_free_space() {
         let path = Path::new("/tmp");
-        let space = get_disk_free_space(path).unwrap();
-        assert!(space >= 0);
+        assert!(get_disk_free_space(path).is_ok());
+    }
+
+    #[test]
+    fn test_find_mount_longest_prefix() {
+        let mounts = "/dev/vda1 / ext4 rw,relatime 0 0\n\
+                      /dev/nvme0n1 /var/lib/tronctl xfs rw,noatime 0 0\n\
+                      tmpfs /var/lib/tmp tmpfs rw 0 0\n";
+
+        let mount = find_mount_in(mounts, Path::new("/var/lib/tronctl/data")).unwrap();
+        assert_eq!(mount.device, "/dev/nvme0n1");
+        assert_eq!(mount.fs_type, "xfs");
+        assert!(mount.options.contains(&"noatime".to_string()));
+
+        let root = find_mount_in(mounts, Path::new("/var/lib/other")).unwrap();
+        assert_eq!(root.mount_point, PathBuf::from("/"));
+    }
+
+    #[test]
+    fn test_find_mount_escaped_path() {
+        let mounts = "/dev/vda1 / ext4 rw 0 0\n/dev/sdb1 /mnt/my\\040disk ext4 rw 0 0\n";
+        let mount = find_mount_in(mounts, Path::new("/mnt/my disk/data")).unwrap();
+        assert_eq!(mount.device, "/dev/sdb1");
+    }
+
+    #[test]
+    fn test_existing_ancestor() {
+        let temp_dir = TempDir::new().unwrap();
+        let missing = temp_dir.path().join("a/b/c");
+        assert_eq!(
+            existing_ancestor(&missing),
+            temp_dir.path().canonicalize().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_get_disk_free_bytes_missing_path() {
+        let temp_dir = TempDir::new().unwrap();
+        let missing = temp_dir.path().join("not/created/yet");
+        assert_eq!(
+            get_disk_free_bytes(&missing).unwrap() / (1024 * 1024 * 1024),
+            get_disk_free_space(temp_dir.path()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_atomic_symlink_replaces_link() {
+        let temp_dir = TempDir::new().unwrap();
+        let link = temp_dir.path().join("FullNode.jar");
+        std::fs::write(temp_dir.path().join("a.jar"), b"a").unwrap();
+        std::fs::write(temp_dir.path().join("b.jar"), b"b").unwrap();
+
+        atomic_symlink(Path::new("a.jar"), &link).unwrap();
+        assert_eq!(std::fs::read(&link).unwrap(), b"a");
+
+        atomic_symlink(Path::new("b.jar"), &link).unwrap();
+        assert_eq!(std::fs::read_link(&link).unwrap(), PathBuf::from("b.jar"));
+        assert_eq!(std::fs::read(&link).unwrap(), b"b");
     }
 
     #[test]
     fn test_get_disk_free_space_root() {
         let path = Path::new("/");
-        let space = get_disk_free_space(path).unwrap();
-        assert!(space >= 0);
+        assert!(get_disk_free_space(path).is_ok());
     }
 }
@@ -0,0 +1,186 @@
+use crate::error::Result;
+use crate::models::checksum::{Checksum, ChecksumAlgorithm};
+use aws_lc_rs::digest::{Context, SHA1_FOR_LEGACY_USE_ONLY, SHA256, SHA512};
+use std::io::Read;
+use std::path::Path;
+
+/// 增量计算指定算法的摘要
+pub enum Hasher {
+    Md5(md5::Context),
+    Digest(Context),
+}
+
+impl Hasher {
+    pub fn new(algorithm: ChecksumAlgorithm) -> Self {
+        match algorithm {
+            ChecksumAlgorithm::Md5 => Self::Md5(md5::Context::new()),
+            ChecksumAlgorithm::Sha1 => Self::Digest(Context::new(&SHA1_FOR_LEGACY_USE_ONLY)),
+            ChecksumAlgorithm::Sha256 => Self::Digest(Context::new(&SHA256)),
+            ChecksumAlgorithm::Sha512 => Self::Digest(Context::new(&SHA512)),
+        }
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        match self {
+            Self::Md5(context) => context.consume(data),
+            Self::Digest(context) => context.update(data),
+        }
+    }
+
+    /// 小写十六进制摘要
+    pub fn finish(self) -> String {
+        match self {
+            Self::Md5(context) => format!("{:x}", context.finalize()),
+            Self::Digest(context) => to_hex(context.finish().as_ref()),
+        }
+    }
+}
+
+/// 计算文件指定算法的摘要（小写十六进制）
+pub fn checksum_file(path: &Path, algorithm: ChecksumAlgorithm) -> Result<String> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Hasher::new(algorithm);
+    let mut buffer = vec![0u8; 64 * 1024];
+
+    loop {
+        let n = file.read(&mut buffer)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buffer[..n]);
+    }
+
+    Ok(hasher.finish())
+}
+
+/// 校验文件摘要，不一致时返回带算法名称的错误
+pub fn verify_file(path: &Path, expected: &Checksum) -> Result<()> {
+    expected.verify(&checksum_file(path, expected.algorithm)?)
+}
+
+/// 计算文件的 SHA-256（小写十六进制）
+pub fn sha256_file(path: &Path) -> Result<String> {
+    checksum_file(path, ChecksumAlgorithm::Sha256)
+}
+
+/// 从 `<hash>  <filename>` 格式的校验文件中取出哈希值
+pub fn parse_checksum_file(content: &str) -> Option<String> {
+    let hash = content.split_whitespace().next()?;
+    hash.chars()
+        .all(|c| c.is_ascii_hexdigit())
+        .then(|| hash.to_lowercase())
+}
+
+/// 从多行校验文件（如 `SHA256SUMS`）或发布说明中找出 `name` 对应的 SHA-256
+pub fn find_sha256(content: &str, name: &str) -> Option<String> {
+    content
+        .lines()
+        .filter(|line| line.contains(name))
+        .find_map(sha256_token)
+}
+
+/// 行中第一个 64 位十六进制串
+pub fn sha256_token(line: &str) -> Option<String> {
+    line.split(|c: char| !c.is_ascii_alphanumeric())
+        .find(|token| token.len() == 64 && token.chars().all(|c| c.is_ascii_hexdigit()))
+        .map(str::to_lowercase)
+}
+
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::TronCtlError;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_sha256_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("data");
+        std::fs::write(&path, b"abc").unwrap();
+
+        assert_eq!(
+            sha256_file(&path).unwrap(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn test_checksum_file_algorithms() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("data");
+        std::fs::write(&path, b"abc").unwrap();
+
+        let expected = [
+            (ChecksumAlgorithm::Md5, "900150983cd24fb0d6963f7d28e17f72"),
+            (
+                ChecksumAlgorithm::Sha1,
+                "a9993e364706816aba3e25717850c26c9cd0d89d",
+            ),
+            (
+                ChecksumAlgorithm::Sha512,
+                "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f",
+            ),
+        ];
+        for (algorithm, digest) in expected {
+            assert_eq!(checksum_file(&path, algorithm).unwrap(), digest);
+            let checksum = Checksum::new(algorithm, digest).unwrap();
+            assert!(verify_file(&path, &checksum).is_ok());
+        }
+
+        let wrong = Checksum::new(ChecksumAlgorithm::Sha1, &"0".repeat(40)).unwrap();
+        match verify_file(&path, &wrong) {
+            Err(TronCtlError::ChecksumMismatch { algorithm, .. }) => assert_eq!(algorithm, "SHA-1"),
+            other => panic!("unexpected: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_verify_file_mismatch() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("data");
+        std::fs::write(&path, b"abc").unwrap();
+
+        let expected =
+            Checksum::parse("BA7816BF8F01CFEA414140DE5DAE2223B00361A396177A9CB410FF61F20015AD")
+                .unwrap();
+        assert!(verify_file(&path, &expected).is_ok());
+
+        let other = Checksum::parse(&"0".repeat(64)).unwrap();
+        assert!(matches!(
+            verify_file(&path, &other),
+            Err(TronCtlError::ChecksumMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_checksum_file() {
+        assert_eq!(
+            parse_checksum_file("ABCdef12  OpenJDK8U-jdk_x64_linux.tar.gz\n").as_deref(),
+            Some("abcdef12")
+        );
+        assert_eq!(parse_checksum_file("<html>404</html>"), None);
+        assert_eq!(parse_checksum_file(""), None);
+    }
+
+    #[test]
+    fn test_find_sha256() {
+        let sum = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+        let sums = format!(
+            "{}  Toolkit.jar\n{}  FullNode.jar\n",
+            "0".repeat(64),
+            sum.to_uppercase()
+        );
+        assert_eq!(find_sha256(&sums, "FullNode.jar").as_deref(), Some(sum));
+
+        let notes = format!(
+            "| File | SHA256 |\n|---|---|\n| FullNode.jar | `{}` |\n",
+            sum
+        );
+        assert_eq!(find_sha256(&notes, "FullNode.jar").as_deref(), Some(sum));
+        assert_eq!(find_sha256(&notes, "Toolkit.jar"), None);
+    }
+}
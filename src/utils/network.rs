@@ -1,6 +1,23 @@
+use crate::error::{Result, TronCtlError};
 use reqwest::Client;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use std::collections::BTreeMap;
 use std::time::{Duration, Instant};
 
+/// 将配置中的 HTTP 头转换为 HeaderMap，名称或值无效时返回配置错误
+pub fn header_map(headers: &BTreeMap<String, String>) -> Result<HeaderMap> {
+    let mut map = HeaderMap::new();
+    for (name, value) in headers {
+        let name = HeaderName::from_bytes(name.as_bytes())
+            .map_err(|_| TronCtlError::ConfigError(format!("无效的 HTTP 头名称: {}", name)))?;
+        let mut value = HeaderValue::from_str(value)
+            .map_err(|_| TronCtlError::ConfigError(format!("HTTP 头 {} 的值无效", name)))?;
+        value.set_sensitive(true);
+        map.insert(name, value);
+    }
+    Ok(map)
+}
+
 pub async fn measure_latency(client: &Client, url: &str, timeout: Duration) -> Option<Duration> {
     let start = Instant::now();
 
@@ -19,6 +36,69 @@ pub async fn check_url_exists(client: &Client, url: &str) -> bool {
         .unwrap_or(false)
 }
 
+/// 通过 HEAD 请求读取资源大小（Content-Length），不可用时返回 None
+pub async fn content_length(client: &Client, url: &str) -> Option<u64> {
+    let resp = client.head(url).send().await.ok()?;
+    if !resp.status().is_success() {
+        return None;
+    }
+
+    head_content_length(&resp)
+}
+
+/// 读取 HEAD 响应的 Content-Length
+///
+/// HEAD 响应没有响应体，`Response::content_length` 返回的是响应体大小，需直接读取头部
+pub fn head_content_length(resp: &reqwest::Response) -> Option<u64> {
+    resp.headers()
+        .get(reqwest::header::CONTENT_LENGTH)?
+        .to_str()
+        .ok()?
+        .parse()
+        .ok()
+}
+
+/// 用 Range 请求读取资源开头的 `bytes` 字节，按实际读取量计算吞吐（字节/秒）
+///
+/// 超过 `timeout` 时按已读取的数据计算，未读到任何数据时返回 None
+pub async fn measure_throughput(
+    client: &Client,
+    url: &str,
+    bytes: u64,
+    timeout: Duration,
+) -> Option<u64> {
+    use futures::StreamExt;
+
+    let start = Instant::now();
+    let deadline = start + timeout;
+
+    let resp = tokio::time::timeout(
+        timeout,
+        client
+            .get(url)
+            .header(reqwest::header::RANGE, format!("bytes=0-{}", bytes - 1))
+            .send(),
+    )
+    .await
+    .ok()?
+    .ok()?;
+    if !resp.status().is_success() {
+        return None;
+    }
+
+    let mut stream = resp.bytes_stream();
+    let mut received = 0u64;
+    while received < bytes {
+        match tokio::time::timeout_at(deadline.into(), stream.next()).await {
+            Ok(Some(Ok(chunk))) => received += chunk.len() as u64,
+            _ => break,
+        }
+    }
+
+    let elapsed = start.elapsed().as_secs_f64().max(0.001);
+    (received > 0).then(|| (received as f64 / elapsed) as u64)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -100,6 +180,69 @@ mod tests {
         assert!(!exists);
     }
 
+    #[tokio::test]
+    async fn test_content_length() {
+        let mut server = mockito::Server::new_async().await;
+        let _ok = server
+            .mock("HEAD", "/snapshot.tgz")
+            .with_status(200)
+            .with_header("content-length", "56908316672")
+            .create_async()
+            .await;
+        let _missing = server
+            .mock("HEAD", "/missing.tgz")
+            .with_status(404)
+            .create_async()
+            .await;
+
+        let client = Client::new();
+        let size = content_length(&client, &format!("{}/snapshot.tgz", server.url())).await;
+        assert_eq!(size, Some(56908316672));
+
+        let size = content_length(&client, &format!("{}/missing.tgz", server.url())).await;
+        assert_eq!(size, None);
+    }
+
+    #[tokio::test]
+    async fn test_measure_throughput() {
+        let mut server = mockito::Server::new_async().await;
+        let ranged = server
+            .mock("GET", "/snapshot.tgz")
+            .match_header("range", "bytes=0-1023")
+            .with_status(206)
+            .with_body(vec![0u8; 1024])
+            .create_async()
+            .await;
+        let _missing = server
+            .mock("GET", "/missing.tgz")
+            .with_status(404)
+            .create_async()
+            .await;
+
+        let client = Client::new();
+        let url = format!("{}/snapshot.tgz", server.url());
+        let throughput = measure_throughput(&client, &url, 1024, Duration::from_secs(5)).await;
+        ranged.assert_async().await;
+        assert!(throughput.unwrap() > 0);
+
+        let url = format!("{}/missing.tgz", server.url());
+        let throughput = measure_throughput(&client, &url, 1024, Duration::from_secs(5)).await;
+        assert_eq!(throughput, None);
+    }
+
+    #[test]
+    fn test_header_map() {
+        let headers = BTreeMap::from([("Authorization".to_string(), "Bearer t".to_string())]);
+        let map = header_map(&headers).unwrap();
+        assert_eq!(map["authorization"], "Bearer t");
+
+        let invalid = BTreeMap::from([("bad header".to_string(), "v".to_string())]);
+        assert!(matches!(
+            header_map(&invalid),
+            Err(TronCtlError::ConfigError(_))
+        ));
+    }
+
     #[tokio::test]
     async fn test_check_url_exists_network_error() {
         let client = Client::new();
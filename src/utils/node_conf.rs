@@ -0,0 +1,182 @@
+//! java-tron 节点配置 (HOCON) 的轻量读取，只支持按完整路径查找标量值
+
+/// 按完整路径（如 `node.listen.port`）查找标量值，找不到返回 None
+pub fn get_value(content: &str, key_path: &str) -> Option<String> {
+    let mut stack: Vec<String> = Vec::new();
+    let mut array_depth = 0i32;
+    let mut found = None;
+
+    for raw_line in content.lines() {
+        let line = strip_comment(raw_line);
+        let line = line.trim().trim_end_matches(',').trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        // 数组内容（可能跨多行、包含对象）整体跳过
+        if array_depth > 0 {
+            array_depth += bracket_delta(line);
+            continue;
+        }
+
+        if line.starts_with('}') {
+            stack.pop();
+            continue;
+        }
+
+        let (key, value) = match line.find(['=', ':', '{']) {
+            Some(pos) if line.as_bytes()[pos] == b'{' => (line[..pos].trim(), "{"),
+            Some(pos) => (line[..pos].trim(), line[pos + 1..].trim()),
+            None => continue,
+        };
+        let key = key.trim_matches('"');
+
+        if value == "{" {
+            stack.push(key.to_string());
+            continue;
+        }
+
+        if value.starts_with('[') {
+            array_depth = bracket_delta(value);
+            continue;
+        }
+
+        if value.starts_with('{') {
+            // 单行对象，不进入作用域
+            continue;
+        }
+
+        let full_path = if stack.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}.{}", stack.join("."), key)
+        };
+
+        // HOCON 中后出现的同名键覆盖前面的值
+        if full_path == key_path {
+            found = Some(value.trim_matches('"').to_string());
+        }
+    }
+
+    found
+}
+
+/// 去掉 `#` 与 `//` 注释（忽略引号内的内容）
+fn strip_comment(line: &str) -> &str {
+    let mut in_quotes = false;
+    let bytes = line.as_bytes();
+
+    for i in 0..bytes.len() {
+        match bytes[i] {
+            b'"' => in_quotes = !in_quotes,
+            b'#' if !in_quotes => return &line[..i],
+            b'/' if !in_quotes && bytes.get(i + 1) == Some(&b'/') => return &line[..i],
+            _ => {}
+        }
+    }
+
+    line
+}
+
+fn bracket_delta(line: &str) -> i32 {
+    line.chars().fold(0, |acc, c| match c {
+        '[' | '{' => acc + 1,
+        ']' | '}' => acc - 1,
+        _ => acc,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r#"
+net {
+  type = mainnet
+}
+
+node {
+  # trust node for solidity node
+  trustNode = "127.0.0.1:50051"
+
+  listen.port = 18888
+
+  active = [
+    # "ip:port",
+  ]
+
+  http {
+    fullNodeEnable = true
+    fullNodePort = 8090   // 注释
+    solidityPort = 8091
+  }
+
+  rpc {
+    port = 50051
+  }
+
+  jsonrpc {
+    httpFullNodeEnable = false
+    httpFullNodePort = 8545
+  }
+}
+
+genesis.block {
+  assets = [
+    {
+      accountName = "Zion"
+      address = "TLLM21wteSPs4hKjbxgmH1L6poyMjeTbHm"
+    }
+  ]
+}
+"#;
+
+    #[test]
+    fn test_get_value_dotted_key() {
+        assert_eq!(
+            get_value(SAMPLE, "node.listen.port").as_deref(),
+            Some("18888")
+        );
+    }
+
+    #[test]
+    fn test_get_value_nested_block() {
+        assert_eq!(
+            get_value(SAMPLE, "node.http.fullNodePort").as_deref(),
+            Some("8090")
+        );
+        assert_eq!(get_value(SAMPLE, "node.rpc.port").as_deref(), Some("50051"));
+        assert_eq!(
+            get_value(SAMPLE, "node.jsonrpc.httpFullNodeEnable").as_deref(),
+            Some("false")
+        );
+    }
+
+    #[test]
+    fn test_get_value_quoted() {
+        assert_eq!(
+            get_value(SAMPLE, "node.trustNode").as_deref(),
+            Some("127.0.0.1:50051")
+        );
+    }
+
+    #[test]
+    fn test_get_value_skips_arrays() {
+        assert_eq!(get_value(SAMPLE, "genesis.block.assets.accountName"), None);
+        assert_eq!(get_value(SAMPLE, "net.type").as_deref(), Some("mainnet"));
+    }
+
+    #[test]
+    fn test_get_value_missing() {
+        assert_eq!(get_value(SAMPLE, "node.missing"), None);
+        assert_eq!(get_value("", "node.listen.port"), None);
+    }
+
+    #[test]
+    fn test_strip_comment_keeps_quoted() {
+        assert_eq!(
+            strip_comment(r#"url = "http://a#b" # c"#),
+            r#"url = "http://a#b" "#
+        );
+    }
+}